@@ -13,10 +13,20 @@ export function optimize_settlements(_debts: unknown): unknown {
     original_count: 0,
     optimized_count: 0,
     payments: [],
-    savings_percent: 0,
+    metrics: {
+      pairs_before: 0,
+      pairs_after: 0,
+      cents_before: 0,
+      cents_after: 0,
+      transactions_saved: 0,
+      savings_bps: 0,
+    },
+    objective: 'min_transactions',
+    objective_value: 0,
+    payment_counts: [],
   };
 }
 
-export function validate_debts(_debts: unknown): boolean {
-  return true;
+export function validate_debts(_debts: unknown): unknown {
+  return null;
 }
@@ -141,7 +141,14 @@ describe('/api/trips/[tripId]/balances/settlements route', () => {
         original_count: 0,
         optimized_count: 0,
         payments: [],
-        savings_percent: 0,
+        metrics: {
+          pairs_before: 0,
+          pairs_after: 0,
+          cents_before: 0,
+          cents_after: 0,
+          transactions_saved: 0,
+          savings_bps: 0,
+        },
       });
 
       const request = new NextRequest(
@@ -255,7 +262,14 @@ describe('/api/trips/[tripId]/balances/settlements route', () => {
             reason: 'Settlement',
           },
         ],
-        savings_percent: 0,
+        metrics: {
+          pairs_before: 0,
+          pairs_after: 0,
+          cents_before: 0,
+          cents_after: 0,
+          transactions_saved: 0,
+          savings_bps: 0,
+        },
       });
 
       const request = new NextRequest(
@@ -349,7 +363,14 @@ describe('/api/trips/[tripId]/balances/settlements route', () => {
             reason: 'Settlement',
           },
         ],
-        savings_percent: 0,
+        metrics: {
+          pairs_before: 0,
+          pairs_after: 0,
+          cents_before: 0,
+          cents_after: 0,
+          transactions_saved: 0,
+          savings_bps: 0,
+        },
       });
 
       const request = new NextRequest(
@@ -583,7 +604,14 @@ describe('/api/trips/[tripId]/balances/settlements route', () => {
             reason: 'Settlement',
           },
         ],
-        savings_percent: 0,
+        metrics: {
+          pairs_before: 0,
+          pairs_after: 0,
+          cents_before: 0,
+          cents_after: 0,
+          transactions_saved: 0,
+          savings_bps: 0,
+        },
       });
 
       const mockPaymentId = new ObjectId();
@@ -670,7 +698,14 @@ describe('/api/trips/[tripId]/balances/settlements route', () => {
         original_count: 0,
         optimized_count: 0,
         payments: [],
-        savings_percent: 0,
+        metrics: {
+          pairs_before: 0,
+          pairs_after: 0,
+          cents_before: 0,
+          cents_after: 0,
+          transactions_saved: 0,
+          savings_bps: 0,
+        },
       });
 
       const request = new NextRequest(
@@ -764,7 +799,14 @@ describe('/api/trips/[tripId]/balances/settlements route', () => {
             reason: 'Settlement',
           },
         ],
-        savings_percent: 0,
+        metrics: {
+          pairs_before: 0,
+          pairs_after: 0,
+          cents_before: 0,
+          cents_after: 0,
+          transactions_saved: 0,
+          savings_bps: 0,
+        },
       });
 
       vi.mocked(paymentOperations.createPayment).mockRejectedValue(
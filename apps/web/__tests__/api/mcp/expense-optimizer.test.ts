@@ -64,7 +64,14 @@ describe('expense-optimizer API route', () => {
             reason: 'Settlement',
           },
         ],
-        savings_percent: 50.0,
+        metrics: {
+          pairs_before: 2,
+          pairs_after: 1,
+          cents_before: 20000,
+          cents_after: 10000,
+          transactions_saved: 1,
+          savings_bps: 5000,
+        },
       });
 
       const request = new NextRequest(
@@ -97,7 +104,7 @@ describe('expense-optimizer API route', () => {
       expect(data.success).toBe(true);
       expect(data.data.original_count).toBe(2);
       expect(data.data.optimized_count).toBe(1);
-      expect(data.data.savings_percent).toBe(50.0);
+      expect(data.data.metrics.savings_bps).toBe(5000);
       expect(data.data.payments).toHaveLength(1);
     });
 
@@ -106,7 +113,14 @@ describe('expense-optimizer API route', () => {
         original_count: 0,
         optimized_count: 0,
         payments: [],
-        savings_percent: 0,
+        metrics: {
+          pairs_before: 0,
+          pairs_after: 0,
+          cents_before: 0,
+          cents_after: 0,
+          transactions_saved: 0,
+          savings_bps: 0,
+        },
       });
 
       const request = new NextRequest(
@@ -160,7 +174,14 @@ describe('expense-optimizer API route', () => {
         original_count: 0,
         optimized_count: 0,
         payments: [],
-        savings_percent: 0,
+        metrics: {
+          pairs_before: 0,
+          pairs_after: 0,
+          cents_before: 0,
+          cents_after: 0,
+          transactions_saved: 0,
+          savings_bps: 0,
+        },
       });
 
       const request = new NextRequest(
@@ -51,7 +51,8 @@ interface SettlementPlan {
   settlements: Settlement[];
   original_count: number;
   optimized_count: number;
-  savings_percent: number;
+  /** Savings in basis points (1/100 of a percent) */
+  savings_bps: number;
 }
 
 /**
@@ -224,7 +225,7 @@ export async function GET(
             settlements: [],
             original_count: 0,
             optimized_count: 0,
-            savings_percent: 0,
+            savings_bps: 0,
           },
         },
         { status: 200 }
@@ -259,7 +260,7 @@ export async function GET(
           settlements,
           original_count: result.original_count,
           optimized_count: result.optimized_count,
-          savings_percent: result.savings_percent,
+          savings_bps: result.metrics.savings_bps,
         },
       },
       { status: 200 }
@@ -77,7 +77,7 @@ beforeEach(() => {
             settlements: mockSettlements,
             original_count: 2,
             optimized_count: 2,
-            savings_percent: 0,
+            savings_bps: 0,
           },
         }),
       });
@@ -59,7 +59,7 @@ interface SettlementData {
   settlements: Settlement[];
   original_count: number;
   optimized_count: number;
-  savings_percent: number;
+  savings_bps: number;
 }
 
 const EXPENSE_CATEGORIES = [
@@ -431,11 +431,11 @@ export default function FinancesPage() {
                 <CardContent>
                   {settlements?.settlements && settlements.settlements.length > 0 ? (
                     <>
-                      {settlements.savings_percent > 0 && (
+                      {settlements.savings_bps > 0 && (
                         <div className="settlements-optimization-notice" data-testid="optimization-notice">
                           <span>
                             Optimized from {settlements.original_count} to {settlements.optimized_count} transactions
-                            ({settlements.savings_percent.toFixed(0)}% fewer)
+                            ({Math.round(settlements.savings_bps / 100)}% fewer)
                           </span>
                         </div>
                       )}
@@ -7,7 +7,12 @@
  * This module is loaded eagerly as it's critical for the Finances page.
  */
 
-import type { Debt, SimplificationResult, LoadingState } from './types';
+import type {
+  Debt,
+  DebtValidationError,
+  SimplificationResult,
+  LoadingState,
+} from './types';
 
 type ExpenseOptimizerWasm = typeof import('expense-optimizer');
 
@@ -85,20 +90,28 @@ export async function optimizeSettlements(
 }
 
 /**
- * Validate that all debts have positive amounts.
+ * Validate debts before simplification.
  *
  * @param debts - Array of debt relationships
- * @returns true if all debts are valid
+ * @returns null if all debts are valid, otherwise the first offending debt
  */
-export async function validateDebts(debts: Debt[]): Promise<boolean> {
+export async function validateDebts(
+  debts: Debt[]
+): Promise<DebtValidationError | null> {
   await load();
 
   if (!wasmModule) {
     // Fallback validation if WASM not available
-    return debts.every((d) => d.amount_cents > 0);
+    const index = debts.findIndex((d) => d.amount_cents <= 0);
+    if (index === -1) return null;
+    return {
+      type: 'non_positive_amount',
+      index,
+      amount_cents: debts[index].amount_cents,
+    };
   }
 
-  return wasmModule.validate_debts(debts);
+  return wasmModule.validate_debts(debts) as DebtValidationError | null;
 }
 
 /**
@@ -26,14 +26,52 @@ export interface Payment {
   reason: string;
 }
 
+/** Exact before/after measurements of a settlement plan */
+export interface SettlementMetrics {
+  pairs_before: number;
+  pairs_after: number;
+  cents_before: number;
+  cents_after: number;
+  transactions_saved: number;
+  /** Savings in basis points (1/100 of a percent) */
+  savings_bps: number;
+}
+
+/** The quantity a settlement plan is optimized for */
+export type Objective =
+  | 'min_transactions'
+  | 'min_total_transferred'
+  | 'min_max_payment'
+  | { max_payments_per_person: { cap: number } };
+
+/** How many payments one person sends and receives in a plan */
+export interface PaymentCount {
+  person: string;
+  sent: number;
+  received: number;
+}
+
 /** Result of debt simplification algorithm */
 export interface SimplificationResult {
   original_count: number;
   optimized_count: number;
   payments: Payment[];
-  savings_percent: number;
+  metrics: SettlementMetrics;
+  objective: Objective;
+  /** Achieved value of the objective (a count or an amount in cents) */
+  objective_value: number;
+  /** Payments each person sends and receives, sorted by person */
+  payment_counts: PaymentCount[];
 }
 
+/** Why a list of debts was rejected, pointing at the first offending debt */
+export type DebtValidationError =
+  | { type: 'non_positive_amount'; index: number; amount_cents: number }
+  | { type: 'self_debt'; index: number; person: string }
+  | { type: 'empty_debtor'; index: number }
+  | { type: 'empty_creditor'; index: number }
+  | { type: 'overflow'; index: number };
+
 // ============================================================================
 // Finance Core Types
 // ============================================================================
@@ -162,12 +200,24 @@ export interface PaymentLinkResult {
 /** MCP request structure */
 export interface McpRequest {
   method: string;
-  params: string;
+  params: unknown;
 }
 
-/** MCP response structure */
-export interface McpResponse {
-  success: boolean;
-  result: string | null;
-  error: string | null;
+/** What kind of failure an MCP error reports */
+export type McpErrorCode =
+  | 'unknown_method'
+  | 'invalid_params'
+  | 'validation'
+  | 'tool_failed'
+  | 'internal';
+
+/** Why an MCP request failed */
+export interface McpError {
+  code: McpErrorCode;
+  message: string;
+  /** JSON pointer to the offending parameter, e.g. "/debts/1/amount_cents" */
+  pointer?: string;
 }
+
+/** MCP response structure: the tool's output or an error */
+export type McpResponse = { ok: string } | { err: McpError };
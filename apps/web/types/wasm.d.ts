@@ -3,7 +3,7 @@
 declare module 'expense-optimizer' {
   export function init(): void;
   export function optimize_settlements(debts: unknown): unknown;
-  export function validate_debts(debts: unknown): boolean;
+  export function validate_debts(debts: unknown): unknown;
   export default function init_wasm(): Promise<void>;
 }
 
@@ -0,0 +1,91 @@
+//! Benchmarks for debt simplification on large groups.
+//!
+//! Run with `cargo bench -p expense-optimizer`. The largest case, 50,000
+//! debts among 500 people, models a company offsite and should stay well
+//! under 100ms with the default objective.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use expense_optimizer::{Debt, Objective, calculate_net_balances, simplify_debts_with_objective};
+
+/// Generate `count` debts among `people` participants.
+///
+/// Uses a fixed linear congruential generator so every run benchmarks the
+/// same input.
+fn generate_debts(people: usize, count: usize) -> Vec<Debt> {
+    let mut state: u64 = 0x5eed;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        state >> 33
+    };
+
+    (0..count)
+        .map(|i| {
+            let debtor = next() as usize % people;
+            let creditor = (debtor + 1 + next() as usize % (people - 1)) % people;
+            Debt {
+                debtor: format!("person_{}", debtor),
+                creditor: format!("person_{}", creditor),
+                amount_cents: 100 + (next() % 50_000) as i64,
+                expense_ids: vec![format!("exp_{}", i)],
+            }
+        })
+        .collect()
+}
+
+fn bench_net_balances(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_net_balances");
+    for (people, count) in [(50, 1_000), (500, 50_000)] {
+        let debts = generate_debts(people, count);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}x{}", people, count)),
+            &debts,
+            |b, debts| b.iter(|| calculate_net_balances(black_box(debts))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_min_transactions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("min_transactions");
+    for (people, count) in [(12, 200), (50, 1_000), (300, 20_000), (500, 50_000)] {
+        let debts = generate_debts(people, count);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}x{}", people, count)),
+            &debts,
+            |b, debts| {
+                b.iter(|| simplify_debts_with_objective(black_box(debts), Objective::MinTransactions))
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_flow_objectives(c: &mut Criterion) {
+    // Flow-based objectives solve a network over many pairs, so they are
+    // benchmarked on smaller groups
+    let mut group = c.benchmark_group("flow_objectives");
+    group.sample_size(10);
+    for (people, count) in [(20, 500), (60, 2_000)] {
+        let debts = generate_debts(people, count);
+        for objective in [Objective::MinTotalTransferred, Objective::MinMaxPayment] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", objective), format!("{}x{}", people, count)),
+                &debts,
+                |b, debts| b.iter(|| simplify_debts_with_objective(black_box(debts), objective)),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_net_balances,
+    bench_min_transactions,
+    bench_flow_objectives
+);
+criterion_main!(benches);
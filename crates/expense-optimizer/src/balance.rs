@@ -5,6 +5,7 @@
 
 use std::collections::HashMap;
 
+use crate::intern::intern_debts;
 use crate::types::Debt;
 
 /// Calculate the net balance for each participant from a list of debts.
@@ -12,20 +13,24 @@ use crate::types::Debt;
 /// A positive balance means the person is owed money (creditor).
 /// A negative balance means the person owes money (debtor).
 ///
+/// Runs in O(d) time for `d` debts and allocates one `String` per distinct
+/// participant, not per debt.
+///
 /// # Arguments
 /// * `debts` - Slice of Debt objects to calculate balances from
 ///
 /// # Returns
 /// A HashMap mapping participant names to their net balance in cents
 pub fn calculate_net_balances(debts: &[Debt]) -> HashMap<String, i64> {
-    let mut balances: HashMap<String, i64> = HashMap::new();
-
-    for debt in debts {
-        *balances.entry(debt.debtor.clone()).or_insert(0) -= debt.amount_cents;
-        *balances.entry(debt.creditor.clone()).or_insert(0) += debt.amount_cents;
-    }
-
-    balances
+    let interned = intern_debts(debts);
+    let balances = interned.balances(&interned.transfers);
+
+    interned
+        .names
+        .into_iter()
+        .zip(balances)
+        .map(|(name, balance)| (name.to_string(), balance))
+        .collect()
 }
 
 #[cfg(test)]
@@ -0,0 +1,263 @@
+//! Settlement graph export.
+//!
+//! This module renders debt and payment graphs as Graphviz DOT or Mermaid
+//! flowchart text. Nodes are labelled with each person's net balance and
+//! edges with the amount that flows along them.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::reason::{Locale, format_wide_amount};
+use crate::types::{Debt, Payment};
+
+/// Text format for exported graphs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphFormat {
+    /// Graphviz DOT (`digraph`).
+    Dot,
+    /// Mermaid flowchart (`flowchart LR`).
+    Mermaid,
+}
+
+/// A directed money graph: net balance per person and amount per edge.
+///
+/// Balances are summed in i128, so no number of i64 edges can overflow them.
+struct MoneyGraph<'a> {
+    balances: BTreeMap<&'a str, i128>,
+    edges: Vec<(&'a str, &'a str, i64)>,
+}
+
+impl<'a> MoneyGraph<'a> {
+    /// Build a graph where each edge moves `amount` from `from` to `to`.
+    ///
+    /// Balances follow the debt convention: for a debt edge the creditor is
+    /// owed money, for a payment edge the recipient is the one who was owed.
+    fn new(edges: Vec<(&'a str, &'a str, i64)>) -> Self {
+        let mut balances = BTreeMap::new();
+        for &(from, to, amount) in &edges {
+            *balances.entry(from).or_insert(0) -= i128::from(amount);
+            *balances.entry(to).or_insert(0) += i128::from(amount);
+        }
+        Self { balances, edges }
+    }
+
+    fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    fn to_dot(&self) -> String {
+        let mut out = String::from("digraph settlement {\n    rankdir=LR;\n");
+
+        for (person, balance) in &self.balances {
+            let label = format!("{}\\n{}", escape_dot(person), format_signed(*balance));
+            let _ = writeln!(out, "    \"{}\" [label=\"{}\"];", escape_dot(person), label);
+        }
+        for (from, to, amount) in &self.edges {
+            let _ = writeln!(
+                out,
+                "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                escape_dot(from),
+                escape_dot(to),
+                format_cents(i128::from(*amount))
+            );
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_mermaid(&self) -> String {
+        // Mermaid node IDs must be plain identifiers, so people get n0, n1, ...
+        let ids: BTreeMap<&str, String> = self
+            .balances
+            .keys()
+            .enumerate()
+            .map(|(i, person)| (*person, format!("n{}", i)))
+            .collect();
+
+        let mut out = String::from("flowchart LR\n");
+
+        for (person, balance) in &self.balances {
+            let _ = writeln!(
+                out,
+                "    {}[\"{}<br/>{}\"]",
+                ids[person],
+                escape_mermaid(person),
+                format_signed(*balance)
+            );
+        }
+        for (from, to, amount) in &self.edges {
+            let _ = writeln!(
+                out,
+                "    {} -->|\"{}\"| {}",
+                ids[from],
+                format_cents(i128::from(*amount)),
+                ids[to]
+            );
+        }
+
+        out
+    }
+}
+
+/// Render the original debt graph.
+///
+/// Each debt becomes an edge from debtor to creditor; each node shows the
+/// person's net balance (positive if they are owed money).
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects to render
+/// * `format` - Output format
+///
+/// # Returns
+/// The graph as DOT or Mermaid text
+pub fn render_debt_graph(debts: &[Debt], format: GraphFormat) -> String {
+    MoneyGraph::new(
+        debts
+            .iter()
+            .map(|d| (d.debtor.as_str(), d.creditor.as_str(), d.amount_cents))
+            .collect(),
+    )
+    .render(format)
+}
+
+/// Render a payment graph, such as a simplified settlement plan.
+///
+/// Each payment becomes an edge from payer to payee; each node shows the net
+/// balance the payments settle (positive if the person receives money).
+///
+/// # Arguments
+/// * `payments` - Slice of Payment objects to render
+/// * `format` - Output format
+///
+/// # Returns
+/// The graph as DOT or Mermaid text
+pub fn render_payment_graph(payments: &[Payment], format: GraphFormat) -> String {
+    MoneyGraph::new(
+        payments
+            .iter()
+            .map(|p| (p.from.as_str(), p.to.as_str(), p.amount_cents))
+            .collect(),
+    )
+    .render(format)
+}
+
+/// Format cents as dollars with exactly two decimals, e.g. `$1,234.05`.
+fn format_cents(cents: i128) -> String {
+    format_wide_amount(cents, Locale::En, "$")
+}
+
+/// Format a balance with an explicit sign, e.g. `+$12.05` or `-$3.00`.
+fn format_signed(cents: i128) -> String {
+    if cents > 0 {
+        format!("+{}", format_cents(cents))
+    } else {
+        format_cents(cents)
+    }
+}
+
+/// Escape a string for use inside a double-quoted DOT ID or label.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escape a string for use inside a double-quoted Mermaid label.
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplify::simplify_debts;
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn test_debt_graph_dot() {
+        let debts = vec![debt("A", "B", 10000), debt("B", "C", 10000)];
+
+        let dot = render_debt_graph(&debts, GraphFormat::Dot);
+
+        assert_eq!(
+            dot,
+            "digraph settlement {\n    rankdir=LR;\n\
+             \x20   \"A\" [label=\"A\\n-$100.00\"];\n\
+             \x20   \"B\" [label=\"B\\n$0.00\"];\n\
+             \x20   \"C\" [label=\"C\\n+$100.00\"];\n\
+             \x20   \"A\" -> \"B\" [label=\"$100.00\"];\n\
+             \x20   \"B\" -> \"C\" [label=\"$100.00\"];\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_payment_graph_mermaid() {
+        let debts = vec![debt("A", "B", 10000), debt("B", "C", 10000)];
+        let result = simplify_debts(&debts);
+
+        let mermaid = render_payment_graph(&result.payments, GraphFormat::Mermaid);
+
+        assert_eq!(
+            mermaid,
+            "flowchart LR\n\
+             \x20   n0[\"A<br/>-$100.00\"]\n\
+             \x20   n1[\"C<br/>+$100.00\"]\n\
+             \x20   n0 -->|\"$100.00\"| n1\n"
+        );
+    }
+
+    #[test]
+    fn test_labels_are_escaped() {
+        let debts = vec![debt("Bob \"The Builder\"", "Ann", 5)];
+
+        let dot = render_debt_graph(&debts, GraphFormat::Dot);
+        let mermaid = render_debt_graph(&debts, GraphFormat::Mermaid);
+
+        assert!(dot.contains("\"Bob \\\"The Builder\\\"\""));
+        assert!(dot.contains("$0.05"));
+        assert!(mermaid.contains("Bob #quot;The Builder#quot;"));
+    }
+
+    #[test]
+    fn test_empty_graph() {
+        assert_eq!(
+            render_debt_graph(&[], GraphFormat::Dot),
+            "digraph settlement {\n    rankdir=LR;\n}\n"
+        );
+        assert_eq!(
+            render_payment_graph(&[], GraphFormat::Mermaid),
+            "flowchart LR\n"
+        );
+    }
+
+    #[test]
+    fn test_format_cents() {
+        assert_eq!(format_cents(0), "$0.00");
+        assert_eq!(format_cents(5), "$0.05");
+        assert_eq!(format_cents(123456), "$1,234.56");
+        assert_eq!(format_cents(-250), "-$2.50");
+    }
+
+    #[test]
+    fn test_balances_beyond_i64_do_not_overflow() {
+        let debts = vec![debt("A", "B", i64::MAX), debt("C", "B", i64::MAX)];
+
+        let dot = render_debt_graph(&debts, GraphFormat::Dot);
+
+        assert!(dot.contains("+$184,467,440,737,095,516.14"), "{}", dot);
+    }
+}
@@ -0,0 +1,235 @@
+//! Network flow primitives used by the settlement solvers.
+//!
+//! Settlement planning is modelled as a bipartite flow network:
+//! source -> debtors -> creditors -> sink, where the source edges carry each
+//! debtor's outstanding amount and the sink edges each creditor's claim.
+//! A feasible settlement is any flow that saturates every source edge.
+//! Routing along existing debts instead uses the debt graph itself between
+//! source and sink, so money can pass through intermediaries.
+
+use std::collections::VecDeque;
+
+/// A directed edge in the residual graph.
+#[derive(Debug, Clone)]
+struct Edge {
+    to: usize,
+    cap: i64,
+    cost: i64,
+}
+
+/// A flow network supporting maximum flow and minimum-cost flow.
+///
+/// Edges are stored in pairs so that `id ^ 1` is always the residual
+/// (reverse) edge of `id`.
+#[derive(Debug, Clone)]
+pub(crate) struct FlowNetwork {
+    adjacency: Vec<Vec<usize>>,
+    edges: Vec<Edge>,
+}
+
+impl FlowNetwork {
+    /// Create an empty network with `nodes` vertices.
+    pub(crate) fn new(nodes: usize) -> Self {
+        Self {
+            adjacency: vec![Vec::new(); nodes],
+            edges: Vec::new(),
+        }
+    }
+
+    /// Add an edge from `from` to `to` and return its id.
+    pub(crate) fn add_edge(&mut self, from: usize, to: usize, cap: i64, cost: i64) -> usize {
+        let id = self.edges.len();
+        self.edges.push(Edge { to, cap, cost });
+        self.adjacency[from].push(id);
+        self.edges.push(Edge {
+            to: from,
+            cap: 0,
+            cost: -cost,
+        });
+        self.adjacency[to].push(id + 1);
+        id
+    }
+
+    /// Amount of flow currently pushed along the edge `id`.
+    pub(crate) fn flow(&self, id: usize) -> i64 {
+        self.edges[id ^ 1].cap
+    }
+
+    /// Compute the maximum flow from `source` to `sink` (Dinic's algorithm).
+    pub(crate) fn max_flow(&mut self, source: usize, sink: usize) -> i64 {
+        let mut total = 0;
+
+        while let Some(levels) = self.levels(source, sink) {
+            let mut next_edge = vec![0usize; self.adjacency.len()];
+            loop {
+                let pushed = self.augment(source, sink, i64::MAX, &levels, &mut next_edge);
+                if pushed == 0 {
+                    break;
+                }
+                total += pushed;
+            }
+        }
+
+        total
+    }
+
+    /// Compute a maximum flow of minimum total cost from `source` to `sink`.
+    ///
+    /// Uses successive shortest paths with Bellman-Ford (SPFA) path search,
+    /// so negative residual costs are handled correctly.
+    ///
+    /// # Returns
+    /// A tuple of `(flow, cost)`
+    pub(crate) fn min_cost_flow(&mut self, source: usize, sink: usize) -> (i64, i64) {
+        let nodes = self.adjacency.len();
+        let mut total_flow = 0;
+        let mut total_cost = 0;
+
+        loop {
+            let mut dist = vec![i64::MAX; nodes];
+            let mut via: Vec<Option<usize>> = vec![None; nodes];
+            let mut queued = vec![false; nodes];
+            let mut queue = VecDeque::new();
+
+            dist[source] = 0;
+            queue.push_back(source);
+            queued[source] = true;
+
+            while let Some(node) = queue.pop_front() {
+                queued[node] = false;
+                for &id in &self.adjacency[node] {
+                    let edge = &self.edges[id];
+                    if edge.cap > 0 && dist[node] + edge.cost < dist[edge.to] {
+                        dist[edge.to] = dist[node] + edge.cost;
+                        via[edge.to] = Some(id);
+                        if !queued[edge.to] {
+                            queued[edge.to] = true;
+                            queue.push_back(edge.to);
+                        }
+                    }
+                }
+            }
+
+            if dist[sink] == i64::MAX {
+                break;
+            }
+
+            // Find the bottleneck along the shortest path
+            let mut bottleneck = i64::MAX;
+            let mut node = sink;
+            while let Some(id) = via[node] {
+                bottleneck = bottleneck.min(self.edges[id].cap);
+                node = self.edges[id ^ 1].to;
+            }
+
+            // Push flow along the path
+            let mut node = sink;
+            while let Some(id) = via[node] {
+                self.edges[id].cap -= bottleneck;
+                self.edges[id ^ 1].cap += bottleneck;
+                node = self.edges[id ^ 1].to;
+            }
+
+            total_flow += bottleneck;
+            total_cost += bottleneck * dist[sink];
+        }
+
+        (total_flow, total_cost)
+    }
+
+    /// Build the BFS level graph, or `None` if the sink is unreachable.
+    fn levels(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut levels = vec![usize::MAX; self.adjacency.len()];
+        let mut queue = VecDeque::new();
+
+        levels[source] = 0;
+        queue.push_back(source);
+
+        while let Some(node) = queue.pop_front() {
+            for &id in &self.adjacency[node] {
+                let edge = &self.edges[id];
+                if edge.cap > 0 && levels[edge.to] == usize::MAX {
+                    levels[edge.to] = levels[node] + 1;
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        (levels[sink] != usize::MAX).then_some(levels)
+    }
+
+    /// Push a blocking-flow path from `node` towards `sink`.
+    fn augment(
+        &mut self,
+        node: usize,
+        sink: usize,
+        limit: i64,
+        levels: &[usize],
+        next_edge: &mut [usize],
+    ) -> i64 {
+        if node == sink {
+            return limit;
+        }
+
+        while next_edge[node] < self.adjacency[node].len() {
+            let id = self.adjacency[node][next_edge[node]];
+            let (to, cap) = (self.edges[id].to, self.edges[id].cap);
+
+            if cap > 0 && levels[to] == levels[node] + 1 {
+                let pushed = self.augment(to, sink, limit.min(cap), levels, next_edge);
+                if pushed > 0 {
+                    self.edges[id].cap -= pushed;
+                    self.edges[id ^ 1].cap += pushed;
+                    return pushed;
+                }
+            }
+
+            next_edge[node] += 1;
+        }
+
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_flow_simple() {
+        // 0 -> 1 -> 3, 0 -> 2 -> 3 with a cross edge 1 -> 2
+        let mut network = FlowNetwork::new(4);
+        network.add_edge(0, 1, 10, 0);
+        network.add_edge(0, 2, 5, 0);
+        network.add_edge(1, 2, 15, 0);
+        network.add_edge(1, 3, 5, 0);
+        network.add_edge(2, 3, 10, 0);
+
+        assert_eq!(network.max_flow(0, 3), 15);
+    }
+
+    #[test]
+    fn test_min_cost_flow_prefers_cheap_path() {
+        let mut network = FlowNetwork::new(4);
+        let cheap = network.add_edge(0, 1, 10, 1);
+        let expensive = network.add_edge(0, 2, 10, 5);
+        network.add_edge(1, 3, 10, 0);
+        network.add_edge(2, 3, 10, 0);
+
+        let (flow, cost) = network.min_cost_flow(0, 3);
+
+        assert_eq!(flow, 20);
+        assert_eq!(cost, 10 + 50);
+        assert_eq!(network.flow(cheap), 10);
+        assert_eq!(network.flow(expensive), 10);
+    }
+
+    #[test]
+    fn test_unreachable_sink_has_no_flow() {
+        let mut network = FlowNetwork::new(3);
+        network.add_edge(0, 1, 10, 0);
+
+        assert_eq!(network.max_flow(0, 2), 0);
+        assert_eq!(network.min_cost_flow(0, 2), (0, 0));
+    }
+}
@@ -0,0 +1,324 @@
+//! Household-level settlement.
+//!
+//! This module lets groups of people, such as couples, settle as a single
+//! unit: debts are mapped onto households before simplification, and each
+//! household's total can be broken down per member.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use finance_core::PersonId;
+
+use crate::balance::calculate_net_balances;
+use crate::simplify::{settlement_metrics, simplify_debts};
+use crate::types::{Debt, Household, HouseholdBreakdown, MemberBalance, SimplificationResult};
+
+/// Errors that make a list of households ambiguous.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HouseholdError {
+    /// A person is listed in two different households.
+    MemberOfTwo {
+        /// The person listed twice.
+        person: PersonId,
+        /// The first household listing them.
+        first: String,
+        /// The other household listing them.
+        second: String,
+    },
+    /// Two households share an ID.
+    DuplicateId {
+        /// The shared ID.
+        household: String,
+    },
+    /// A household's ID is also the ID of a person outside that household,
+    /// so payments to or from it would be ambiguous.
+    IdClash {
+        /// The clashing ID.
+        household: String,
+    },
+}
+
+impl std::fmt::Display for HouseholdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HouseholdError::MemberOfTwo {
+                person,
+                first,
+                second,
+            } => write!(
+                f,
+                "{} is a member of both households {} and {}",
+                person, first, second
+            ),
+            HouseholdError::DuplicateId { household } => {
+                write!(f, "household ID {} is used twice", household)
+            }
+            HouseholdError::IdClash { household } => write!(
+                f,
+                "household ID {} is also the ID of a person outside it",
+                household
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HouseholdError {}
+
+/// Simplify debts between households rather than individuals.
+///
+/// Every debtor and creditor is replaced by their household ID; people who
+/// belong to no household settle as themselves. Debts within a household
+/// cancel out and never produce a payment. The individual-level plan for the
+/// same debts is still available from [`simplify_debts`].
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects between individuals
+/// * `households` - The households people belong to
+///
+/// # Returns
+/// A SimplificationResult whose payments are between household IDs, or the
+/// HouseholdError if a person is in two households or a household ID names
+/// someone outside it
+pub fn simplify_household_debts(
+    debts: &[Debt],
+    households: &[Household],
+) -> Result<SimplificationResult, HouseholdError> {
+    let unit = household_lookup(debts, households)?;
+    let unit_of = |person: &str| -> String {
+        unit.get(person)
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| person.to_string())
+    };
+
+    let household_debts: Vec<Debt> = debts
+        .iter()
+        .map(|debt| Debt {
+            debtor: unit_of(&debt.debtor),
+            creditor: unit_of(&debt.creditor),
+            amount_cents: debt.amount_cents,
+            expense_ids: debt.expense_ids.clone(),
+        })
+        .collect();
+
+    let mut result = simplify_debts(&household_debts);
+    result.original_count = debts.len();
+    result.metrics = settlement_metrics(debts, &result.payments);
+    Ok(result)
+}
+
+/// Explain how each household's net balance breaks down per member.
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects between individuals
+/// * `households` - The households to explain
+///
+/// # Returns
+/// One HouseholdBreakdown per household, in the order given, or the
+/// HouseholdError if the households are ambiguous
+pub fn explain_household_balances(
+    debts: &[Debt],
+    households: &[Household],
+) -> Result<Vec<HouseholdBreakdown>, HouseholdError> {
+    household_lookup(debts, households)?;
+    let balances = calculate_net_balances(debts);
+
+    Ok(households
+        .iter()
+        .map(|household| {
+            let members: BTreeMap<&str, i64> = household
+                .members
+                .iter()
+                .map(|m| (m.as_str(), balances.get(m).copied().unwrap_or(0)))
+                .collect();
+
+            HouseholdBreakdown {
+                household: household.id.clone(),
+                balance_cents: members.values().sum(),
+                members: members
+                    .into_iter()
+                    .map(|(person, balance_cents)| MemberBalance {
+                        person: person.to_string(),
+                        balance_cents,
+                    })
+                    .collect(),
+            }
+        })
+        .collect())
+}
+
+/// Map each member to their household ID, rejecting ambiguous households.
+fn household_lookup<'a>(
+    debts: &[Debt],
+    households: &'a [Household],
+) -> Result<HashMap<&'a str, &'a str>, HouseholdError> {
+    let mut unit: HashMap<&str, &str> = HashMap::new();
+    let mut ids = HashSet::new();
+
+    for household in households {
+        if !ids.insert(household.id.as_str()) {
+            return Err(HouseholdError::DuplicateId {
+                household: household.id.clone(),
+            });
+        }
+        for member in &household.members {
+            match unit.insert(member, &household.id) {
+                Some(first) if first != household.id => {
+                    return Err(HouseholdError::MemberOfTwo {
+                        person: member.clone(),
+                        first: first.to_string(),
+                        second: household.id.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // An ID may name one of the household's own members, but nobody else
+    for household in households {
+        let id = household.id.as_str();
+        let clashes = match unit.get(id) {
+            Some(&owner) => owner != id,
+            None => debts.iter().any(|d| d.debtor == id || d.creditor == id),
+        };
+        if clashes {
+            return Err(HouseholdError::IdClash {
+                household: household.id.clone(),
+            });
+        }
+    }
+
+    Ok(unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: vec![],
+        }
+    }
+
+    fn household(id: &str, members: &[&str]) -> Household {
+        Household {
+            id: id.to_string(),
+            members: members.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_couple_settles_as_one_unit() {
+        // Alice and Bob each owe Carol; they pay her once as a household
+        let debts = vec![debt("Alice", "Carol", 3000), debt("Bob", "Carol", 2000)];
+        let households = vec![household("Smiths", &["Alice", "Bob"])];
+
+        let result = simplify_household_debts(&debts, &households).unwrap();
+
+        assert_eq!(result.original_count, 2);
+        assert_eq!(result.payments.len(), 1);
+        assert_eq!(result.payments[0].from, "Smiths");
+        assert_eq!(result.payments[0].to, "Carol");
+        assert_eq!(result.payments[0].amount_cents, 5000);
+    }
+
+    #[test]
+    fn test_debts_within_household_cancel() {
+        let debts = vec![debt("Alice", "Bob", 4000), debt("Alice", "Carol", 1000)];
+        let households = vec![household("Smiths", &["Alice", "Bob"])];
+
+        let result = simplify_household_debts(&debts, &households).unwrap();
+
+        assert_eq!(result.payments.len(), 1);
+        assert_eq!(result.payments[0].amount_cents, 1000);
+    }
+
+    #[test]
+    fn test_breakdown_per_member() {
+        // Alice is owed by Dave, Bob owes Carol: the household nets out
+        let debts = vec![debt("Dave", "Alice", 3000), debt("Bob", "Carol", 5000)];
+        let households = vec![
+            household("Smiths", &["Bob", "Alice"]),
+            household("Joneses", &["Carol", "Dave"]),
+        ];
+
+        let breakdown = explain_household_balances(&debts, &households).unwrap();
+
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0].household, "Smiths");
+        assert_eq!(breakdown[0].balance_cents, -2000);
+        assert_eq!(
+            breakdown[0].members,
+            vec![
+                MemberBalance {
+                    person: "Alice".to_string(),
+                    balance_cents: 3000,
+                },
+                MemberBalance {
+                    person: "Bob".to_string(),
+                    balance_cents: -5000,
+                },
+            ]
+        );
+        assert_eq!(breakdown[1].balance_cents, 2000);
+
+        let result = simplify_household_debts(&debts, &households).unwrap();
+        assert_eq!(result.payments.len(), 1);
+        assert_eq!(result.payments[0].from, "Smiths");
+        assert_eq!(result.payments[0].amount_cents, 2000);
+    }
+
+    #[test]
+    fn test_ambiguous_households_are_rejected() {
+        let debts = vec![debt("Alice", "Carol", 3000), debt("Bob", "Smiths", 1000)];
+
+        let twice = vec![
+            household("Smiths", &["Alice", "Bob"]),
+            household("Joneses", &["Bob", "Carol"]),
+        ];
+        assert_eq!(
+            simplify_household_debts(&debts, &twice).unwrap_err(),
+            HouseholdError::MemberOfTwo {
+                person: "Bob".to_string(),
+                first: "Smiths".to_string(),
+                second: "Joneses".to_string(),
+            }
+        );
+        assert!(explain_household_balances(&debts, &twice).is_err());
+
+        // "Smiths" is also a creditor who belongs to no household
+        let clash = vec![household("Smiths", &["Alice"])];
+        assert_eq!(
+            simplify_household_debts(&debts, &clash).unwrap_err(),
+            HouseholdError::IdClash {
+                household: "Smiths".to_string(),
+            }
+        );
+
+        // "Carol" names a member of another household
+        let clash = vec![
+            household("Carol", &["Alice"]),
+            household("Joneses", &["Carol"]),
+        ];
+        assert!(matches!(
+            simplify_household_debts(&debts, &clash),
+            Err(HouseholdError::IdClash { .. })
+        ));
+
+        let duplicate = vec![
+            household("Joneses", &["Alice"]),
+            household("Joneses", &["Bob"]),
+        ];
+        assert!(matches!(
+            simplify_household_debts(&debts, &duplicate),
+            Err(HouseholdError::DuplicateId { .. })
+        ));
+
+        // A household may share its ID with one of its own members
+        let own = vec![household("Alice", &["Alice", "Bob"])];
+        assert!(simplify_household_debts(&debts[..1], &own).is_ok());
+    }
+}
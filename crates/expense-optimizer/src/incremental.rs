@@ -0,0 +1,287 @@
+//! Incremental settlement plan updates.
+//!
+//! This module updates an existing settlement plan when new debts arrive,
+//! keeping payments that were already made or scheduled and changing as few
+//! of the remaining payments as possible.
+
+use std::collections::HashMap;
+
+use crate::simplify::{
+    make_payment, payment_counts, settle_balances, settlement_metrics, sort_payments,
+};
+use crate::types::{
+    Debt, Objective, Payment, PaymentChange, PlanDiff, PlanUpdate, SimplificationResult,
+};
+
+/// Update a settlement plan to also cover `new_debts`.
+///
+/// The previous plan's payments stand in for the debts it settled, so the
+/// updated plan must settle those payments plus the new debts. The algorithm:
+/// 1. Apply every locked payment as-is; locked payments are always kept
+/// 2. Keep each remaining previous payment for as much of its amount as
+///    the outstanding balances still allow
+/// 3. Settle whatever is left with [`Objective::MinTransactions`], folding
+///    new transfers into kept payments between the same two people
+///
+/// # Arguments
+/// * `previous` - The plan currently shown to participants
+/// * `locked` - Payments already completed or scheduled, which must not change
+/// * `new_debts` - Debts added since `previous` was computed
+///
+/// # Returns
+/// A PlanUpdate containing the new plan and its diff against `previous`
+pub fn update_settlement_plan(
+    previous: &SimplificationResult,
+    locked: &[Payment],
+    new_debts: &[Debt],
+) -> PlanUpdate {
+    // Outstanding balance per person: positive = still owed money
+    let mut outstanding: HashMap<String, i64> = HashMap::new();
+    let mut owe = |from: &str, to: &str, amount: i64| {
+        *outstanding.entry(from.to_string()).or_insert(0) -= amount;
+        *outstanding.entry(to.to_string()).or_insert(0) += amount;
+    };
+
+    for payment in &previous.payments {
+        owe(&payment.from, &payment.to, payment.amount_cents);
+    }
+    for debt in new_debts {
+        owe(&debt.debtor, &debt.creditor, debt.amount_cents);
+    }
+
+    // Step 1: Locked payments are applied unconditionally
+    for payment in locked {
+        owe(&payment.to, &payment.from, payment.amount_cents);
+    }
+
+    // Locked payments that match a previous payment exactly are unchanged;
+    // the rest are new to this plan
+    let mut unmatched_locked: Vec<&Payment> = locked.iter().collect();
+    let mut candidates: Vec<&Payment> = Vec::new();
+    for payment in &previous.payments {
+        match unmatched_locked
+            .iter()
+            .position(|l| same_payment(l, payment))
+        {
+            Some(pos) => {
+                unmatched_locked.remove(pos);
+            }
+            None => candidates.push(payment),
+        }
+    }
+
+    // Step 2: Keep as much of each unlocked previous payment as still fits
+    let mut kept: Vec<(&Payment, i64)> = Vec::new();
+    let mut removed: Vec<Payment> = Vec::new();
+    for payment in candidates {
+        let still_owes = (-outstanding.get(&payment.from).copied().unwrap_or(0)).max(0);
+        let still_owed = outstanding.get(&payment.to).copied().unwrap_or(0).max(0);
+        let amount = payment.amount_cents.min(still_owes).min(still_owed);
+
+        if amount > 0 {
+            *outstanding.get_mut(&payment.from).unwrap() += amount;
+            *outstanding.get_mut(&payment.to).unwrap() -= amount;
+            kept.push((payment, amount));
+        } else {
+            removed.push(payment.clone());
+        }
+    }
+
+    // Step 3: Settle the rest, reusing existing pairs where possible
+    let mut fresh: Vec<Payment> = Vec::new();
+    for payment in settle_balances(outstanding, Objective::MinTransactions) {
+        match kept
+            .iter_mut()
+            .find(|(k, _)| k.from == payment.from && k.to == payment.to)
+        {
+            Some((_, amount)) => *amount += payment.amount_cents,
+            None => fresh.push(payment),
+        }
+    }
+
+    // Assemble the plan and its diff
+    let mut payments: Vec<Payment> = locked.to_vec();
+    let mut changed: Vec<PaymentChange> = Vec::new();
+    for (before, amount) in kept {
+        if amount == before.amount_cents {
+            payments.push(before.clone());
+        } else {
+            let after = make_payment(&before.from, &before.to, amount);
+            payments.push(after.clone());
+            changed.push(PaymentChange {
+                before: before.clone(),
+                after,
+            });
+        }
+    }
+    payments.extend(fresh.iter().cloned());
+    sort_payments(&mut payments);
+
+    let mut added: Vec<Payment> = unmatched_locked.into_iter().cloned().collect();
+    added.extend(fresh);
+
+    let original_count = previous.original_count + new_debts.len();
+    let optimized_count = payments.len();
+    let payment_counts = payment_counts(&payments);
+
+    // As above, the previous payments stand in for the debts they settled
+    let settled: Vec<Debt> = previous
+        .payments
+        .iter()
+        .map(|p| Debt {
+            debtor: p.from.clone(),
+            creditor: p.to.clone(),
+            amount_cents: p.amount_cents,
+            expense_ids: Vec::new(),
+        })
+        .chain(new_debts.iter().cloned())
+        .collect();
+    let metrics = settlement_metrics(&settled, &payments);
+
+    PlanUpdate {
+        result: SimplificationResult {
+            original_count,
+            optimized_count,
+            payments,
+            metrics,
+            objective: Objective::MinTransactions,
+            objective_value: optimized_count as i64,
+            payment_counts,
+        },
+        diff: PlanDiff {
+            added,
+            removed,
+            changed,
+        },
+    }
+}
+
+/// Whether two payments move the same amount between the same people.
+fn same_payment(a: &Payment, b: &Payment) -> bool {
+    a.from == b.from && a.to == b.to && a.amount_cents == b.amount_cents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::calculate_net_balances;
+    use crate::simplify::simplify_debts;
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: vec![],
+        }
+    }
+
+    /// Check that `payments` settle exactly the balances of `debts`.
+    fn assert_settles(debts: &[Debt], payments: &[Payment]) {
+        let mut balances = calculate_net_balances(debts);
+        for payment in payments {
+            *balances.entry(payment.from.clone()).or_insert(0) += payment.amount_cents;
+            *balances.entry(payment.to.clone()).or_insert(0) -= payment.amount_cents;
+        }
+        for (person, balance) in balances {
+            assert_eq!(balance, 0, "{} is not settled", person);
+        }
+    }
+
+    #[test]
+    fn test_no_new_debts_keeps_plan() {
+        let debts = vec![debt("A", "B", 5000), debt("C", "B", 3000)];
+        let previous = simplify_debts(&debts);
+
+        let update = update_settlement_plan(&previous, &[], &[]);
+
+        assert_eq!(update.result.payments.len(), previous.payments.len());
+        for payment in &previous.payments {
+            assert!(update.result.payments.contains(payment));
+        }
+        assert_eq!(update.diff, PlanDiff::default());
+    }
+
+    #[test]
+    fn test_new_debt_between_existing_pair_changes_amount() {
+        let debts = vec![debt("A", "B", 5000)];
+        let previous = simplify_debts(&debts);
+
+        let update = update_settlement_plan(&previous, &[], &[debt("A", "B", 2000)]);
+
+        assert_eq!(update.result.payments.len(), 1);
+        assert_eq!(update.result.payments[0].amount_cents, 7000);
+        assert!(update.diff.added.is_empty());
+        assert!(update.diff.removed.is_empty());
+        assert_eq!(update.diff.changed.len(), 1);
+        assert_eq!(update.diff.changed[0].before.amount_cents, 5000);
+        assert_eq!(update.diff.changed[0].after.amount_cents, 7000);
+    }
+
+    #[test]
+    fn test_new_unrelated_debt_is_added() {
+        let debts = vec![debt("A", "B", 5000)];
+        let previous = simplify_debts(&debts);
+        let new_debts = vec![debt("C", "D", 1000)];
+
+        let update = update_settlement_plan(&previous, &[], &new_debts);
+
+        assert_eq!(update.diff.added.len(), 1);
+        assert_eq!(update.diff.added[0].from, "C");
+        assert_eq!(update.diff.added[0].to, "D");
+        assert!(update.diff.changed.is_empty());
+        assert!(update.result.payments.contains(&previous.payments[0]));
+
+        let all_debts: Vec<Debt> = debts.into_iter().chain(new_debts).collect();
+        assert_settles(&all_debts, &update.result.payments);
+    }
+
+    #[test]
+    fn test_locked_payment_survives_reversal() {
+        // A already paid B; then a new debt makes B owe A the same amount
+        let debts = vec![debt("A", "B", 5000)];
+        let previous = simplify_debts(&debts);
+        let locked = previous.payments.clone();
+        let new_debts = vec![debt("B", "A", 5000)];
+
+        let update = update_settlement_plan(&previous, &locked, &new_debts);
+
+        assert!(update.result.payments.contains(&locked[0]));
+        assert_eq!(update.diff.added.len(), 1);
+        assert_eq!(update.diff.added[0].from, "B");
+        assert_eq!(update.diff.added[0].to, "A");
+        assert!(update.diff.removed.is_empty());
+
+        let all_debts: Vec<Debt> = debts.into_iter().chain(new_debts).collect();
+        assert_settles(&all_debts, &update.result.payments);
+    }
+
+    #[test]
+    fn test_cancelled_debt_removes_payment() {
+        let debts = vec![debt("A", "B", 5000), debt("C", "D", 2000)];
+        let previous = simplify_debts(&debts);
+        let new_debts = vec![debt("B", "A", 5000)];
+
+        let update = update_settlement_plan(&previous, &[], &new_debts);
+
+        assert_eq!(update.diff.removed.len(), 1);
+        assert_eq!(update.diff.removed[0].from, "A");
+        assert_eq!(update.result.payments.len(), 1);
+        assert_eq!(update.result.payments[0].from, "C");
+    }
+
+    #[test]
+    fn test_partial_locked_payment_reduces_remainder() {
+        // A owes B $50 and has already sent $20 of it
+        let debts = vec![debt("A", "B", 5000)];
+        let previous = simplify_debts(&debts);
+        let locked = vec![make_payment("A", "B", 2000)];
+
+        let update = update_settlement_plan(&previous, &locked, &[]);
+
+        assert_eq!(update.diff.added, locked);
+        assert_eq!(update.diff.changed.len(), 1);
+        assert_eq!(update.diff.changed[0].after.amount_cents, 3000);
+        assert_settles(&debts, &update.result.payments);
+    }
+}
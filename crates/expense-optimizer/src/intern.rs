@@ -0,0 +1,156 @@
+//! Participant ID interning.
+//!
+//! Large groups repeat the same few hundred names across tens of thousands of
+//! debts. Interning maps each distinct name to a dense `usize` index once, so
+//! the rest of the planner can work on plain vectors instead of cloning and
+//! hashing `String`s per debt.
+
+use std::collections::HashMap;
+
+use foldhash::fast::RandomState;
+
+use crate::types::Debt;
+
+/// A transfer of `.2` cents owed by participant `.0` to participant `.1`.
+pub(crate) type IdTransfer = (usize, usize, i64);
+
+/// Maps borrowed participant names to dense indices.
+///
+/// Names come from untrusted input such as MCP requests, so the map uses
+/// foldhash with a random seed: much faster than SipHash for short names,
+/// while crafted names cannot be chosen to collide. Indices follow first
+/// appearance, so the seed never affects the plan.
+pub(crate) struct Interner<'a> {
+    ids: HashMap<&'a str, usize, RandomState>,
+    names: Vec<&'a str>,
+}
+
+impl<'a> Interner<'a> {
+    /// Create an empty interner with room for `capacity` names.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ids: HashMap::with_capacity_and_hasher(capacity, Default::default()),
+            names: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Return the index for `name`, assigning the next one if it is new.
+    pub(crate) fn intern(&mut self, name: &'a str) -> usize {
+        let next = self.names.len();
+        let id = *self.ids.entry(name).or_insert(next);
+        if id == next {
+            self.names.push(name);
+        }
+        id
+    }
+
+    /// The names in index order.
+    pub(crate) fn into_names(self) -> Vec<&'a str> {
+        self.names
+    }
+}
+
+/// Debts with participant names replaced by interned indices.
+pub(crate) struct InternedDebts<'a> {
+    /// Participant name per index.
+    pub(crate) names: Vec<&'a str>,
+    /// One transfer per input debt, in input order.
+    pub(crate) transfers: Vec<IdTransfer>,
+}
+
+impl InternedDebts<'_> {
+    /// Net balance per index: positive = owed money, negative = owes money.
+    ///
+    /// Runs in O(t) time for `t` transfers.
+    pub(crate) fn balances(&self, transfers: &[IdTransfer]) -> Vec<i64> {
+        let mut balances = vec![0; self.names.len()];
+        for &(debtor, creditor, amount) in transfers {
+            balances[debtor] -= amount;
+            balances[creditor] += amount;
+        }
+        balances
+    }
+
+    /// Number of distinct (debtor, creditor) pairs among the transfers.
+    ///
+    /// Runs in O(t log t) time for `t` transfers.
+    pub(crate) fn distinct_pairs(&self) -> usize {
+        let mut pairs: Vec<(usize, usize)> =
+            self.transfers.iter().map(|&(d, c, _)| (d, c)).collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs.len()
+    }
+}
+
+/// Intern every debtor and creditor in `debts`.
+///
+/// Runs in O(d) time for `d` debts, allocating only once per distinct
+/// participant.
+pub(crate) fn intern_debts(debts: &[Debt]) -> InternedDebts<'_> {
+    // Groups rarely have more participants than a fraction of their debts
+    let mut interner = Interner::with_capacity(debts.len().min(1024));
+    let transfers = debts
+        .iter()
+        .map(|debt| {
+            (
+                interner.intern(&debt.debtor),
+                interner.intern(&debt.creditor),
+                debt.amount_cents,
+            )
+        })
+        .collect();
+
+    InternedDebts {
+        names: interner.into_names(),
+        transfers,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn test_intern_assigns_dense_indices() {
+        let mut interner = Interner::with_capacity(2);
+
+        assert_eq!(interner.intern("A"), 0);
+        assert_eq!(interner.intern("B"), 1);
+        assert_eq!(interner.intern("A"), 0);
+        assert_eq!(interner.into_names(), vec!["A", "B"]);
+    }
+
+    #[test]
+    fn test_interned_balances_match_debts() {
+        let debts = vec![debt("A", "B", 5000), debt("B", "C", 2000), debt("A", "B", 100)];
+
+        let interned = intern_debts(&debts);
+
+        assert_eq!(interned.names, vec!["A", "B", "C"]);
+        assert_eq!(
+            interned.balances(&interned.transfers),
+            vec![-5100, 3100, 2000]
+        );
+        assert_eq!(interned.distinct_pairs(), 2);
+    }
+
+    #[test]
+    fn test_self_debt_nets_to_zero() {
+        let debts = vec![debt("A", "A", 5000)];
+
+        let interned = intern_debts(&debts);
+
+        assert_eq!(interned.names, vec!["A"]);
+        assert_eq!(interned.balances(&interned.transfers), vec![0]);
+    }
+}
@@ -2,15 +2,52 @@
 
 pub mod types;
 pub mod balance;
+pub mod normalize;
 pub mod simplify;
+pub mod incremental;
+pub mod threshold;
+pub mod verify;
+pub mod validate;
+pub mod pipeline;
+pub mod schedule;
+pub mod households;
+pub mod export;
+pub mod reason;
+
+mod flow;
+mod intern;
 
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
 // Re-export main types and functions
-pub use types::{Debt, Payment, SimplificationResult};
+pub use types::{
+    BalanceResidual, Debt, ExpenseSettlement, Household, HouseholdBreakdown, Installment,
+    InstallmentTerms, MemberBalance, MinimumPayment, MinimumPaymentResult, Objective, Payment,
+    PaymentChange, PaymentCount, PlanDiff, PlanUpdate, Residue, ResidueKind, SettlementMetrics,
+    SettlementSchedule, SimplificationResult, SmallDebtPolicy, VerificationReport,
+};
 pub use balance::calculate_net_balances;
-pub use simplify::simplify_debts;
+pub use normalize::normalize_debts;
+pub use simplify::{
+    simplify_debts, simplify_debts_with_objective, sort_payments, try_simplify_debts,
+    try_simplify_debts_with_objective,
+};
+pub use incremental::update_settlement_plan;
+pub use threshold::simplify_debts_with_minimum;
+pub use verify::verify_settlement;
+pub use validate::{SimplifyError, validate_debts};
+pub use pipeline::{expenses_to_debts, settle_expenses};
+pub use schedule::schedule_settlements;
+pub use households::{HouseholdError, explain_household_balances, simplify_household_debts};
+pub use export::{GraphFormat, render_debt_graph, render_payment_graph};
+pub use reason::{Locale, ReasonOptions, format_amount, localize_reasons, settlement_reason};
+pub use finance_core::PersonId;
 
 #[cfg(feature = "wasm")]
-pub use wasm::{init, optimize_settlements, validate_debts};
+pub use wasm::{
+    explain_households, export_debt_graph, export_payment_graph, init,
+    optimize_household_settlements, optimize_settlements, optimize_settlements_with_minimum,
+    optimize_settlements_with_objective, localize_settlement_reasons, normalize,
+    schedule_installments, settle_trip_expenses, update_settlements, verify_settlements,
+};
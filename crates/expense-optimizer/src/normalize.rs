@@ -0,0 +1,178 @@
+//! Pairwise debt normalization.
+//!
+//! This module nets every debt between the same two people, in either
+//! direction, into at most one debt per pair. The canonical form is useful on
+//! its own for showing how two people stand, and is the first stage of
+//! [`simplify_debts`](crate::simplify_debts).
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::intern::{IdTransfer, intern_debts};
+use crate::types::Debt;
+
+/// Net all debts between each pair of people into a canonical list.
+///
+/// Debts between the same two people are summed, with debts in the opposite
+/// direction subtracted. Each pair that does not cancel out becomes a single
+/// debt from whoever still owes to whoever is owed, carrying the
+/// `expense_ids` of every debt between the two in input order without
+/// duplicates. Self-debts cancel out and are dropped.
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects to normalize
+///
+/// # Returns
+/// Debts with positive amounts, at most one per pair, sorted by debtor, then
+/// creditor
+pub fn normalize_debts(debts: &[Debt]) -> Vec<Debt> {
+    let interned = intern_debts(debts);
+
+    // Expense IDs per unordered pair, keyed (lower index, higher index), in
+    // first-seen order; the set keeps deduplication linear in the IDs
+    let mut expense_ids: HashMap<(usize, usize), Vec<&str>> = HashMap::new();
+    let mut seen: HashSet<((usize, usize), &str)> = HashSet::new();
+    for (debt, &(debtor, creditor, _)) in debts.iter().zip(&interned.transfers) {
+        let pair = (debtor.min(creditor), debtor.max(creditor));
+        let ids = expense_ids.entry(pair).or_default();
+        for id in &debt.expense_ids {
+            if seen.insert((pair, id)) {
+                ids.push(id);
+            }
+        }
+    }
+
+    let mut normalized: Vec<Debt> = net_transfers(&interned.transfers)
+        .into_iter()
+        .map(|(debtor, creditor, amount_cents)| Debt {
+            debtor: interned.names[debtor].to_string(),
+            creditor: interned.names[creditor].to_string(),
+            amount_cents,
+            expense_ids: expense_ids[&(debtor.min(creditor), debtor.max(creditor))]
+                .iter()
+                .map(|id| id.to_string())
+                .collect(),
+        })
+        .collect();
+    normalized.sort_by(|a, b| {
+        a.debtor
+            .cmp(&b.debtor)
+            .then_with(|| a.creditor.cmp(&b.creditor))
+    });
+    normalized
+}
+
+/// Net interned transfers per pair.
+///
+/// Returns one transfer with a positive amount for every pair that does not
+/// cancel out, ordered by the pair's indices. Runs in O(t log t) time for
+/// `t` transfers without hashing.
+pub(crate) fn net_transfers(transfers: &[IdTransfer]) -> Vec<IdTransfer> {
+    // Amount the lower index owes the higher index
+    let mut signed: Vec<IdTransfer> = transfers
+        .iter()
+        .filter(|(debtor, creditor, _)| debtor != creditor)
+        .map(|&(debtor, creditor, amount)| {
+            if debtor < creditor {
+                (debtor, creditor, amount)
+            } else {
+                (creditor, debtor, -amount)
+            }
+        })
+        .collect();
+    signed.sort_unstable_by_key(|&(lower, higher, _)| (lower, higher));
+
+    let mut netted = Vec::new();
+    for pair in signed.chunk_by(|a, b| (a.0, a.1) == (b.0, b.1)) {
+        let (lower, higher, _) = pair[0];
+        let net: i64 = pair.iter().map(|t| t.2).sum();
+        match net.cmp(&0) {
+            Ordering::Greater => netted.push((lower, higher, net)),
+            Ordering::Less => netted.push((higher, lower, -net)),
+            Ordering::Equal => {}
+        }
+    }
+    netted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64, ids: &[&str]) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: ids.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_duplicates_are_summed() {
+        let debts = vec![
+            debt("A", "B", 3000, &["exp1"]),
+            debt("A", "B", 2000, &["exp2"]),
+        ];
+
+        let normalized = normalize_debts(&debts);
+
+        assert_eq!(normalized, vec![debt("A", "B", 5000, &["exp1", "exp2"])]);
+    }
+
+    #[test]
+    fn test_opposing_debts_are_netted() {
+        // Bob owes Alice $50, Alice owes Bob $38: Bob is short $12
+        let debts = vec![
+            debt("Bob", "Alice", 5000, &["dinner"]),
+            debt("Alice", "Bob", 3800, &["taxi", "dinner"]),
+        ];
+
+        let normalized = normalize_debts(&debts);
+
+        assert_eq!(
+            normalized,
+            vec![debt("Bob", "Alice", 1200, &["dinner", "taxi"])]
+        );
+    }
+
+    #[test]
+    fn test_square_pairs_and_self_debts_are_dropped() {
+        let debts = vec![
+            debt("A", "B", 2000, &["exp1"]),
+            debt("B", "A", 2000, &["exp2"]),
+            debt("C", "C", 500, &["exp3"]),
+        ];
+
+        assert!(normalize_debts(&debts).is_empty());
+    }
+
+    #[test]
+    fn test_output_is_sorted_and_order_independent() {
+        let debts = vec![
+            debt("C", "A", 100, &[]),
+            debt("B", "A", 200, &[]),
+            debt("A", "D", 300, &[]),
+        ];
+        let mut reversed = debts.clone();
+        reversed.reverse();
+
+        let normalized = normalize_debts(&debts);
+
+        let pairs: Vec<(&str, &str)> = normalized
+            .iter()
+            .map(|d| (d.debtor.as_str(), d.creditor.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("A", "D"), ("B", "A"), ("C", "A")]);
+        assert_eq!(
+            normalized
+                .iter()
+                .map(|d| d.amount_cents)
+                .collect::<Vec<_>>(),
+            normalize_debts(&reversed)
+                .iter()
+                .map(|d| d.amount_cents)
+                .collect::<Vec<_>>()
+        );
+    }
+}
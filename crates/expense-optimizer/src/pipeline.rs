@@ -0,0 +1,165 @@
+//! Bridge from finance-core expenses to debt simplification.
+//!
+//! This module turns `finance_core::Expense` records and recorded settlements
+//! into pairwise debts, so callers can go from raw expenses to a settlement
+//! plan in one step.
+
+use finance_core::{Expense, Settlement, split_expense_impl};
+
+use crate::normalize::normalize_debts;
+use crate::simplify::simplify_debts;
+use crate::types::{Debt, ExpenseSettlement};
+
+/// Convert expenses and settlements into net pairwise debts.
+///
+/// Each participant owes the payer their share of an expense (as computed by
+/// `split_expense_impl`), and each settlement reduces what its payer owes the
+/// recipient. Debts in both directions between the same two people are then
+/// netted by [`normalize_debts`], leaving at most one debt per pair with the
+/// `expense_ids` of every expense between them.
+///
+/// # Arguments
+/// * `expenses` - Slice of expenses to convert
+/// * `settlements` - Payments already made between attendees
+///
+/// # Returns
+/// Debts sorted by debtor, then creditor
+pub fn expenses_to_debts(expenses: &[Expense], settlements: &[Settlement]) -> Vec<Debt> {
+    let mut debts = Vec::new();
+
+    for expense in expenses {
+        let split = split_expense_impl(expense);
+        for share in &split.shares {
+            if share.attendee_id == expense.payer_id || share.share_cents == 0 {
+                continue;
+            }
+
+            debts.push(Debt {
+                debtor: share.attendee_id.clone(),
+                creditor: expense.payer_id.clone(),
+                amount_cents: share.share_cents,
+                expense_ids: vec![expense.id.clone()],
+            });
+        }
+    }
+
+    // A settlement from A to B cancels part of what A owes B
+    for settlement in settlements {
+        debts.push(Debt {
+            debtor: settlement.to_id.clone(),
+            creditor: settlement.from_id.clone(),
+            amount_cents: settlement.amount_cents,
+            expense_ids: Vec::new(),
+        });
+    }
+
+    // Net opposing debts between each pair
+    normalize_debts(&debts)
+}
+
+/// Convert expenses and settlements into debts and simplify them.
+///
+/// # Arguments
+/// * `expenses` - Slice of expenses to settle
+/// * `settlements` - Payments already made between attendees
+///
+/// # Returns
+/// An ExpenseSettlement with the pairwise debts and the simplified plan
+pub fn settle_expenses(expenses: &[Expense], settlements: &[Settlement]) -> ExpenseSettlement {
+    let debts = expenses_to_debts(expenses, settlements);
+    let result = simplify_debts(&debts);
+
+    ExpenseSettlement { debts, result }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use finance_core::Cents;
+
+    fn make_expense(
+        id: &str,
+        payer_id: &str,
+        amount_cents: Cents,
+        participants: Vec<&str>,
+    ) -> Expense {
+        Expense {
+            id: id.to_string(),
+            payer_id: payer_id.to_string(),
+            amount_cents,
+            participants: participants.into_iter().map(String::from).collect(),
+            category: "test".to_string(),
+            description: "Test expense".to_string(),
+        }
+    }
+
+    fn settlement(from_id: &str, to_id: &str, amount_cents: Cents) -> Settlement {
+        Settlement {
+            from_id: from_id.to_string(),
+            to_id: to_id.to_string(),
+            amount_cents,
+        }
+    }
+
+    #[test]
+    fn test_single_expense_creates_debt_per_participant() {
+        let expenses = vec![make_expense("exp1", "A", 9000, vec!["A", "B", "C"])];
+
+        let debts = expenses_to_debts(&expenses, &[]);
+
+        assert_eq!(debts.len(), 2);
+        assert_eq!(debts[0].debtor, "B");
+        assert_eq!(debts[0].creditor, "A");
+        assert_eq!(debts[0].amount_cents, 3000);
+        assert_eq!(debts[0].expense_ids, vec!["exp1".to_string()]);
+        assert_eq!(debts[1].debtor, "C");
+    }
+
+    #[test]
+    fn test_opposing_expenses_are_netted() {
+        // A covers B for $50, B covers A for $20
+        let expenses = vec![
+            make_expense("exp1", "A", 10000, vec!["A", "B"]),
+            make_expense("exp2", "B", 4000, vec!["A", "B"]),
+        ];
+
+        let debts = expenses_to_debts(&expenses, &[]);
+
+        assert_eq!(debts.len(), 1);
+        assert_eq!(debts[0].debtor, "B");
+        assert_eq!(debts[0].creditor, "A");
+        assert_eq!(debts[0].amount_cents, 3000);
+        assert_eq!(
+            debts[0].expense_ids,
+            vec!["exp1".to_string(), "exp2".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_settlements_reduce_debts() {
+        let expenses = vec![make_expense("exp1", "A", 10000, vec!["A", "B"])];
+
+        let partial = expenses_to_debts(&expenses, &[settlement("B", "A", 2000)]);
+        assert_eq!(partial.len(), 1);
+        assert_eq!(partial[0].amount_cents, 3000);
+
+        let settled = expenses_to_debts(&expenses, &[settlement("B", "A", 5000)]);
+        assert!(settled.is_empty());
+    }
+
+    #[test]
+    fn test_settle_expenses_simplifies_chain() {
+        // B owes A, C owes B: C can pay A directly
+        let expenses = vec![
+            make_expense("exp1", "A", 2000, vec!["B"]),
+            make_expense("exp2", "B", 2000, vec!["C"]),
+        ];
+
+        let settlement = settle_expenses(&expenses, &[]);
+
+        assert_eq!(settlement.debts.len(), 2);
+        assert_eq!(settlement.result.payments.len(), 1);
+        assert_eq!(settlement.result.payments[0].from, "C");
+        assert_eq!(settlement.result.payments[0].to, "A");
+    }
+}
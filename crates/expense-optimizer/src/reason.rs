@@ -0,0 +1,314 @@
+//! Localized settlement reasons.
+//!
+//! This module fills `Payment::reason` from per-locale templates, formatting
+//! amounts exactly from integer cents. Reasons can use display names instead
+//! of person IDs and list the expenses that contributed most to a payment.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use finance_core::PersonId;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Debt, Payment};
+
+/// Language and number conventions for settlement reasons.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    /// English: `Settlement: Alice pays Bob $1,234.56`.
+    #[default]
+    En,
+    /// German: `Ausgleich: Alice zahlt Bob 1.234,56 €`.
+    De,
+}
+
+impl Locale {
+    /// Currency symbol used when none is configured.
+    fn default_symbol(self) -> &'static str {
+        match self {
+            Locale::En => "$",
+            Locale::De => "€",
+        }
+    }
+
+    /// Thousands separator and decimal separator.
+    fn separators(self) -> (char, char) {
+        match self {
+            Locale::En => (',', '.'),
+            Locale::De => ('.', ','),
+        }
+    }
+
+    /// Fill the reason template for this locale.
+    fn reason(self, from: &str, to: &str, amount: &str, expenses: &[&str]) -> String {
+        let (template, joiner) = match self {
+            Locale::En => (format!("Settlement: {} pays {} {}", from, to, amount), "for"),
+            Locale::De => (format!("Ausgleich: {} zahlt {} {}", from, to, amount), "für"),
+        };
+
+        if expenses.is_empty() {
+            template
+        } else {
+            format!("{} ({} {})", template, joiner, expenses.join(", "))
+        }
+    }
+}
+
+/// How settlement reasons are written.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReasonOptions {
+    /// Language and number conventions.
+    #[serde(default)]
+    pub locale: Locale,
+    /// Currency symbol; defaults to the locale's own (`$` or `€`).
+    #[serde(default)]
+    pub currency_symbol: Option<String>,
+    /// Names to show instead of person IDs.
+    #[serde(default)]
+    pub display_names: BTreeMap<PersonId, String>,
+    /// Labels to show instead of expense IDs, e.g. expense descriptions.
+    #[serde(default)]
+    pub expense_labels: BTreeMap<String, String>,
+    /// How many contributing expenses to list per payment; 0 lists none.
+    #[serde(default)]
+    pub top_expenses: usize,
+}
+
+/// Format cents as an exact currency amount for `locale`.
+///
+/// English puts the symbol first (`-$1,234.05`); German puts it last,
+/// separated by a space (`-1.234,05 €`).
+///
+/// # Arguments
+/// * `cents` - The amount in cents
+/// * `locale` - Number conventions to use
+/// * `symbol` - Currency symbol
+///
+/// # Returns
+/// The formatted amount
+pub fn format_amount(cents: i64, locale: Locale, symbol: &str) -> String {
+    format_wide_amount(i128::from(cents), locale, symbol)
+}
+
+/// [`format_amount`] for sums of amounts, which may not fit in an i64.
+pub(crate) fn format_wide_amount(cents: i128, locale: Locale, symbol: &str) -> String {
+    let (thousands, decimal) = locale.separators();
+    let sign = if cents < 0 { "-" } else { "" };
+    let abs = cents.unsigned_abs();
+
+    // Group the whole units in threes from the right
+    let units = (abs / 100).to_string();
+    let mut grouped = String::with_capacity(units.len() + units.len() / 3);
+    for (i, digit) in units.chars().enumerate() {
+        if i > 0 && (units.len() - i).is_multiple_of(3) {
+            grouped.push(thousands);
+        }
+        grouped.push(digit);
+    }
+    let number = format!("{}{}{:02}", grouped, decimal, abs % 100);
+
+    match locale {
+        Locale::En => format!("{}{}{}", sign, symbol, number),
+        Locale::De => format!("{}{} {}", sign, number, symbol),
+    }
+}
+
+/// Write the reason for a single payment.
+///
+/// Contributing expenses are taken from `debts` owed by the payer: expenses
+/// on debts directly to the payee come first, then the rest, each ranked by
+/// the amount of the debts that list them.
+///
+/// # Arguments
+/// * `payment` - The payment to describe
+/// * `debts` - The debts the plan settles, used to find contributing expenses
+/// * `options` - Locale, currency, and naming options
+///
+/// # Returns
+/// The localized reason
+pub fn settlement_reason(payment: &Payment, debts: &[Debt], options: &ReasonOptions) -> String {
+    let name = |id: &str| -> String {
+        options
+            .display_names
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    };
+    let symbol = options
+        .currency_symbol
+        .as_deref()
+        .unwrap_or(options.locale.default_symbol());
+    let amount = format_amount(payment.amount_cents, options.locale, symbol);
+
+    let expenses = top_expenses(payment, debts, options.top_expenses);
+    let labels: Vec<&str> = expenses
+        .iter()
+        .map(|id| options.expense_labels.get(*id).map_or(*id, String::as_str))
+        .collect();
+
+    options
+        .locale
+        .reason(&name(&payment.from), &name(&payment.to), &amount, &labels)
+}
+
+/// Rewrite the reason of every payment with [`settlement_reason`].
+///
+/// # Arguments
+/// * `payments` - The payments to describe
+/// * `debts` - The debts the plan settles, used to find contributing expenses
+/// * `options` - Locale, currency, and naming options
+///
+/// # Returns
+/// The payments with localized reasons, in the same order
+pub fn localize_reasons(
+    payments: &[Payment],
+    debts: &[Debt],
+    options: &ReasonOptions,
+) -> Vec<Payment> {
+    payments
+        .iter()
+        .map(|payment| Payment {
+            reason: settlement_reason(payment, debts, options),
+            ..payment.clone()
+        })
+        .collect()
+}
+
+/// The default English reason, used when building payments.
+pub(crate) fn default_reason(from: &str, to: &str, amount_cents: i64) -> String {
+    let amount = format_amount(amount_cents, Locale::En, Locale::En.default_symbol());
+    Locale::En.reason(from, to, &amount, &[])
+}
+
+/// Up to `limit` expense IDs that contributed most to `payment`.
+fn top_expenses<'a>(payment: &Payment, debts: &'a [Debt], limit: usize) -> Vec<&'a str> {
+    if limit == 0 {
+        return Vec::new();
+    }
+
+    // (direct to payee, total amount) per expense ID
+    let mut scores: BTreeMap<&str, (bool, i64)> = BTreeMap::new();
+    for debt in debts.iter().filter(|d| d.debtor == payment.from) {
+        let direct = debt.creditor == payment.to;
+        for id in &debt.expense_ids {
+            let score = scores.entry(id).or_default();
+            score.0 |= direct;
+            score.1 += debt.amount_cents;
+        }
+    }
+
+    let mut ranked: Vec<(&str, (bool, i64))> = scores.into_iter().collect();
+    // Stable sort keeps ties in expense ID order
+    ranked.sort_by_key(|&(_, score)| Reverse(score));
+    ranked.into_iter().take(limit).map(|(id, _)| id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64, ids: &[&str]) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: ids.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    fn payment(from: &str, to: &str, amount_cents: i64) -> Payment {
+        Payment {
+            from: from.to_string(),
+            to: to.to_string(),
+            amount_cents,
+            reason: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_format_amount_english() {
+        assert_eq!(format_amount(0, Locale::En, "$"), "$0.00");
+        assert_eq!(format_amount(5, Locale::En, "$"), "$0.05");
+        assert_eq!(format_amount(123456, Locale::En, "$"), "$1,234.56");
+        assert_eq!(format_amount(100000000, Locale::En, "£"), "£1,000,000.00");
+        assert_eq!(format_amount(-99999, Locale::En, "$"), "-$999.99");
+    }
+
+    #[test]
+    fn test_format_amount_german() {
+        assert_eq!(format_amount(5, Locale::De, "€"), "0,05 €");
+        assert_eq!(format_amount(123456, Locale::De, "€"), "1.234,56 €");
+        assert_eq!(format_amount(-100000, Locale::De, "€"), "-1.000,00 €");
+    }
+
+    #[test]
+    fn test_format_amount_is_exact_for_large_values() {
+        // Float formatting would lose the last cent here
+        assert_eq!(
+            format_amount(9_007_199_254_740_993, Locale::En, "$"),
+            "$90,071,992,547,409.93"
+        );
+    }
+
+    #[test]
+    fn test_default_reason() {
+        assert_eq!(
+            default_reason("A", "B", 123456),
+            "Settlement: A pays B $1,234.56"
+        );
+    }
+
+    #[test]
+    fn test_english_reason_with_names_and_expenses() {
+        let debts = vec![
+            debt("u1", "u2", 3000, &["taxi"]),
+            debt("u1", "u3", 9000, &["hotel"]),
+            debt("u1", "u2", 4500, &["dinner"]),
+        ];
+        let options = ReasonOptions {
+            display_names: BTreeMap::from([
+                ("u1".to_string(), "Alice".to_string()),
+                ("u2".to_string(), "Bob".to_string()),
+            ]),
+            expense_labels: BTreeMap::from([("dinner".to_string(), "Dinner".to_string())]),
+            top_expenses: 2,
+            ..ReasonOptions::default()
+        };
+
+        let reason = settlement_reason(&payment("u1", "u2", 7500), &debts, &options);
+
+        assert_eq!(reason, "Settlement: Alice pays Bob $75.00 (for Dinner, taxi)");
+    }
+
+    #[test]
+    fn test_german_reason_with_custom_symbol() {
+        let debts = vec![debt("A", "B", 120050, &["Miete"])];
+        let options = ReasonOptions {
+            locale: Locale::De,
+            currency_symbol: Some("CHF".to_string()),
+            top_expenses: 3,
+            ..ReasonOptions::default()
+        };
+
+        let reason = settlement_reason(&payment("A", "B", 120050), &debts, &options);
+
+        assert_eq!(reason, "Ausgleich: A zahlt B 1.200,50 CHF (für Miete)");
+    }
+
+    #[test]
+    fn test_localize_reasons_keeps_payments() {
+        let payments = vec![payment("A", "B", 100), payment("C", "D", 250)];
+        let options = ReasonOptions {
+            locale: Locale::De,
+            ..ReasonOptions::default()
+        };
+
+        let localized = localize_reasons(&payments, &[], &options);
+
+        assert_eq!(localized.len(), 2);
+        assert_eq!(localized[1].amount_cents, 250);
+        assert_eq!(localized[0].reason, "Ausgleich: A zahlt B 1,00 €");
+        assert_eq!(localized[1].reason, "Ausgleich: C zahlt D 2,50 €");
+    }
+}
@@ -0,0 +1,331 @@
+//! Installment schedules for settlement plans.
+//!
+//! This module spreads a person's settlement payments over several due dates
+//! and recomputes the remaining schedule as installments are paid.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use finance_core::PersonId;
+
+use crate::incremental::update_settlement_plan;
+use crate::simplify::{make_payment, settlement_metrics, simplify_debts};
+use crate::types::{Debt, Installment, InstallmentTerms, Payment, SettlementSchedule};
+
+/// Build an installment schedule for settling `debts`.
+///
+/// The overall plan is the minimal plan from [`simplify_debts`], updated with
+/// the payments already made via [`update_settlement_plan`] so that paying an
+/// installment never reshuffles the rest of the plan. Each payer's outstanding
+/// payments are then spread over their remaining due dates:
+/// 1. The payer's outstanding total is split evenly across the remaining
+///    dates, with leftover cents going to the earliest dates
+/// 2. Payments are assigned to dates in order, splitting a payment only
+///    where it crosses from one date to the next
+///
+/// Paying an installment uses up its due date, so installments to different
+/// creditors on the same date use it once between them. Any other payment,
+/// such as a partial one, uses up the payer's next due date. Payers without
+/// terms, or with no dates left, get a single installment due immediately.
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects to settle
+/// * `terms` - Due dates for people who pay in installments
+/// * `paid` - Payments already made towards the plan
+///
+/// # Returns
+/// A SettlementSchedule with the remaining installments
+pub fn schedule_settlements(
+    debts: &[Debt],
+    terms: &[InstallmentTerms],
+    paid: &[Payment],
+) -> SettlementSchedule {
+    let used = used_dates(debts, terms, paid);
+    build_schedule(debts, terms, paid, &used)
+}
+
+/// Work out which due dates the paid payments used up.
+///
+/// Payments are replayed in order. Each is looked up among its payer's
+/// installments from the schedule it was most likely paid from: the one
+/// the payer's earlier payments matched, or else the schedule as it stood
+/// just before the payment.
+fn used_dates(
+    debts: &[Debt],
+    terms: &[InstallmentTerms],
+    paid: &[Payment],
+) -> BTreeMap<PersonId, BTreeSet<String>> {
+    let mut used: BTreeMap<PersonId, BTreeSet<String>> = BTreeMap::new();
+    let mut open: BTreeMap<PersonId, Vec<Installment>> = BTreeMap::new();
+
+    for (k, payment) in paid.iter().enumerate() {
+        let matches = |i: &Installment| {
+            i.payment.to == payment.to && i.payment.amount_cents == payment.amount_cents
+        };
+        let installments = open.entry(payment.from.clone()).or_default();
+
+        let date = match installments.iter().position(matches) {
+            Some(i) => installments.remove(i).due_date,
+            None => {
+                *installments = build_schedule(debts, terms, &paid[..k], &used)
+                    .installments
+                    .into_iter()
+                    .filter(|i| i.payment.from == payment.from)
+                    .collect();
+                match installments.iter().position(matches) {
+                    Some(i) => installments.remove(i).due_date,
+                    None => installments.first().and_then(|i| i.due_date.clone()),
+                }
+            }
+        };
+
+        if let Some(date) = date {
+            used.entry(payment.from.clone()).or_default().insert(date);
+        }
+    }
+
+    used
+}
+
+/// Build the schedule for `paid`, given the due dates each payer has used.
+fn build_schedule(
+    debts: &[Debt],
+    terms: &[InstallmentTerms],
+    paid: &[Payment],
+    used: &BTreeMap<PersonId, BTreeSet<String>>,
+) -> SettlementSchedule {
+    let mut plan = update_settlement_plan(&simplify_debts(debts), paid, &[]).result;
+    plan.metrics = settlement_metrics(debts, &plan.payments);
+
+    // Outstanding payments are the plan minus those already made
+    let mut outstanding: Vec<Payment> = plan.payments.clone();
+    for payment in paid {
+        if let Some(pos) = outstanding.iter().position(|p| p == payment) {
+            outstanding.remove(pos);
+        }
+    }
+
+    let mut by_payer: BTreeMap<PersonId, Vec<Payment>> = BTreeMap::new();
+    for payment in &outstanding {
+        by_payer
+            .entry(payment.from.clone())
+            .or_default()
+            .push(payment.clone());
+    }
+
+    let mut installments = Vec::new();
+    for (payer, payments) in by_payer {
+        let used = used.get(&payer);
+        let dates: Vec<Option<String>> = terms
+            .iter()
+            .find(|t| t.person == payer)
+            .map(|t| {
+                t.dates
+                    .iter()
+                    .filter(|date| used.is_none_or(|used| !used.contains(*date)))
+                    .cloned()
+                    .map(Some)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let dates = if dates.is_empty() { vec![None] } else { dates };
+
+        installments.extend(split_over_dates(&payments, &dates));
+    }
+
+    SettlementSchedule {
+        paid_cents: paid.iter().map(|p| p.amount_cents).sum(),
+        remaining_cents: outstanding.iter().map(|p| p.amount_cents).sum(),
+        result: plan,
+        paid: paid.to_vec(),
+        installments,
+    }
+}
+
+/// Spread one payer's payments across `dates` with as few splits as possible.
+fn split_over_dates(payments: &[Payment], dates: &[Option<String>]) -> Vec<Installment> {
+    let total: i64 = payments.iter().map(|p| p.amount_cents).sum();
+    let slots = dates.len() as i64;
+
+    // Even budgets per date, leftover cents to the earliest dates; dates
+    // that would receive nothing are dropped
+    let budgets: Vec<(&Option<String>, i64)> = dates
+        .iter()
+        .enumerate()
+        .map(|(i, date)| (date, total / slots + i64::from((i as i64) < total % slots)))
+        .filter(|(_, budget)| *budget > 0)
+        .collect();
+    let count = budgets.len();
+
+    let mut installments = Vec::new();
+    let mut payments = payments.iter();
+    let mut current = payments.next();
+    let mut left = current.map(|p| p.amount_cents).unwrap_or(0);
+
+    for (number, (date, mut budget)) in budgets.into_iter().enumerate() {
+        while budget > 0 {
+            let Some(payment) = current else { break };
+            let amount = budget.min(left);
+
+            installments.push(Installment {
+                payment: make_payment(&payment.from, &payment.to, amount),
+                due_date: date.clone(),
+                number: number + 1,
+                count,
+            });
+
+            budget -= amount;
+            left -= amount;
+            if left == 0 {
+                current = payments.next();
+                left = current.map(|p| p.amount_cents).unwrap_or(0);
+            }
+        }
+    }
+
+    installments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: vec![],
+        }
+    }
+
+    fn terms(person: &str, dates: &[&str]) -> InstallmentTerms {
+        InstallmentTerms {
+            person: person.to_string(),
+            dates: dates.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_without_terms_everything_is_due_now() {
+        let debts = vec![debt("A", "B", 5000)];
+
+        let schedule = schedule_settlements(&debts, &[], &[]);
+
+        assert_eq!(schedule.installments.len(), 1);
+        assert_eq!(schedule.installments[0].due_date, None);
+        assert_eq!(schedule.installments[0].payment.amount_cents, 5000);
+        assert_eq!(schedule.remaining_cents, 5000);
+    }
+
+    #[test]
+    fn test_single_payment_split_evenly() {
+        let debts = vec![debt("A", "B", 10000)];
+        let terms = vec![terms("A", &["2026-01-01", "2026-02-01", "2026-03-01"])];
+
+        let schedule = schedule_settlements(&debts, &terms, &[]);
+
+        let amounts: Vec<i64> = schedule
+            .installments
+            .iter()
+            .map(|i| i.payment.amount_cents)
+            .collect();
+        assert_eq!(amounts, vec![3334, 3333, 3333]);
+        assert_eq!(
+            schedule.installments[2].due_date.as_deref(),
+            Some("2026-03-01")
+        );
+        assert!(schedule.installments.iter().all(|i| i.count == 3));
+    }
+
+    #[test]
+    fn test_multiple_creditors_split_only_at_boundaries() {
+        // A owes B $60 and C $40, over two dates of $50 each
+        let debts = vec![debt("A", "B", 6000), debt("A", "C", 4000)];
+        let terms = vec![terms("A", &["2026-01-01", "2026-02-01"])];
+
+        let schedule = schedule_settlements(&debts, &terms, &[]);
+
+        let parts: Vec<(&str, i64, usize)> = schedule
+            .installments
+            .iter()
+            .map(|i| (i.payment.to.as_str(), i.payment.amount_cents, i.number))
+            .collect();
+        assert_eq!(parts, vec![("B", 5000, 1), ("B", 1000, 2), ("C", 4000, 2)]);
+    }
+
+    #[test]
+    fn test_paid_installment_recomputes_remaining_schedule() {
+        let debts = vec![debt("A", "B", 9000)];
+        let terms = vec![terms("A", &["2026-01-01", "2026-02-01", "2026-03-01"])];
+
+        let first = schedule_settlements(&debts, &terms, &[]);
+        let paid = vec![first.installments[0].payment.clone()];
+        let schedule = schedule_settlements(&debts, &terms, &paid);
+
+        assert_eq!(schedule.paid_cents, 3000);
+        assert_eq!(schedule.remaining_cents, 6000);
+        assert_eq!(schedule.installments.len(), 2);
+        assert_eq!(
+            schedule.installments[0].due_date.as_deref(),
+            Some("2026-02-01")
+        );
+        assert!(schedule.result.payments.contains(&paid[0]));
+    }
+
+    #[test]
+    fn test_installments_on_one_date_use_it_once() {
+        // A owes B $30 and C $70 over three dates; both pay on the first
+        let debts = vec![debt("A", "B", 3000), debt("A", "C", 7000)];
+        let terms = vec![terms("A", &["2026-01-01", "2026-02-01", "2026-03-01"])];
+
+        let first = schedule_settlements(&debts, &terms, &[]);
+        let paid: Vec<Payment> = first
+            .installments
+            .iter()
+            .filter(|i| i.number == 1)
+            .map(|i| i.payment.clone())
+            .collect();
+        assert_eq!(paid.len(), 2);
+        let schedule = schedule_settlements(&debts, &terms, &paid);
+
+        let parts: Vec<(i64, Option<&str>, usize)> = schedule
+            .installments
+            .iter()
+            .map(|i| (i.payment.amount_cents, i.due_date.as_deref(), i.count))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![(3333, Some("2026-02-01"), 2), (3333, Some("2026-03-01"), 2)]
+        );
+    }
+
+    #[test]
+    fn test_partial_payment_spreads_remainder() {
+        // A pays $10 instead of the $30 first installment
+        let debts = vec![debt("A", "B", 9000)];
+        let terms = vec![terms("A", &["2026-01-01", "2026-02-01", "2026-03-01"])];
+        let paid = vec![make_payment("A", "B", 1000)];
+
+        let schedule = schedule_settlements(&debts, &terms, &paid);
+
+        let amounts: Vec<i64> = schedule
+            .installments
+            .iter()
+            .map(|i| i.payment.amount_cents)
+            .collect();
+        assert_eq!(amounts, vec![4000, 4000]);
+    }
+
+    #[test]
+    fn test_dates_exhausted_falls_back_to_due_now() {
+        let debts = vec![debt("A", "B", 9000)];
+        let terms = vec![terms("A", &["2026-01-01"])];
+        let paid = vec![make_payment("A", "B", 1000)];
+
+        let schedule = schedule_settlements(&debts, &terms, &paid);
+
+        assert_eq!(schedule.installments.len(), 1);
+        assert_eq!(schedule.installments[0].due_date, None);
+        assert_eq!(schedule.installments[0].payment.amount_cents, 8000);
+    }
+}
@@ -1,20 +1,33 @@
 //! Debt simplification algorithm.
 //!
 //! This module provides the core algorithm to minimize the number of transactions
-//! needed to settle debts between multiple parties.
+//! needed to settle debts between multiple parties, along with alternative
+//! objectives that minimize money moved along existing debts or the largest
+//! single payment.
 
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::balance::calculate_net_balances;
-use crate::types::{Debt, Payment, SimplificationResult};
+use crate::flow::FlowNetwork;
+use crate::intern::{IdTransfer, intern_debts};
+use crate::normalize::net_transfers;
+use crate::reason::default_reason;
+use crate::types::{
+    Debt, Objective, Payment, PaymentCount, SettlementMetrics, SimplificationResult,
+};
+use crate::validate::{SimplifyError, validate_debts};
+
+/// Largest number of unsettled participants solved exactly for
+/// [`Objective::MinTransactions`]. Beyond this the solver falls back to greedy
+/// matching, since the exact search is exponential in the participant count.
+const EXACT_PARTITION_LIMIT: usize = 16;
+
+/// A single transfer from `debtors[.0]` to `creditors[.1]` of `.2` cents.
+type Transfer = (usize, usize, i64);
 
 /// Simplify a list of debts into the minimum number of payments.
 ///
-/// This uses a greedy algorithm:
-/// 1. Calculate net balance for each person
-/// 2. Separate into creditors (positive balance) and debtors (negative balance)
-/// 3. Sort both lists by amount (descending by absolute value)
-/// 4. Greedily match largest debtor with largest creditor
+/// This is [`simplify_debts_with_objective`] with [`Objective::MinTransactions`].
 ///
 /// # Arguments
 /// * `debts` - Slice of Debt objects to simplify
@@ -22,6 +35,60 @@ use crate::types::{Debt, Payment, SimplificationResult};
 /// # Returns
 /// A SimplificationResult containing the optimized payments
 pub fn simplify_debts(debts: &[Debt]) -> SimplificationResult {
+    simplify_debts_with_objective(debts, Objective::MinTransactions)
+}
+
+/// Simplify a list of debts, optimizing the payments for `objective`.
+///
+/// All objectives work on net balances:
+/// 1. Net the debts between each pair of people (see
+///    [`normalize_debts`](crate::normalize_debts)),
+///    then calculate net balance for each person
+/// 2. Separate into creditors (positive balance) and debtors (negative balance)
+/// 3. Sort both lists by amount (descending by absolute value)
+/// 4. Solve the settlement for the requested objective:
+///    - `MinTransactions`: partition participants into the largest number of
+///      zero-sum groups (exact up to 16 participants, greedy beyond), then
+///      greedily match largest debtor with largest creditor within each group
+///    - `MinTotalTransferred`: min-cost flow along the netted debts from step
+///      1, so people only pay someone they owe and money is passed through
+///      intermediaries only where no shorter route exists
+///    - `MinMaxPayment`: binary search on a per-payment cap, checking each cap
+///      with a max-flow feasibility test
+///    - `MaxPaymentsPerPerson`: use the `MinTransactions` plan if it keeps
+///      everyone within the cap, otherwise greedily match while reserving
+///      each person's last allowed payment for one that settles them. This
+///      is a heuristic: it may use more payments than needed, or exceed the
+///      cap where an exact search would have found a plan within it
+///
+/// The result is fully deterministic: participants with equal balances are
+/// ordered by name, and payments are returned in the canonical order defined
+/// by [`sort_payments`].
+///
+/// # Complexity
+/// With `d` debts and `n` people who have a nonzero balance, split into `a`
+/// debtors and `b` creditors:
+/// - Pairwise netting and net balances: O(d log d), interning each name once
+///   and then sorting integer pairs rather than hashing strings per debt
+/// - `MinTransactions`: O(2^n · n) subset DP when n <= 16, otherwise
+///   O(n log n) greedy matching
+/// - `MinTotalTransferred`: min-cost flow over the `p` netted pairs, up to
+///   `p` augmentations each costing O(n · p)
+/// - `MinMaxPayment`: O(log max_balance) max-flow runs over `a · b` edges
+/// - `MaxPaymentsPerPerson`: the `MinTransactions` cost, plus O(n^2) per
+///   cap tried when that plan exceeds the cap
+///
+/// The default objective therefore handles groups of hundreds of people and
+/// tens of thousands of debts in milliseconds; see `benches/simplify.rs`.
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects to simplify
+/// * `objective` - The quantity to optimize the payments for
+///
+/// # Returns
+/// A SimplificationResult containing the optimized payments and the achieved
+/// objective value
+pub fn simplify_debts_with_objective(debts: &[Debt], objective: Objective) -> SimplificationResult {
     let original_count = debts.len();
 
     if debts.is_empty() {
@@ -29,57 +96,261 @@ pub fn simplify_debts(debts: &[Debt]) -> SimplificationResult {
             original_count: 0,
             optimized_count: 0,
             payments: Vec::new(),
-            savings_percent: 0.0,
+            metrics: SettlementMetrics::default(),
+            objective,
+            objective_value: 0,
+            payment_counts: Vec::new(),
         };
     }
 
-    // Step 1: Calculate net balance for each person
+    // Step 1: Net debts per pair, then calculate net balance for each person
     // Positive balance = person is owed money (creditor)
     // Negative balance = person owes money (debtor)
-    let balances: HashMap<String, i64> = calculate_net_balances(debts);
+    let interned = intern_debts(debts);
+    let netted = net_transfers(&interned.transfers);
+    let balances = interned.balances(&netted);
+
+    // Steps 2-4: Settle the balances for the requested objective
+    let payments = match objective {
+        Objective::MinTotalTransferred => {
+            min_total_transferred(&interned.names, &balances, &netted)
+        }
+        _ => settle_interned(&interned.names, &balances, objective),
+    };
+    let optimized_count = payments.len();
+    let objective_value = objective_value(objective, &payments);
+    let payment_counts = payment_counts(&payments);
+    let metrics = measure_plan(interned.distinct_pairs(), debts, &payments);
+
+    SimplificationResult {
+        original_count,
+        optimized_count,
+        payments,
+        metrics,
+        objective,
+        objective_value,
+        payment_counts,
+    }
+}
+
+/// Validate debts, then simplify them into the minimum number of payments.
+///
+/// This is [`simplify_debts`] for untrusted input: malformed debts are
+/// rejected by [`validate_debts`] instead of producing a nonsensical plan.
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects to simplify
+///
+/// # Returns
+/// A SimplificationResult, or the SimplifyError for the first invalid debt
+pub fn try_simplify_debts(debts: &[Debt]) -> Result<SimplificationResult, SimplifyError> {
+    try_simplify_debts_with_objective(debts, Objective::MinTransactions)
+}
+
+/// Validate debts, then simplify them for `objective`.
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects to simplify
+/// * `objective` - The quantity to optimize the payments for
+///
+/// # Returns
+/// A SimplificationResult, or the SimplifyError for the first invalid debt
+pub fn try_simplify_debts_with_objective(
+    debts: &[Debt],
+    objective: Objective,
+) -> Result<SimplificationResult, SimplifyError> {
+    validate_debts(debts)?;
+    Ok(simplify_debts_with_objective(debts, objective))
+}
 
+/// Settle a set of net balances with payments optimized for `objective`.
+///
+/// Balances must sum to zero. Settled participants (zero balance) are ignored.
+/// There are no debts to route along, so `MinTotalTransferred` settles like
+/// `MinTransactions`.
+pub(crate) fn settle_balances(
+    balances: HashMap<String, i64>,
+    objective: Objective,
+) -> Vec<Payment> {
+    let (names, amounts): (Vec<&str>, Vec<i64>) = balances
+        .iter()
+        .map(|(name, balance)| (name.as_str(), *balance))
+        .unzip();
+    settle_interned(&names, &amounts, objective)
+}
+
+/// Settle balances indexed by interned participant.
+///
+/// `names[i]` is the participant whose net balance is `balances[i]`. Names
+/// are only read to break ties and to build the final payments.
+fn settle_interned(names: &[&str], balances: &[i64], objective: Objective) -> Vec<Payment> {
     // Step 2: Separate into creditors and debtors
-    let mut creditors: Vec<(String, i64)> = Vec::new();
-    let mut debtors: Vec<(String, i64)> = Vec::new();
+    let mut creditors: Vec<usize> = Vec::new();
+    let mut debtors: Vec<usize> = Vec::new();
 
-    for (person, balance) in balances {
+    for (person, &balance) in balances.iter().enumerate() {
         if balance > 0 {
-            creditors.push((person, balance));
+            creditors.push(person);
         } else if balance < 0 {
-            debtors.push((person, -balance)); // Store as positive amount
+            debtors.push(person);
         }
         // If balance is 0, person is settled and can be ignored
     }
 
-    // Step 3: Sort both lists by amount descending
-    creditors.sort_by(|a, b| b.1.cmp(&a.1));
-    debtors.sort_by(|a, b| b.1.cmp(&a.1));
+    // Step 3: Sort both lists by amount descending, ties broken by name so
+    // the result never depends on hash map iteration order
+    let by_amount = |a: &usize, b: &usize| {
+        balances[*b]
+            .abs()
+            .cmp(&balances[*a].abs())
+            .then_with(|| names[*a].cmp(names[*b]))
+    };
+    creditors.sort_unstable_by(by_amount);
+    debtors.sort_unstable_by(by_amount);
+
+    // Step 4: Solve for the requested objective
+    let debtor_amounts: Vec<i64> = debtors.iter().map(|&d| -balances[d]).collect();
+    let creditor_amounts: Vec<i64> = creditors.iter().map(|&c| balances[c]).collect();
+
+    let transfers = match objective {
+        // Any plan between debtors and creditors moves the same total, so
+        // the fewest payments is also the least money moved
+        Objective::MinTransactions | Objective::MinTotalTransferred => {
+            min_transactions(&debtor_amounts, &creditor_amounts)
+        }
+        Objective::MinMaxPayment => min_max_payment(&debtor_amounts, &creditor_amounts),
+        Objective::MaxPaymentsPerPerson { cap } => {
+            capped_payments(&debtor_amounts, &creditor_amounts, cap)
+        }
+    };
+
+    let mut payments: Vec<Payment> = transfers
+        .into_iter()
+        .map(|(debtor, creditor, amount)| {
+            make_payment(names[debtors[debtor]], names[creditors[creditor]], amount)
+        })
+        .collect();
+    sort_payments(&mut payments);
+    payments
+}
+
+/// Sort payments into canonical order.
+///
+/// Payments are ordered by payer, then payee (byte-wise string comparison),
+/// then by amount descending. Every plan returned by this crate uses this
+/// order, so identical inputs always serialize identically.
+pub fn sort_payments(payments: &mut [Payment]) {
+    payments.sort_by(|a, b| {
+        a.from
+            .cmp(&b.from)
+            .then_with(|| a.to.cmp(&b.to))
+            .then_with(|| b.amount_cents.cmp(&a.amount_cents))
+    });
+}
+
+/// The achieved value of `objective` for a list of payments.
+pub(crate) fn objective_value(objective: Objective, payments: &[Payment]) -> i64 {
+    match objective {
+        Objective::MinTransactions => payments.len() as i64,
+        Objective::MinTotalTransferred => payments.iter().map(|p| p.amount_cents).sum(),
+        Objective::MinMaxPayment => payments.iter().map(|p| p.amount_cents).max().unwrap_or(0),
+        Objective::MaxPaymentsPerPerson { .. } => payment_counts(payments)
+            .iter()
+            .map(|c| (c.sent + c.received) as i64)
+            .max()
+            .unwrap_or(0),
+    }
+}
+
+/// Count the payments each person sends and receives, sorted by person.
+pub(crate) fn payment_counts(payments: &[Payment]) -> Vec<PaymentCount> {
+    let mut counts: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for payment in payments {
+        counts.entry(&payment.from).or_default().0 += 1;
+        counts.entry(&payment.to).or_default().1 += 1;
+    }
+
+    counts
+        .into_iter()
+        .map(|(person, (sent, received))| PaymentCount {
+            person: person.to_string(),
+            sent,
+            received,
+        })
+        .collect()
+}
+
+/// Measure a plan against the debts it settles.
+pub(crate) fn settlement_metrics(debts: &[Debt], payments: &[Payment]) -> SettlementMetrics {
+    measure_plan(intern_debts(debts).distinct_pairs(), debts, payments)
+}
+
+/// Measure a plan, given the number of distinct pairs among `debts`.
+fn measure_plan(pairs_before: usize, debts: &[Debt], payments: &[Payment]) -> SettlementMetrics {
+    let pairs_after: HashSet<(&str, &str)> = payments
+        .iter()
+        .map(|p| (p.from.as_str(), p.to.as_str()))
+        .collect();
+
+    let transactions_saved = pairs_before as i64 - payments.len() as i64;
+    let savings_bps = if pairs_before == 0 {
+        0
+    } else {
+        transactions_saved * 10_000 / pairs_before as i64
+    };
+
+    SettlementMetrics {
+        pairs_before,
+        pairs_after: pairs_after.len(),
+        cents_before: saturating_total(debts.iter().map(|d| d.amount_cents)),
+        cents_after: saturating_total(payments.iter().map(|p| p.amount_cents)),
+        transactions_saved,
+        savings_bps,
+    }
+}
+
+/// Sum amounts in i128, saturating at the i64 bounds.
+///
+/// Validated debts always fit; the infallible entry points also measure
+/// unvalidated input, whose totals may not.
+fn saturating_total(amounts: impl Iterator<Item = i64>) -> i64 {
+    let total: i128 = amounts.map(i128::from).sum();
+    total.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+}
 
-    // Step 4: Greedy matching
-    let mut payments: Vec<Payment> = Vec::new();
+/// Build a payment with the default English settlement reason.
+///
+/// Use [`localize_reasons`](crate::localize_reasons) to rewrite reasons for
+/// another locale or with display names.
+pub(crate) fn make_payment(from: &str, to: &str, amount_cents: i64) -> Payment {
+    Payment {
+        from: from.to_string(),
+        to: to.to_string(),
+        amount_cents,
+        reason: default_reason(from, to, amount_cents),
+    }
+}
+
+/// Greedily match the largest debtor with the largest creditor.
+///
+/// Both inputs are `(index, amount)` pairs sorted by amount descending, and
+/// the returned transfers refer to those indices.
+fn greedy_match(debtors: &[(usize, i64)], creditors: &[(usize, i64)]) -> Vec<Transfer> {
+    let mut debtors = debtors.to_vec();
+    let mut creditors = creditors.to_vec();
+    let mut transfers = Vec::new();
     let mut creditor_idx = 0;
     let mut debtor_idx = 0;
 
     while creditor_idx < creditors.len() && debtor_idx < debtors.len() {
-        let (creditor_name, creditor_amount) = &mut creditors[creditor_idx];
-        let (debtor_name, debtor_amount) = &mut debtors[debtor_idx];
+        let (creditor, creditor_amount) = &mut creditors[creditor_idx];
+        let (debtor, debtor_amount) = &mut debtors[debtor_idx];
 
         // Transfer the minimum of what's owed and what's due
         let transfer_amount = (*creditor_amount).min(*debtor_amount);
 
         if transfer_amount > 0 {
-            payments.push(Payment {
-                from: debtor_name.clone(),
-                to: creditor_name.clone(),
-                amount_cents: transfer_amount,
-                reason: format!(
-                    "Settlement: {} pays {} ${:.2}",
-                    debtor_name,
-                    creditor_name,
-                    transfer_amount as f64 / 100.0
-                ),
-            });
-
+            transfers.push((*debtor, *creditor, transfer_amount));
             *creditor_amount -= transfer_amount;
             *debtor_amount -= transfer_amount;
         }
@@ -93,26 +364,313 @@ pub fn simplify_debts(debts: &[Debt]) -> SimplificationResult {
         }
     }
 
-    let optimized_count = payments.len();
+    transfers
+}
 
-    // Calculate savings percentage
-    let savings_percent = if original_count > 0 {
-        ((original_count as f64 - optimized_count as f64) / original_count as f64) * 100.0
-    } else {
-        0.0
+/// Settle with as few transfers as possible.
+///
+/// A group of `k` participants whose balances sum to zero can always settle
+/// with `k - 1` transfers, so the minimum number of transfers is the number of
+/// participants minus the largest number of disjoint zero-sum groups. The
+/// groups are found with a subset dynamic program when small enough.
+fn min_transactions(debtors: &[i64], creditors: &[i64]) -> Vec<Transfer> {
+    let indexed =
+        |amounts: &[i64]| -> Vec<(usize, i64)> { amounts.iter().copied().enumerate().collect() };
+
+    let n = debtors.len() + creditors.len();
+    if n > EXACT_PARTITION_LIMIT {
+        return greedy_match(&indexed(debtors), &indexed(creditors));
+    }
+
+    // Signed balances: debtors first, then creditors
+    let values: Vec<i128> = debtors
+        .iter()
+        .map(|&d| -(d as i128))
+        .chain(creditors.iter().map(|&c| c as i128))
+        .collect();
+
+    let full = (1usize << n) - 1;
+    let mut sums = vec![0i128; full + 1];
+    let mut groups = vec![0u8; full + 1];
+
+    for mask in 1..=full {
+        let lowest = mask.trailing_zeros() as usize;
+        sums[mask] = sums[mask & (mask - 1)] + values[lowest];
+
+        let best = (0..n)
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| groups[mask ^ (1 << i)])
+            .max()
+            .unwrap_or(0);
+        groups[mask] = best + u8::from(sums[mask] == 0);
+    }
+
+    // Recover an insertion order whose zero prefix sums delimit the groups
+    let mut order = Vec::with_capacity(n);
+    let mut mask = full;
+    while mask != 0 {
+        let target = groups[mask] - u8::from(sums[mask] == 0);
+        let i = (0..n)
+            .find(|&i| mask & (1 << i) != 0 && groups[mask ^ (1 << i)] == target)
+            .expect("a predecessor state always exists");
+        order.push(i);
+        mask ^= 1 << i;
+    }
+    order.reverse();
+
+    let mut transfers = Vec::new();
+    let mut group_debtors = Vec::new();
+    let mut group_creditors = Vec::new();
+    let mut running = 0i128;
+
+    for i in order {
+        running += values[i];
+        if i < debtors.len() {
+            group_debtors.push((i, debtors[i]));
+        } else {
+            let j = i - debtors.len();
+            group_creditors.push((j, creditors[j]));
+        }
+
+        if running == 0 {
+            group_debtors.sort_by_key(|d| (Reverse(d.1), d.0));
+            group_creditors.sort_by_key(|c| (Reverse(c.1), c.0));
+            transfers.extend(greedy_match(&group_debtors, &group_creditors));
+            group_debtors.clear();
+            group_creditors.clear();
+        }
+    }
+
+    transfers
+}
+
+/// Build the bipartite settlement network.
+///
+/// Node 0 is the source, nodes `1..=d` are debtors, the next `c` nodes are
+/// creditors, and the last node is the sink. Every debtor-creditor edge has
+/// capacity `pair_cap(debtor, creditor)`.
+///
+/// # Returns
+/// The network, the sink node, and the edge id of each debtor-creditor pair
+fn settlement_network(
+    debtors: &[i64],
+    creditors: &[i64],
+    pair_cap: impl Fn(i64, i64) -> i64,
+) -> (FlowNetwork, usize, Vec<(usize, usize, usize)>) {
+    let sink = debtors.len() + creditors.len() + 1;
+    let mut network = FlowNetwork::new(sink + 1);
+    let mut pairs = Vec::with_capacity(debtors.len() * creditors.len());
+
+    for (i, &debt) in debtors.iter().enumerate() {
+        network.add_edge(0, 1 + i, debt, 0);
+    }
+    for (j, &credit) in creditors.iter().enumerate() {
+        network.add_edge(1 + debtors.len() + j, sink, credit, 0);
+    }
+    for (i, &debt) in debtors.iter().enumerate() {
+        for (j, &credit) in creditors.iter().enumerate() {
+            let id = network.add_edge(1 + i, 1 + debtors.len() + j, pair_cap(debt, credit), 0);
+            pairs.push((i, j, id));
+        }
+    }
+
+    (network, sink, pairs)
+}
+
+/// Read the transfers out of a solved settlement network.
+fn network_transfers(network: &FlowNetwork, pairs: &[(usize, usize, usize)]) -> Vec<Transfer> {
+    pairs
+        .iter()
+        .filter_map(|&(i, j, id)| {
+            let amount = network.flow(id);
+            (amount > 0).then_some((i, j, amount))
+        })
+        .collect()
+}
+
+/// Settle along the netted debts while moving the least total money, via
+/// min-cost flow.
+///
+/// Each route is a netted debt, and a person may only pay along their own
+/// routes, so money owed through a chain of people has to pass through it
+/// unless a shortcut exists. Every cent sent along a route costs one unit, so
+/// the cheapest flow takes the shortest routes. Node 0 is the source, each
+/// person is a node in name order, and the last node is the sink.
+fn min_total_transferred(names: &[&str], balances: &[i64], routes: &[IdTransfer]) -> Vec<Payment> {
+    // Numbering people by name keeps ties independent of input order
+    let mut order: Vec<usize> = (0..names.len()).collect();
+    order.sort_unstable_by_key(|&person| names[person]);
+    let mut node = vec![0; names.len()];
+    for (position, &person) in order.iter().enumerate() {
+        node[person] = 1 + position;
+    }
+
+    let sink = names.len() + 1;
+    let mut network = FlowNetwork::new(sink + 1);
+    for &person in &order {
+        let balance = balances[person];
+        if balance < 0 {
+            network.add_edge(0, node[person], -balance, 0);
+        } else if balance > 0 {
+            network.add_edge(node[person], sink, balance, 0);
+        }
+    }
+
+    // No route ever needs to carry more than everything that is owed
+    let total = balances
+        .iter()
+        .filter(|&&balance| balance > 0)
+        .fold(0i64, |total, &balance| total.saturating_add(balance));
+    let mut routes: Vec<(usize, usize)> = routes.iter().map(|&(from, to, _)| (from, to)).collect();
+    routes.sort_unstable_by_key(|&(from, to)| (names[from], names[to]));
+    let edges: Vec<(usize, usize, usize)> = routes
+        .into_iter()
+        .map(|(from, to)| (from, to, network.add_edge(node[from], node[to], total, 1)))
+        .collect();
+    network.min_cost_flow(0, sink);
+
+    let mut payments: Vec<Payment> = edges
+        .into_iter()
+        .filter_map(|(from, to, id)| {
+            let amount = network.flow(id);
+            (amount > 0).then(|| make_payment(names[from], names[to], amount))
+        })
+        .collect();
+    sort_payments(&mut payments);
+    payments
+}
+
+/// Settle while keeping the largest single payment as small as possible.
+///
+/// The smallest feasible cap is found by binary search, where a cap is
+/// feasible when a max flow with every payment limited to the cap settles
+/// all debts.
+fn min_max_payment(debtors: &[i64], creditors: &[i64]) -> Vec<Transfer> {
+    let total: i64 = debtors.iter().sum();
+    let solve = |cap: i64| {
+        let (mut network, sink, pairs) =
+            settlement_network(debtors, creditors, |debt, credit| debt.min(credit).min(cap));
+        let flow = network.max_flow(0, sink);
+        (flow, network, pairs)
     };
 
-    SimplificationResult {
-        original_count,
-        optimized_count,
-        payments,
-        savings_percent,
+    // No payment can exceed the largest balance; each debtor needs at least
+    // one cap-sized payment per creditor to clear their balance
+    let ceil_div = |amount: i64, parts: usize| {
+        let parts = parts.max(1) as i64;
+        amount / parts + i64::from(amount % parts != 0)
+    };
+    let mut low = 1;
+    let mut high = debtors.iter().chain(creditors).copied().max().unwrap_or(0);
+    for &debt in debtors {
+        low = low.max(ceil_div(debt, creditors.len()));
+    }
+    for &credit in creditors {
+        low = low.max(ceil_div(credit, debtors.len()));
+    }
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if solve(mid).0 == total {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    let (_, network, pairs) = solve(high);
+    network_transfers(&network, &pairs)
+}
+
+/// Settle with few payments while keeping everyone within `cap` payments,
+/// as far as a heuristic can.
+///
+/// The `MinTransactions` plan is used when it already fits. Otherwise
+/// [`capped_greedy`] is tried with `cap`, then `cap + 1`, and so on; once the
+/// cap reaches the participant count no limit applies and plain greedy
+/// matching is used. Greedy matching can miss plans within the cap, so the
+/// cap may be raised when it need not be, and the plan found may not have
+/// the fewest payments.
+fn capped_payments(debtors: &[i64], creditors: &[i64], cap: usize) -> Vec<Transfer> {
+    let plan = min_transactions(debtors, creditors);
+    if max_payments_per_person(&plan, debtors.len(), creditors.len()) <= cap {
+        return plan;
+    }
+
+    let unlimited = debtors.len() + creditors.len();
+    (cap.max(1)..unlimited)
+        .find_map(|cap| capped_greedy(debtors, creditors, cap))
+        .unwrap_or_else(|| {
+            let indexed = |amounts: &[i64]| -> Vec<(usize, i64)> {
+                amounts.iter().copied().enumerate().collect()
+            };
+            greedy_match(&indexed(debtors), &indexed(creditors))
+        })
+}
+
+/// The most transfers any single debtor or creditor takes part in.
+fn max_payments_per_person(transfers: &[Transfer], debtors: usize, creditors: usize) -> usize {
+    let mut sent = vec![0; debtors];
+    let mut received = vec![0; creditors];
+    for &(debtor, creditor, _) in transfers {
+        sent[debtor] += 1;
+        received[creditor] += 1;
+    }
+    sent.into_iter().chain(received).max().unwrap_or(0)
+}
+
+/// Greedy matching where nobody takes part in more than `cap` transfers.
+///
+/// The largest remaining debt is always settled next. A person on their last
+/// allowed transfer must be settled by it, so they are only paired with
+/// someone who can absorb their whole remaining balance. Otherwise the
+/// largest debtor pays the largest creditor, which spreads big balances
+/// across big counterparts instead of many small ones.
+///
+/// Returns `None` if the greedy choice runs out of valid pairings.
+fn capped_greedy(debtors: &[i64], creditors: &[i64], cap: usize) -> Option<Vec<Transfer>> {
+    let mut owes = debtors.to_vec();
+    let mut owed = creditors.to_vec();
+    let mut sent = vec![0; debtors.len()];
+    let mut received = vec![0; creditors.len()];
+    let mut transfers = Vec::new();
+
+    // Ties go to the lower index, which is the name order from settle_interned
+    while let Some(debtor) = (0..owes.len())
+        .filter(|&i| owes[i] > 0)
+        .max_by_key(|&i| (owes[i], Reverse(i)))
+    {
+        let remaining = owes[debtor];
+        let last = sent[debtor] + 1 >= cap;
+        let fits = |j: usize| {
+            owed[j] > 0
+                && (!last || owed[j] >= remaining)
+                && (received[j] + 1 < cap || remaining >= owed[j])
+        };
+
+        let candidates = (0..owed.len()).filter(|&j| fits(j));
+        let creditor = if last {
+            // Use the smallest creditor that covers the whole remainder
+            candidates.min_by_key(|&j| (owed[j], j))?
+        } else {
+            candidates.max_by_key(|&j| (owed[j], Reverse(j)))?
+        };
+
+        let amount = remaining.min(owed[creditor]);
+        transfers.push((debtor, creditor, amount));
+        owes[debtor] -= amount;
+        owed[creditor] -= amount;
+        sent[debtor] += 1;
+        received[creditor] += 1;
     }
+
+    Some(transfers)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::balance::calculate_net_balances;
 
     #[test]
     fn test_empty_debts() {
@@ -122,7 +680,7 @@ mod tests {
         assert_eq!(result.original_count, 0);
         assert_eq!(result.optimized_count, 0);
         assert_eq!(result.payments.len(), 0);
-        assert_eq!(result.savings_percent, 0.0);
+        assert_eq!(result.metrics, SettlementMetrics::default());
     }
 
     #[test]
@@ -144,7 +702,8 @@ mod tests {
         assert_eq!(payment.from, "Alice");
         assert_eq!(payment.to, "Bob");
         assert_eq!(payment.amount_cents, 5000);
-        assert_eq!(result.savings_percent, 0.0);
+        assert_eq!(result.metrics.transactions_saved, 0);
+        assert_eq!(result.metrics.savings_bps, 0);
     }
 
     #[test]
@@ -177,7 +736,8 @@ mod tests {
         assert_eq!(result.original_count, 3);
         assert_eq!(result.optimized_count, 0);
         assert_eq!(result.payments.len(), 0);
-        assert_eq!(result.savings_percent, 100.0);
+        assert_eq!(result.metrics.savings_bps, 10_000);
+        assert_eq!(result.metrics.cents_after, 0);
     }
 
     #[test]
@@ -209,7 +769,28 @@ mod tests {
         assert_eq!(payment.from, "A");
         assert_eq!(payment.to, "C");
         assert_eq!(payment.amount_cents, 10000);
-        assert_eq!(result.savings_percent, 50.0);
+        assert_eq!(
+            result.metrics,
+            SettlementMetrics {
+                pairs_before: 2,
+                pairs_after: 1,
+                cents_before: 20000,
+                cents_after: 10000,
+                transactions_saved: 1,
+                savings_bps: 5000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_metrics_saturate_for_unvalidated_totals() {
+        let debts = vec![debt("A", "B", i64::MAX), debt("C", "D", i64::MAX)];
+        let payments = vec![make_payment("A", "B", i64::MAX), make_payment("C", "D", 1)];
+
+        let metrics = settlement_metrics(&debts, &payments);
+
+        assert_eq!(metrics.cents_before, i64::MAX);
+        assert_eq!(metrics.cents_after, i64::MAX);
     }
 
     #[test]
@@ -240,7 +821,10 @@ mod tests {
         assert_eq!(payment.from, "A");
         assert_eq!(payment.to, "B");
         assert_eq!(payment.amount_cents, 10000);
-        assert_eq!(result.savings_percent, 50.0);
+        // Duplicates between the same pair save no real transaction
+        assert_eq!(result.metrics.pairs_before, 1);
+        assert_eq!(result.metrics.transactions_saved, 0);
+        assert_eq!(result.metrics.savings_bps, 0);
     }
 
     #[test]
@@ -322,4 +906,273 @@ mod tests {
         let total_paid: i64 = result.payments.iter().map(|p| p.amount_cents).sum();
         assert_eq!(total_paid, 11000); // $80 + $30 = $110
     }
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn test_min_transactions_finds_zero_sum_groups() {
+        // Debtors A:40, B:30, C:30, creditors D:60, E:40
+        // Greedy needs 4 payments; {A, E} and {B, C, D} settle in 1 + 2
+        let debts = vec![
+            debt("A", "E", 4000),
+            debt("B", "D", 3000),
+            debt("C", "D", 3000),
+        ];
+
+        let result = simplify_debts(&debts);
+
+        assert_eq!(result.objective, Objective::MinTransactions);
+        assert_eq!(result.optimized_count, 3);
+        assert_eq!(result.objective_value, 3);
+    }
+
+    #[test]
+    fn test_min_transactions_beats_greedy() {
+        // Chains that net to debtors A:4000, B:3000, C:3000 and creditors
+        // D:6000, E:4000 without any direct pairing in the input
+        let debts = vec![
+            debt("A", "X", 4000),
+            debt("X", "E", 4000),
+            debt("B", "Y", 3000),
+            debt("C", "Y", 3000),
+            debt("Y", "D", 6000),
+        ];
+
+        let result = simplify_debts(&debts);
+
+        // Greedy would pair A with D first and need 4 payments
+        assert_eq!(result.optimized_count, 3);
+        let total: i64 = result.payments.iter().map(|p| p.amount_cents).sum();
+        assert_eq!(total, 10000);
+    }
+
+    fn pairs(result: &SimplificationResult) -> Vec<(&str, &str, i64)> {
+        result
+            .payments
+            .iter()
+            .map(|p| (p.from.as_str(), p.to.as_str(), p.amount_cents))
+            .collect()
+    }
+
+    #[test]
+    fn test_min_total_transferred_pays_only_along_debts() {
+        // Net: A -100, B -101, C +101, D +100
+        let debts = vec![debt("A", "C", 100), debt("B", "D", 100), debt("B", "C", 1)];
+
+        // Greedy pairs A with D, who A never owed
+        let greedy = simplify_debts(&debts);
+        assert_eq!(pairs(&greedy), vec![("A", "D", 100), ("B", "C", 101)]);
+
+        let result = simplify_debts_with_objective(&debts, Objective::MinTotalTransferred);
+        assert_eq!(result.objective, Objective::MinTotalTransferred);
+        assert_eq!(
+            pairs(&result),
+            vec![("A", "C", 100), ("B", "C", 1), ("B", "D", 100)]
+        );
+        assert_eq!(result.objective_value, 201);
+    }
+
+    #[test]
+    fn test_min_total_transferred_routes_through_chains_only_when_needed() {
+        // A can skip B by paying C directly; D has no route to F except via E
+        let debts = vec![
+            debt("A", "B", 10000),
+            debt("B", "C", 10000),
+            debt("A", "C", 100),
+            debt("D", "E", 5000),
+            debt("E", "F", 5000),
+        ];
+
+        let result = simplify_debts_with_objective(&debts, Objective::MinTotalTransferred);
+
+        assert_eq!(
+            pairs(&result),
+            vec![("A", "C", 10100), ("D", "E", 5000), ("E", "F", 5000)]
+        );
+        // Paying every debt as given would move 30100
+        assert_eq!(result.objective_value, 20100);
+    }
+
+    #[test]
+    fn test_min_max_payment_spreads_large_payments() {
+        // Net: A -6000, B -4000, C +5000, D +5000
+        let debts = vec![
+            debt("A", "C", 5000),
+            debt("A", "D", 1000),
+            debt("B", "D", 4000),
+        ];
+
+        let greedy = simplify_debts(&debts);
+        let result = simplify_debts_with_objective(&debts, Objective::MinMaxPayment);
+
+        let greedy_max = greedy
+            .payments
+            .iter()
+            .map(|p| p.amount_cents)
+            .max()
+            .unwrap();
+        assert_eq!(greedy_max, 5000);
+        assert_eq!(result.objective, Objective::MinMaxPayment);
+        assert_eq!(result.objective_value, 3000);
+
+        let balances = calculate_net_balances(&debts);
+        for (person, balance) in balances {
+            let received: i64 = result
+                .payments
+                .iter()
+                .filter(|p| p.to == person)
+                .map(|p| p.amount_cents)
+                .sum();
+            let sent: i64 = result
+                .payments
+                .iter()
+                .filter(|p| p.from == person)
+                .map(|p| p.amount_cents)
+                .sum();
+            assert_eq!(received - sent, balance, "{} must end settled", person);
+        }
+    }
+
+    #[test]
+    fn test_min_max_payment_near_i64_max() {
+        let debts = vec![debt("A", "C", i64::MAX - 1), debt("B", "C", 1)];
+
+        let result = try_simplify_debts_with_objective(&debts, Objective::MinMaxPayment).unwrap();
+        assert_eq!(result.objective_value, i64::MAX - 1);
+        assert_eq!(result.payments.len(), 2);
+    }
+
+    #[test]
+    fn test_objectives_on_circular_debts() {
+        let debts = vec![
+            debt("A", "B", 100),
+            debt("B", "C", 100),
+            debt("C", "A", 100),
+        ];
+
+        for objective in [
+            Objective::MinTransactions,
+            Objective::MinTotalTransferred,
+            Objective::MinMaxPayment,
+            Objective::MaxPaymentsPerPerson { cap: 1 },
+        ] {
+            let result = simplify_debts_with_objective(&debts, objective);
+            assert!(result.payments.is_empty());
+            assert_eq!(result.objective_value, 0);
+        }
+    }
+
+    #[test]
+    fn test_payment_cap_spreads_burden() {
+        // Net: A +30000, B +15000, C +15000, D1-D3 -10000, D4-D5 -15000.
+        // The minimal plan has A receive from D1, D2 and D3.
+        let debts = vec![
+            debt("D1", "A", 10000),
+            debt("D2", "A", 10000),
+            debt("D3", "A", 10000),
+            debt("D4", "B", 15000),
+            debt("D5", "C", 15000),
+        ];
+
+        let minimal = simplify_debts(&debts);
+        let capped =
+            simplify_debts_with_objective(&debts, Objective::MaxPaymentsPerPerson { cap: 2 });
+
+        assert_eq!(minimal.payments.len(), 5);
+        assert_eq!(
+            objective_value(
+                Objective::MaxPaymentsPerPerson { cap: 2 },
+                &minimal.payments
+            ),
+            3
+        );
+        assert_eq!(capped.objective_value, 2);
+        assert_eq!(capped.payments.len(), 6);
+        assert!(
+            capped
+                .payment_counts
+                .iter()
+                .all(|c| c.sent + c.received <= 2)
+        );
+
+        let mut balances = calculate_net_balances(&debts);
+        for payment in &capped.payments {
+            *balances.get_mut(&payment.from).unwrap() += payment.amount_cents;
+            *balances.get_mut(&payment.to).unwrap() -= payment.amount_cents;
+        }
+        assert!(balances.values().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_payment_cap_kept_when_minimal_plan_fits() {
+        let debts = vec![debt("A", "B", 10000), debt("C", "D", 5000)];
+
+        let capped =
+            simplify_debts_with_objective(&debts, Objective::MaxPaymentsPerPerson { cap: 1 });
+
+        assert_eq!(capped.payments, simplify_debts(&debts).payments);
+        assert_eq!(capped.objective_value, 1);
+    }
+
+    #[test]
+    fn test_payment_cap_relaxed_when_infeasible() {
+        // A must receive from four people no matter how payments are arranged
+        let debts = vec![
+            debt("B", "A", 100),
+            debt("C", "A", 100),
+            debt("D", "A", 100),
+            debt("E", "A", 100),
+        ];
+
+        let result =
+            simplify_debts_with_objective(&debts, Objective::MaxPaymentsPerPerson { cap: 2 });
+
+        assert_eq!(result.payments.len(), 4);
+        assert_eq!(result.objective_value, 4);
+    }
+
+    #[test]
+    fn test_try_simplify_rejects_invalid_debts() {
+        let debts = vec![debt("A", "B", 5000), debt("B", "B", 100)];
+
+        let error = try_simplify_debts(&debts).unwrap_err();
+
+        assert_eq!(error.index(), 1);
+        assert!(try_simplify_debts(&[debt("A", "B", 5000)]).is_ok());
+    }
+
+    #[test]
+    fn test_payment_counts_reported() {
+        let debts = vec![debt("A", "C", 5000), debt("B", "C", 3000)];
+
+        let result = simplify_debts(&debts);
+
+        assert_eq!(
+            result.payment_counts,
+            vec![
+                PaymentCount {
+                    person: "A".to_string(),
+                    sent: 1,
+                    received: 0,
+                },
+                PaymentCount {
+                    person: "B".to_string(),
+                    sent: 1,
+                    received: 0,
+                },
+                PaymentCount {
+                    person: "C".to_string(),
+                    sent: 0,
+                    received: 2,
+                },
+            ]
+        );
+    }
 }
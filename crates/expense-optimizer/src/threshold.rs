@@ -0,0 +1,490 @@
+//! Minimum payment thresholds for settlement plans.
+//!
+//! This module keeps tiny payments out of settlement plans. Amounts below the
+//! minimum are absorbed by a designated person, rolled into another payment,
+//! or rounded away, and every cent that is not paid is recorded as a
+//! [`Residue`] so the plan can be audited.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::balance::calculate_net_balances;
+use crate::simplify::{
+    make_payment, objective_value, payment_counts, settle_balances, settlement_metrics,
+    sort_payments,
+};
+use crate::types::{
+    Debt, MinimumPayment, MinimumPaymentResult, Objective, Payment, Residue, ResidueKind,
+    SimplificationResult, SmallDebtPolicy,
+};
+
+/// Simplify debts while avoiding payments below a minimum amount.
+///
+/// The policy decides how small amounts are handled:
+/// - `Absorb`: every other person whose net balance is below the minimum is
+///   settled against the designated person, whose own settlement grows or
+///   shrinks by that amount
+/// - `RollIntoPayment`: each payment below the minimum is merged with another
+///   payment by the same payer or to the same payee, which is rerouted so
+///   nobody's balance changes; a small payment with no such partner is kept
+///   and reported in `below_minimum`
+/// - `RoundToDollars`: every balance is rounded to whole dollars and the
+///   designated person takes on the rounding residue, so all payments are
+///   whole-dollar amounts; rounded balances still below the minimum are
+///   then absorbed by the same person
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects to simplify
+/// * `objective` - The quantity to optimize the payments for
+/// * `minimum` - The minimum payment amount and small-debt policy
+///
+/// # Returns
+/// A MinimumPaymentResult with the plan and a residue report that accounts
+/// for every unpaid cent
+pub fn simplify_debts_with_minimum(
+    debts: &[Debt],
+    objective: Objective,
+    minimum: &MinimumPayment,
+) -> MinimumPaymentResult {
+    let mut balances = calculate_net_balances(debts);
+    let mut residues: BTreeMap<(String, ResidueKind), i64> = BTreeMap::new();
+
+    match &minimum.policy {
+        SmallDebtPolicy::Absorb { person } => {
+            absorb_small_balances(&mut balances, person, minimum.min_cents, &mut residues)
+        }
+        SmallDebtPolicy::RoundToDollars { absorber } => {
+            round_balances(&mut balances, absorber, &mut residues);
+            // Whole-dollar balances keep the absorber's balance whole too
+            absorb_small_balances(&mut balances, absorber, minimum.min_cents, &mut residues)
+        }
+        SmallDebtPolicy::RollIntoPayment => {}
+    }
+
+    let mut payments = settle_balances(balances, objective);
+    if minimum.policy == SmallDebtPolicy::RollIntoPayment {
+        roll_into_payments(&mut payments, minimum.min_cents);
+    }
+
+    let below_minimum = payments
+        .iter()
+        .filter(|p| p.amount_cents < minimum.min_cents)
+        .cloned()
+        .collect();
+
+    let original_count = debts.len();
+    let optimized_count = payments.len();
+    let objective_value = objective_value(objective, &payments);
+    let payment_counts = payment_counts(&payments);
+    let metrics = settlement_metrics(debts, &payments);
+
+    MinimumPaymentResult {
+        result: SimplificationResult {
+            original_count,
+            optimized_count,
+            payments,
+            metrics,
+            objective,
+            objective_value,
+            payment_counts,
+        },
+        residues: residues
+            .into_iter()
+            .filter(|(_, amount)| *amount != 0)
+            .map(|((person, kind), amount_cents)| Residue {
+                person,
+                amount_cents,
+                kind,
+            })
+            .collect(),
+        below_minimum,
+    }
+}
+
+/// Add `amount` to the residue of `person` for `kind`.
+fn record(
+    residues: &mut BTreeMap<(String, ResidueKind), i64>,
+    person: &str,
+    kind: ResidueKind,
+    amount: i64,
+) {
+    *residues.entry((person.to_string(), kind)).or_insert(0) += amount;
+}
+
+/// Roll every payment below `min_cents` into another payment.
+///
+/// For a small payment from X to Y of `s` cents:
+/// - If X also pays Z `a >= min_cents`, X pays Y `a + s` instead and Y
+///   passes `a` on to Z
+/// - Otherwise, if W also pays Y `b >= min_cents`, W pays X `b` instead and
+///   X pays Y `b + s`
+///
+/// Either way every payment involved is at least the minimum and everyone's
+/// net balance is unchanged. A small payment between two people who trade
+/// with nobody else cannot be rolled and is left in place.
+///
+/// Refused payments are remembered by payer and payee, since merging moves
+/// payments around, and are tried again after each successful roll, which
+/// may have given them a payment to roll into.
+fn roll_into_payments(payments: &mut Vec<Payment>, min_cents: i64) {
+    sort_payments(payments);
+    let mut refused: Vec<(String, String)> = Vec::new();
+
+    while let Some(small) = payments.iter().position(|p| {
+        p.amount_cents < min_cents && !refused.iter().any(|(f, t)| *f == p.from && *t == p.to)
+    }) {
+        let (from, to, amount) = {
+            let p = &payments[small];
+            (p.from.clone(), p.to.clone(), p.amount_cents)
+        };
+        let large = |p: &Payment| p.amount_cents >= min_cents;
+
+        if let Some(other) = payments
+            .iter()
+            .position(|p| p.from == from && p.to != to && large(p))
+        {
+            let (next, forwarded) = (payments[other].to.clone(), payments[other].amount_cents);
+            payments[small] = make_payment(&from, &to, forwarded + amount);
+            payments[other] = make_payment(&to, &next, forwarded);
+        } else if let Some(other) = payments
+            .iter()
+            .position(|p| p.to == to && p.from != from && large(p))
+        {
+            let (payer, collected) = (payments[other].from.clone(), payments[other].amount_cents);
+            payments[small] = make_payment(&from, &to, collected + amount);
+            payments[other] = make_payment(&payer, &from, collected);
+        } else {
+            refused.push((from, to));
+            continue;
+        }
+
+        // Each roll leaves one small payment fewer, so retrying terminates
+        merge_duplicate_payments(payments);
+        refused.clear();
+    }
+}
+
+/// Merge payments between the same payer and payee into one.
+fn merge_duplicate_payments(payments: &mut Vec<Payment>) {
+    sort_payments(payments);
+    let mut merged: Vec<Payment> = Vec::with_capacity(payments.len());
+    for payment in payments.drain(..) {
+        match merged.last_mut() {
+            Some(last) if last.from == payment.from && last.to == payment.to => {
+                *last = make_payment(
+                    &last.from,
+                    &last.to,
+                    last.amount_cents + payment.amount_cents,
+                );
+            }
+            _ => merged.push(payment),
+        }
+    }
+    *payments = merged;
+}
+
+/// Move every small balance other than `absorber`'s onto `absorber`.
+fn absorb_small_balances(
+    balances: &mut HashMap<String, i64>,
+    absorber: &str,
+    min_cents: i64,
+    residues: &mut BTreeMap<(String, ResidueKind), i64>,
+) {
+    let mut absorbed = 0;
+
+    for (person, balance) in balances.iter_mut() {
+        if person != absorber && *balance != 0 && balance.abs() < min_cents {
+            record(residues, person, ResidueKind::Absorbed, *balance);
+            absorbed += *balance;
+            *balance = 0;
+        }
+    }
+
+    if absorbed != 0 {
+        *balances.entry(absorber.to_string()).or_insert(0) += absorbed;
+        record(residues, absorber, ResidueKind::Absorbed, -absorbed);
+    }
+}
+
+/// Round every balance to whole dollars, balancing the total on `absorber`.
+fn round_balances(
+    balances: &mut HashMap<String, i64>,
+    absorber: &str,
+    residues: &mut BTreeMap<(String, ResidueKind), i64>,
+) {
+    let mut rounded_total = 0;
+
+    for (person, balance) in balances.iter_mut() {
+        if person == absorber {
+            continue;
+        }
+        let rounded = round_to_dollars(*balance);
+        record(residues, person, ResidueKind::Rounding, *balance - rounded);
+        rounded_total += rounded;
+        *balance = rounded;
+    }
+
+    // The absorber settles whatever keeps the total at zero, which is
+    // itself a whole-dollar amount
+    let balance = balances.entry(absorber.to_string()).or_insert(0);
+    record(
+        residues,
+        absorber,
+        ResidueKind::Rounding,
+        *balance + rounded_total,
+    );
+    *balance = -rounded_total;
+}
+
+/// Round cents to the nearest whole dollar, with halves rounding away from zero.
+fn round_to_dollars(cents: i64) -> i64 {
+    // Rounding |i64::MIN| down to a whole dollar leaves it within i64
+    let rounded = ((cents.unsigned_abs() + 50) / 100 * 100) as i64;
+    if cents < 0 { -rounded } else { rounded }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: vec![],
+        }
+    }
+
+    /// Check that payments plus residues account for every balance exactly.
+    fn assert_balances_to_the_cent(debts: &[Debt], result: &MinimumPaymentResult) {
+        let mut balances = calculate_net_balances(debts);
+        for payment in &result.result.payments {
+            *balances.entry(payment.from.clone()).or_insert(0) += payment.amount_cents;
+            *balances.entry(payment.to.clone()).or_insert(0) -= payment.amount_cents;
+        }
+        for residue in &result.residues {
+            *balances.entry(residue.person.clone()).or_insert(0) -= residue.amount_cents;
+        }
+        for (person, balance) in balances {
+            assert_eq!(balance, 0, "{} is off by {} cents", person, balance);
+        }
+
+        let residue_total: i64 = result.residues.iter().map(|r| r.amount_cents).sum();
+        assert_eq!(residue_total, 0);
+    }
+
+    fn minimum(min_cents: i64, policy: SmallDebtPolicy) -> MinimumPayment {
+        MinimumPayment { min_cents, policy }
+    }
+
+    #[test]
+    fn test_absorb_writes_off_small_balance() {
+        let debts = vec![debt("A", "Organizer", 5000), debt("B", "Organizer", 37)];
+        let policy = SmallDebtPolicy::Absorb {
+            person: "Organizer".to_string(),
+        };
+
+        let result =
+            simplify_debts_with_minimum(&debts, Objective::MinTransactions, &minimum(100, policy));
+
+        assert_eq!(result.result.payments.len(), 1);
+        assert_eq!(result.result.payments[0].from, "A");
+        assert!(result.below_minimum.is_empty());
+        assert!(result.residues.contains(&Residue {
+            person: "B".to_string(),
+            amount_cents: -37,
+            kind: ResidueKind::Absorbed,
+        }));
+        assert_balances_to_the_cent(&debts, &result);
+    }
+
+    fn pairs(result: &MinimumPaymentResult) -> Vec<(&str, &str, i64)> {
+        result
+            .result
+            .payments
+            .iter()
+            .map(|p| (p.from.as_str(), p.to.as_str(), p.amount_cents))
+            .collect()
+    }
+
+    #[test]
+    fn test_roll_into_payment_by_same_payer() {
+        // Greedy pays A->C 1000, B->C 300 and B->D 700
+        let debts = vec![
+            debt("A", "C", 1000),
+            debt("B", "C", 300),
+            debt("B", "D", 700),
+        ];
+
+        let result = simplify_debts_with_minimum(
+            &debts,
+            Objective::MinTransactions,
+            &minimum(500, SmallDebtPolicy::RollIntoPayment),
+        );
+
+        // B pays C both amounts and C passes D's share on
+        assert_eq!(
+            pairs(&result),
+            vec![("A", "C", 1000), ("B", "C", 1000), ("C", "D", 700)]
+        );
+        assert!(result.residues.is_empty());
+        assert!(result.below_minimum.is_empty());
+        assert_balances_to_the_cent(&debts, &result);
+    }
+
+    #[test]
+    fn test_roll_into_payment_to_same_payee() {
+        let debts = vec![debt("A", "C", 5000), debt("B", "C", 37)];
+
+        let result = simplify_debts_with_minimum(
+            &debts,
+            Objective::MinTransactions,
+            &minimum(100, SmallDebtPolicy::RollIntoPayment),
+        );
+
+        // A's payment is collected by B, who pays C once
+        assert_eq!(pairs(&result), vec![("A", "B", 5000), ("B", "C", 5037)]);
+        assert!(result.residues.is_empty());
+        assert_balances_to_the_cent(&debts, &result);
+    }
+
+    #[test]
+    fn test_roll_into_payment_reports_isolated_small_payments() {
+        let debts = vec![debt("A", "B", 5000), debt("C", "D", 37)];
+
+        let result = simplify_debts_with_minimum(
+            &debts,
+            Objective::MinTransactions,
+            &minimum(100, SmallDebtPolicy::RollIntoPayment),
+        );
+
+        assert_eq!(result.result.payments.len(), 2);
+        assert_eq!(result.below_minimum[0].from, "C");
+        assert!(result.residues.is_empty());
+        assert_balances_to_the_cent(&debts, &result);
+    }
+
+    #[test]
+    fn test_roll_retries_payments_refused_earlier() {
+        let net = |payments: &[Payment]| {
+            let mut balances: BTreeMap<String, i64> = BTreeMap::new();
+            for p in payments {
+                *balances.entry(p.from.clone()).or_insert(0) -= p.amount_cents;
+                *balances.entry(p.to.clone()).or_insert(0) += p.amount_cents;
+            }
+            balances
+        };
+        let mut payments = vec![
+            make_payment("B", "C", 100),
+            make_payment("C", "A", 20),
+            make_payment("D", "A", 20),
+            make_payment("D", "C", 20),
+            make_payment("D", "E", 100),
+            make_payment("E", "C", 10),
+        ];
+        let before = net(&payments);
+
+        roll_into_payments(&mut payments, 100);
+
+        // E's payment has nothing to roll into until B is rerouted to pay E
+        assert!(
+            payments.iter().all(|p| p.amount_cents >= 100),
+            "{:?}",
+            payments
+        );
+        assert_eq!(net(&payments), before);
+    }
+
+    #[test]
+    fn test_round_to_dollars_produces_whole_dollar_payments() {
+        let debts = vec![
+            debt("A", "Organizer", 1249),
+            debt("B", "Organizer", 2051),
+            debt("C", "Organizer", 3350),
+        ];
+        let policy = SmallDebtPolicy::RoundToDollars {
+            absorber: "Organizer".to_string(),
+        };
+
+        let result =
+            simplify_debts_with_minimum(&debts, Objective::MinTransactions, &minimum(100, policy));
+
+        assert!(
+            result
+                .result
+                .payments
+                .iter()
+                .all(|p| p.amount_cents % 100 == 0)
+        );
+        let total: i64 = result.result.payments.iter().map(|p| p.amount_cents).sum();
+        assert_eq!(total, 1200 + 2100 + 3400);
+        assert_balances_to_the_cent(&debts, &result);
+    }
+
+    #[test]
+    fn test_absorber_as_party_clears_the_payment() {
+        let debts = vec![debt("A", "B", 50)];
+        let policy = SmallDebtPolicy::Absorb {
+            person: "B".to_string(),
+        };
+
+        let result =
+            simplify_debts_with_minimum(&debts, Objective::MinTransactions, &minimum(100, policy));
+
+        assert!(result.result.payments.is_empty());
+        assert_balances_to_the_cent(&debts, &result);
+    }
+
+    #[test]
+    fn test_round_to_dollars_applies_minimum_after_rounding() {
+        // B's $2.49 rounds to $2.00, still below a $5.00 minimum, so the
+        // organizer absorbs it
+        let debts = vec![debt("A", "Organizer", 5000), debt("B", "Organizer", 249)];
+        let policy = SmallDebtPolicy::RoundToDollars {
+            absorber: "Organizer".to_string(),
+        };
+
+        let result =
+            simplify_debts_with_minimum(&debts, Objective::MinTransactions, &minimum(500, policy));
+
+        assert_eq!(pairs(&result), vec![("A", "Organizer", 5000)]);
+        assert!(result.below_minimum.is_empty());
+        assert!(result.residues.contains(&Residue {
+            person: "B".to_string(),
+            amount_cents: -200,
+            kind: ResidueKind::Absorbed,
+        }));
+        assert_balances_to_the_cent(&debts, &result);
+    }
+
+    #[test]
+    fn test_payment_still_below_minimum_is_reported() {
+        // Whole-dollar balances A -10, B -10, C +13, D +7 settle with a $3
+        // payment from B to C
+        let debts = vec![
+            debt("A", "C", 1000),
+            debt("B", "C", 300),
+            debt("B", "D", 700),
+        ];
+        let policy = SmallDebtPolicy::RoundToDollars {
+            absorber: "E".to_string(),
+        };
+
+        let result =
+            simplify_debts_with_minimum(&debts, Objective::MinTransactions, &minimum(500, policy));
+
+        assert_eq!(result.below_minimum.len(), 1);
+        assert_eq!(result.below_minimum[0].amount_cents, 300);
+        assert_balances_to_the_cent(&debts, &result);
+    }
+
+    #[test]
+    fn test_round_half_away_from_zero() {
+        assert_eq!(round_to_dollars(150), 200);
+        assert_eq!(round_to_dollars(149), 100);
+        assert_eq!(round_to_dollars(-150), -200);
+        assert_eq!(round_to_dollars(-49), 0);
+        assert_eq!(round_to_dollars(i64::MAX), 9_223_372_036_854_775_800);
+        assert_eq!(round_to_dollars(i64::MIN), -9_223_372_036_854_775_800);
+    }
+}
@@ -2,15 +2,16 @@
 //!
 //! This module contains the core data structures used for debt simplification.
 
+use finance_core::PersonId;
 use serde::{Deserialize, Serialize};
 
 /// Represents a debt from one person to another.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Debt {
     /// The person who owes money.
-    pub debtor: String,
+    pub debtor: PersonId,
     /// The person who is owed money.
-    pub creditor: String,
+    pub creditor: PersonId,
     /// The amount owed in cents (to avoid floating point issues).
     pub amount_cents: i64,
     /// IDs of expenses that contributed to this debt.
@@ -21,15 +22,39 @@ pub struct Debt {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Payment {
     /// The person making the payment.
-    pub from: String,
+    pub from: PersonId,
     /// The person receiving the payment.
-    pub to: String,
+    pub to: PersonId,
     /// The amount to pay in cents.
     pub amount_cents: i64,
     /// Human-readable reason for the payment.
     pub reason: String,
 }
 
+/// The quantity a settlement plan is optimized for.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    /// Minimize the number of payments.
+    #[default]
+    MinTransactions,
+    /// Minimize the total cents transferred, paying only along existing
+    /// debts after pairwise netting. Money is routed through intermediaries
+    /// only where no shorter route exists.
+    MinTotalTransferred,
+    /// Minimize the largest single payment.
+    MinMaxPayment,
+    /// Keep the number of payments low while no person sends or receives
+    /// more than `cap` of them, on a best-effort basis. The plan is found
+    /// heuristically, so it need not have the fewest payments possible, and
+    /// when the heuristic finds no plan within the cap, the cap is raised one
+    /// payment at a time until it does, even if some plan would have fit.
+    MaxPaymentsPerPerson {
+        /// Most payments any one person should send or receive.
+        cap: usize,
+    },
+}
+
 /// Result of the debt simplification algorithm.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SimplificationResult {
@@ -39,6 +64,249 @@ pub struct SimplificationResult {
     pub optimized_count: usize,
     /// The simplified list of payments.
     pub payments: Vec<Payment>,
-    /// Percentage reduction in number of transactions.
-    pub savings_percent: f64,
+    /// Exact before/after measurements of the plan.
+    pub metrics: SettlementMetrics,
+    /// The objective the payments were optimized for.
+    pub objective: Objective,
+    /// The achieved value of the objective: the payment count, the total
+    /// cents transferred, the largest payment in cents, or the most payments
+    /// any one person is part of, respectively.
+    pub objective_value: i64,
+    /// How many payments each person sends and receives, sorted by person.
+    #[serde(default)]
+    pub payment_counts: Vec<PaymentCount>,
+}
+
+/// Exact measurements comparing the original debts with a settlement plan.
+///
+/// All values are integers so results are identical on every platform.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SettlementMetrics {
+    /// Distinct (debtor, creditor) pairs among the original debts, which is
+    /// the number of transfers needed to pay them without simplification.
+    pub pairs_before: usize,
+    /// Distinct (from, to) pairs among the plan's payments.
+    pub pairs_after: usize,
+    /// Total cents across the original debts, saturating at `i64::MAX` for
+    /// debts that would fail validation.
+    pub cents_before: i64,
+    /// Total cents moved by the plan's payments, saturating like
+    /// `cents_before`.
+    pub cents_after: i64,
+    /// `pairs_before` minus the number of payments; negative if the plan
+    /// needs more payments than paying each pair directly.
+    pub transactions_saved: i64,
+    /// `transactions_saved` as a share of `pairs_before` in basis points
+    /// (1/100 of a percent), rounded toward zero.
+    pub savings_bps: i64,
+}
+
+/// Number of payments one person sends and receives in a plan.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PaymentCount {
+    /// The person's ID.
+    pub person: PersonId,
+    /// Payments this person sends.
+    pub sent: usize,
+    /// Payments this person receives.
+    pub received: usize,
+}
+
+/// A payment whose amount changed between two settlement plans.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PaymentChange {
+    /// The payment as it appeared in the previous plan.
+    pub before: Payment,
+    /// The payment as it appears in the updated plan.
+    pub after: Payment,
+}
+
+/// Differences between a previous settlement plan and its replacement.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PlanDiff {
+    /// Payments that are new in the updated plan.
+    pub added: Vec<Payment>,
+    /// Payments from the previous plan that are no longer needed.
+    pub removed: Vec<Payment>,
+    /// Payments kept between the same people with a different amount.
+    pub changed: Vec<PaymentChange>,
+}
+
+/// Result of incrementally updating a settlement plan.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlanUpdate {
+    /// The updated plan, including all locked payments.
+    pub result: SimplificationResult,
+    /// What changed relative to the previous plan.
+    pub diff: PlanDiff,
+}
+
+/// How balances and payments below the minimum payment amount are handled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SmallDebtPolicy {
+    /// Balances below the minimum are written off against a designated person,
+    /// who receives that much less (or more) in their own settlement.
+    Absorb {
+        /// The person absorbing small balances.
+        person: PersonId,
+    },
+    /// Payments below the minimum are rolled into another payment by the same
+    /// payer or to the same payee, with one of the two passing the other's
+    /// amount on, so the plan still settles everyone exactly.
+    RollIntoPayment,
+    /// Every balance is rounded to whole dollars, with the designated person
+    /// taking on the accumulated rounding residue. Rounded balances below the
+    /// minimum are then written off against that person as well.
+    RoundToDollars {
+        /// The person absorbing the rounding residue.
+        absorber: PersonId,
+    },
+}
+
+/// A minimum payment amount and the policy for amounts below it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MinimumPayment {
+    /// Smallest payment, in cents, that should appear in a plan.
+    pub min_cents: i64,
+    /// What to do with amounts below `min_cents`.
+    pub policy: SmallDebtPolicy,
+}
+
+/// Why part of a person's balance is not settled by a payment.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ResidueKind {
+    /// Written off against the designated absorber.
+    Absorbed,
+    /// Lost or gained when rounding to whole dollars.
+    Rounding,
+}
+
+/// The part of a person's net balance that a plan deliberately leaves unsettled.
+///
+/// For every person, the payments in the plan plus their residues add up to
+/// their net balance exactly, and all residues together sum to zero.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Residue {
+    /// The person the residue belongs to.
+    pub person: PersonId,
+    /// Unsettled amount in cents: positive if they are owed it but won't
+    /// receive it, negative if they owe it but won't pay it.
+    pub amount_cents: i64,
+    /// Why the amount is unsettled.
+    pub kind: ResidueKind,
+}
+
+/// Result of simplifying debts under a minimum payment amount.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MinimumPaymentResult {
+    /// The settlement plan.
+    pub result: SimplificationResult,
+    /// Per-person amounts left unsettled by the plan, sorted by person.
+    pub residues: Vec<Residue>,
+    /// Payments in the plan that are still below the minimum and could not be
+    /// removed by the policy.
+    pub below_minimum: Vec<Payment>,
+}
+
+/// A person left with a nonzero balance after applying a proposed plan.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BalanceResidual {
+    /// The person whose balance is not settled.
+    pub person: PersonId,
+    /// Remaining balance in cents: positive if they are still owed money,
+    /// negative if they still owe money.
+    pub amount_cents: i64,
+}
+
+/// Outcome of checking a proposed list of payments against a list of debts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VerificationReport {
+    /// Whether the payments settle every debt exactly and are all well-formed.
+    pub valid: bool,
+    /// People whose balance is not zero after the payments, sorted by person.
+    pub residuals: Vec<BalanceResidual>,
+    /// Indices of payments where the payer and payee are the same person.
+    pub self_payments: Vec<usize>,
+    /// Indices of payments with a zero or negative amount.
+    pub non_positive_payments: Vec<usize>,
+}
+
+/// Debts derived from expenses, together with their simplified settlement.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExpenseSettlement {
+    /// Net pairwise debts, one per pair of people who still owe each other.
+    pub debts: Vec<Debt>,
+    /// The simplified settlement plan for `debts`.
+    pub result: SimplificationResult,
+}
+
+/// The dates on which a person wants to spread their payments.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InstallmentTerms {
+    /// The paying person.
+    pub person: PersonId,
+    /// Due dates as ISO-8601 dates (`YYYY-MM-DD`), in order.
+    pub dates: Vec<String>,
+}
+
+/// One scheduled part of a settlement payment.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Installment {
+    /// The transfer to make.
+    pub payment: Payment,
+    /// When the transfer is due, or `None` if it is due immediately.
+    pub due_date: Option<String>,
+    /// Position of this installment's due date among the payer's remaining
+    /// due dates, from 1. Installments to different creditors on the same
+    /// date share a number.
+    pub number: usize,
+    /// Number of remaining due dates for the payer.
+    pub count: usize,
+}
+
+/// A settlement plan broken into installments over time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SettlementSchedule {
+    /// The overall plan, including payments already made.
+    pub result: SimplificationResult,
+    /// Payments already made towards the plan.
+    pub paid: Vec<Payment>,
+    /// Installments still to be paid, ordered by payer, then due date.
+    pub installments: Vec<Installment>,
+    /// Total cents already paid.
+    pub paid_cents: i64,
+    /// Total cents still to be paid.
+    pub remaining_cents: i64,
+}
+
+/// A group of people, such as a couple, who settle as one unit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Household {
+    /// Identifier used for the household in payments. Must not clash with
+    /// the ID of anyone outside the household.
+    pub id: String,
+    /// The people in the household.
+    pub members: Vec<PersonId>,
+}
+
+/// One member's part of their household's net balance.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MemberBalance {
+    /// The member.
+    pub person: PersonId,
+    /// The member's individual net balance in cents: positive if owed money.
+    pub balance_cents: i64,
+}
+
+/// How a household's net balance breaks down per member.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HouseholdBreakdown {
+    /// The household ID.
+    pub household: String,
+    /// The household's net balance in cents: positive if owed money.
+    pub balance_cents: i64,
+    /// Each member's contribution to `balance_cents`, sorted by person.
+    pub members: Vec<MemberBalance>,
 }
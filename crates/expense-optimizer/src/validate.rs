@@ -0,0 +1,227 @@
+//! Input validation for debt simplification.
+//!
+//! This module checks debts before they reach the planner, so malformed
+//! input is reported against the offending debt instead of producing a
+//! nonsensical plan.
+
+use finance_core::PersonId;
+use serde::Serialize;
+
+use crate::types::Debt;
+
+/// Errors that make a list of debts unusable for simplification.
+///
+/// Every variant carries the index of the offending debt in the input slice.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SimplifyError {
+    /// The debt's amount is zero or negative.
+    NonPositiveAmount {
+        /// Index of the debt in the input.
+        index: usize,
+        /// The rejected amount.
+        amount_cents: i64,
+    },
+    /// The debtor and creditor are the same person.
+    SelfDebt {
+        /// Index of the debt in the input.
+        index: usize,
+        /// The person who owes themselves.
+        person: PersonId,
+    },
+    /// The debtor's name is empty or only whitespace.
+    EmptyDebtor {
+        /// Index of the debt in the input.
+        index: usize,
+    },
+    /// The creditor's name is empty or only whitespace.
+    EmptyCreditor {
+        /// Index of the debt in the input.
+        index: usize,
+    },
+    /// The running total of amounts no longer fits in an `i64`.
+    Overflow {
+        /// Index of the debt whose amount overflowed the total.
+        index: usize,
+    },
+}
+
+impl SimplifyError {
+    /// Index of the offending debt in the input slice.
+    pub fn index(&self) -> usize {
+        match self {
+            SimplifyError::NonPositiveAmount { index, .. }
+            | SimplifyError::SelfDebt { index, .. }
+            | SimplifyError::EmptyDebtor { index }
+            | SimplifyError::EmptyCreditor { index }
+            | SimplifyError::Overflow { index } => *index,
+        }
+    }
+
+    /// Name of the offending field of the debt.
+    pub fn field(&self) -> &'static str {
+        match self {
+            SimplifyError::NonPositiveAmount { .. } | SimplifyError::Overflow { .. } => {
+                "amount_cents"
+            }
+            SimplifyError::SelfDebt { .. } | SimplifyError::EmptyCreditor { .. } => "creditor",
+            SimplifyError::EmptyDebtor { .. } => "debtor",
+        }
+    }
+}
+
+impl std::fmt::Display for SimplifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimplifyError::NonPositiveAmount {
+                index,
+                amount_cents,
+            } => write!(
+                f,
+                "debt {}: amount_cents must be positive, got {}",
+                index, amount_cents
+            ),
+            SimplifyError::SelfDebt { index, person } => {
+                write!(f, "debt {}: {} cannot owe themselves", index, person)
+            }
+            SimplifyError::EmptyDebtor { index } => write!(f, "debt {}: debtor is empty", index),
+            SimplifyError::EmptyCreditor { index } => {
+                write!(f, "debt {}: creditor is empty", index)
+            }
+            SimplifyError::Overflow { index } => {
+                write!(f, "debt {}: total amount overflows i64", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SimplifyError {}
+
+/// Check that debts are safe to simplify.
+///
+/// Each debt must name a non-empty debtor and creditor who are different
+/// people and carry a positive amount. The total of all amounts must fit in
+/// an `i64`, which guarantees no balance or plan total can overflow.
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects to check
+///
+/// # Returns
+/// `Ok(())` if every debt is valid, otherwise the error for the first
+/// offending debt
+pub fn validate_debts(debts: &[Debt]) -> Result<(), SimplifyError> {
+    let mut total: i64 = 0;
+
+    for (index, debt) in debts.iter().enumerate() {
+        if debt.debtor.trim().is_empty() {
+            return Err(SimplifyError::EmptyDebtor { index });
+        }
+        if debt.creditor.trim().is_empty() {
+            return Err(SimplifyError::EmptyCreditor { index });
+        }
+        if debt.debtor == debt.creditor {
+            return Err(SimplifyError::SelfDebt {
+                index,
+                person: debt.debtor.clone(),
+            });
+        }
+        if debt.amount_cents <= 0 {
+            return Err(SimplifyError::NonPositiveAmount {
+                index,
+                amount_cents: debt.amount_cents,
+            });
+        }
+        total = total
+            .checked_add(debt.amount_cents)
+            .ok_or(SimplifyError::Overflow { index })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn test_valid_debts() {
+        let debts = vec![debt("A", "B", 5000), debt("B", "C", 1)];
+        assert_eq!(validate_debts(&debts), Ok(()));
+        assert_eq!(validate_debts(&[]), Ok(()));
+    }
+
+    #[test]
+    fn test_non_positive_amounts() {
+        let debts = vec![debt("A", "B", 5000), debt("B", "C", -200)];
+        assert_eq!(
+            validate_debts(&debts),
+            Err(SimplifyError::NonPositiveAmount {
+                index: 1,
+                amount_cents: -200,
+            })
+        );
+
+        let zero = vec![debt("A", "B", 0)];
+        assert_eq!(validate_debts(&zero).unwrap_err().index(), 0);
+    }
+
+    #[test]
+    fn test_self_debt() {
+        let debts = vec![debt("A", "A", 5000)];
+        assert_eq!(
+            validate_debts(&debts),
+            Err(SimplifyError::SelfDebt {
+                index: 0,
+                person: "A".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_empty_names() {
+        assert_eq!(
+            validate_debts(&[debt("", "B", 100)]),
+            Err(SimplifyError::EmptyDebtor { index: 0 })
+        );
+        assert_eq!(
+            validate_debts(&[debt("A", "B", 100), debt("A", "  ", 100)]),
+            Err(SimplifyError::EmptyCreditor { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let debts = vec![
+            debt("A", "B", i64::MAX / 2),
+            debt("B", "C", i64::MAX / 2),
+            debt("C", "A", i64::MAX / 2),
+        ];
+        assert_eq!(
+            validate_debts(&debts),
+            Err(SimplifyError::Overflow { index: 2 })
+        );
+    }
+
+    #[test]
+    fn test_error_messages_name_the_debt() {
+        let error = SimplifyError::NonPositiveAmount {
+            index: 3,
+            amount_cents: -5,
+        };
+        assert_eq!(
+            error.to_string(),
+            "debt 3: amount_cents must be positive, got -5"
+        );
+        assert_eq!((error.index(), error.field()), (3, "amount_cents"));
+        assert_eq!(SimplifyError::EmptyDebtor { index: 0 }.field(), "debtor");
+    }
+}
@@ -0,0 +1,160 @@
+//! Settlement plan verification.
+//!
+//! This module checks a proposed settlement plan, for example one returned by
+//! the frontend or an agent, against the debts it claims to settle.
+
+use std::collections::BTreeMap;
+
+use crate::types::{BalanceResidual, Debt, Payment, VerificationReport};
+
+/// Verify that `payments` settle `debts` exactly.
+///
+/// A plan is valid when:
+/// - applying the payments brings every person's net balance to zero
+/// - no payment has the same payer and payee
+/// - every payment amount is positive
+///
+/// # Arguments
+/// * `debts` - Slice of Debt objects the plan should settle
+/// * `payments` - The proposed payments
+///
+/// # Returns
+/// A VerificationReport listing every residual balance and malformed payment
+pub fn verify_settlement(debts: &[Debt], payments: &[Payment]) -> VerificationReport {
+    // Remaining balance per person: positive = still owed money
+    let mut remaining: BTreeMap<&str, i128> = BTreeMap::new();
+
+    for debt in debts {
+        *remaining.entry(&debt.debtor).or_insert(0) -= debt.amount_cents as i128;
+        *remaining.entry(&debt.creditor).or_insert(0) += debt.amount_cents as i128;
+    }
+    for payment in payments {
+        *remaining.entry(&payment.from).or_insert(0) += payment.amount_cents as i128;
+        *remaining.entry(&payment.to).or_insert(0) -= payment.amount_cents as i128;
+    }
+
+    let residuals: Vec<BalanceResidual> = remaining
+        .into_iter()
+        .filter(|(_, amount)| *amount != 0)
+        .map(|(person, amount)| BalanceResidual {
+            person: person.to_string(),
+            amount_cents: amount.clamp(i64::MIN as i128, i64::MAX as i128) as i64,
+        })
+        .collect();
+
+    let self_payments: Vec<usize> = payments
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.from == p.to)
+        .map(|(i, _)| i)
+        .collect();
+
+    let non_positive_payments: Vec<usize> = payments
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.amount_cents <= 0)
+        .map(|(i, _)| i)
+        .collect();
+
+    VerificationReport {
+        valid: residuals.is_empty() && self_payments.is_empty() && non_positive_payments.is_empty(),
+        residuals,
+        self_payments,
+        non_positive_payments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplify::{make_payment, simplify_debts};
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn test_simplified_plan_is_valid() {
+        let debts = vec![
+            debt("A", "B", 5000),
+            debt("A", "C", 3000),
+            debt("B", "D", 8000),
+            debt("C", "D", 2000),
+        ];
+        let result = simplify_debts(&debts);
+
+        let report = verify_settlement(&debts, &result.payments);
+
+        assert!(report.valid);
+        assert!(report.residuals.is_empty());
+    }
+
+    #[test]
+    fn test_underpayment_reports_residuals() {
+        let debts = vec![debt("A", "B", 5000)];
+        let payments = vec![make_payment("A", "B", 3000)];
+
+        let report = verify_settlement(&debts, &payments);
+
+        assert!(!report.valid);
+        assert_eq!(
+            report.residuals,
+            vec![
+                BalanceResidual {
+                    person: "A".to_string(),
+                    amount_cents: -2000,
+                },
+                BalanceResidual {
+                    person: "B".to_string(),
+                    amount_cents: 2000,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrong_recipient_reports_residuals() {
+        let debts = vec![debt("A", "B", 5000)];
+        let payments = vec![make_payment("A", "C", 5000)];
+
+        let report = verify_settlement(&debts, &payments);
+
+        assert!(!report.valid);
+        assert_eq!(report.residuals.len(), 2);
+        assert!(
+            report
+                .residuals
+                .iter()
+                .any(|r| r.person == "C" && r.amount_cents == -5000)
+        );
+    }
+
+    #[test]
+    fn test_self_payment_is_invalid() {
+        let debts: Vec<Debt> = vec![];
+        let payments = vec![make_payment("A", "A", 1000)];
+
+        let report = verify_settlement(&debts, &payments);
+
+        assert!(!report.valid);
+        assert!(report.residuals.is_empty());
+        assert_eq!(report.self_payments, vec![0]);
+    }
+
+    #[test]
+    fn test_negative_payment_is_invalid() {
+        let debts = vec![debt("A", "B", 5000)];
+        let payments = vec![make_payment("B", "A", -5000)];
+
+        let report = verify_settlement(&debts, &payments);
+
+        assert!(!report.valid);
+        assert!(report.residuals.is_empty());
+        assert_eq!(report.non_positive_payments, vec![0]);
+    }
+}
@@ -2,10 +2,23 @@
 //!
 //! This module provides WebAssembly bindings for use in browser environments.
 
+use finance_core::{Expense, Settlement};
 use wasm_bindgen::prelude::*;
 
-use crate::simplify::simplify_debts;
-use crate::types::Debt;
+use crate::export::{GraphFormat, render_debt_graph, render_payment_graph};
+use crate::households::{explain_household_balances, simplify_household_debts};
+use crate::incremental::update_settlement_plan;
+use crate::normalize::normalize_debts;
+use crate::pipeline::settle_expenses;
+use crate::reason::{ReasonOptions, localize_reasons};
+use crate::schedule::schedule_settlements;
+use crate::simplify::{try_simplify_debts, try_simplify_debts_with_objective};
+use crate::threshold::simplify_debts_with_minimum;
+use crate::types::{
+    Debt, Household, InstallmentTerms, MinimumPayment, Objective, Payment, SimplificationResult,
+};
+use crate::validate;
+use crate::verify::verify_settlement;
 
 /// Initialize the WASM module with panic hook for better error messages.
 #[wasm_bindgen(start)]
@@ -16,22 +29,324 @@ pub fn init() {
 /// Optimize settlements from a list of debts.
 ///
 /// Takes a JSON array of Debt objects and returns a SimplificationResult.
+/// Invalid debts are rejected with an error naming the offending index.
 #[wasm_bindgen]
 pub fn optimize_settlements(debts: JsValue) -> Result<JsValue, JsValue> {
     let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
 
-    let result = simplify_debts(&debts);
+    let result = try_simplify_debts(&debts)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
 
     serde_wasm_bindgen::to_value(&result)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
 }
 
-/// Validate that all debts have positive amounts.
+/// Optimize settlements for a specific objective.
+///
+/// Takes a JSON array of Debt objects and an objective (`"min_transactions"`,
+/// `"min_total_transferred"`, `"min_max_payment"` or
+/// `{"max_payments_per_person": {"cap": 3}}`) and returns a
+/// SimplificationResult. Invalid debts are rejected with an error naming the
+/// offending index.
+#[wasm_bindgen]
+pub fn optimize_settlements_with_objective(
+    debts: JsValue,
+    objective: JsValue,
+) -> Result<JsValue, JsValue> {
+    let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+    let objective: Objective = serde_wasm_bindgen::from_value(objective)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse objective: {}", e)))?;
+
+    let result = try_simplify_debts_with_objective(&debts, objective)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Optimize settlements while avoiding payments below a minimum amount.
+///
+/// Takes a JSON array of Debt objects, an objective name, and a
+/// MinimumPayment object, and returns a MinimumPaymentResult.
+#[wasm_bindgen]
+pub fn optimize_settlements_with_minimum(
+    debts: JsValue,
+    objective: JsValue,
+    minimum: JsValue,
+) -> Result<JsValue, JsValue> {
+    let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+    let objective: Objective = serde_wasm_bindgen::from_value(objective)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse objective: {}", e)))?;
+    let minimum: MinimumPayment = serde_wasm_bindgen::from_value(minimum)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse minimum payment: {}", e)))?;
+    validate::validate_debts(&debts)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
+
+    let result = simplify_debts_with_minimum(&debts, objective, &minimum);
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Update a previous settlement plan with new debts.
+///
+/// Takes the previous SimplificationResult, a JSON array of locked Payment
+/// objects, and a JSON array of new Debt objects, and returns a PlanUpdate.
+#[wasm_bindgen]
+pub fn update_settlements(
+    previous: JsValue,
+    locked: JsValue,
+    new_debts: JsValue,
+) -> Result<JsValue, JsValue> {
+    let previous: SimplificationResult = serde_wasm_bindgen::from_value(previous)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse previous plan: {}", e)))?;
+    let locked: Vec<Payment> = serde_wasm_bindgen::from_value(locked)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse locked payments: {}", e)))?;
+    let new_debts: Vec<Debt> = serde_wasm_bindgen::from_value(new_debts)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+    validate::validate_debts(&new_debts)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
+
+    let update = update_settlement_plan(&previous, &locked, &new_debts);
+
+    serde_wasm_bindgen::to_value(&update)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Net debts between each pair of people into one debt per pair.
+///
+/// Takes a JSON array of Debt objects and returns the normalized array,
+/// e.g. to show that two people are square except for a small amount.
+#[wasm_bindgen]
+pub fn normalize(debts: JsValue) -> Result<JsValue, JsValue> {
+    let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+    validate::validate_debts(&debts)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
+
+    let normalized = normalize_debts(&debts);
+
+    serde_wasm_bindgen::to_value(&normalized)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Validate debts before simplification.
+///
+/// Checks that every debt has a positive amount, distinct non-empty debtor
+/// and creditor, and the total fits in an i64. Returns `null` when the debts
+/// are valid, otherwise the SimplifyError for the first offending debt, e.g.
+/// `{"type": "non_positive_amount", "index": 2, "amount_cents": 0}`.
+#[wasm_bindgen]
+pub fn validate_debts(debts: JsValue) -> Result<JsValue, JsValue> {
+    let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+
+    match validate::validate_debts(&debts) {
+        Ok(()) => Ok(JsValue::NULL),
+        Err(e) => serde_wasm_bindgen::to_value(&e)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize error: {}", e))),
+    }
+}
+
+/// Verify that a proposed list of payments settles a list of debts.
+///
+/// Takes a JSON array of Debt objects and a JSON array of Payment objects
+/// and returns a VerificationReport. Invalid debts are rejected; problems
+/// with the payments are what the report describes.
+#[wasm_bindgen]
+pub fn verify_settlements(debts: JsValue, payments: JsValue) -> Result<JsValue, JsValue> {
+    let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+    let payments: Vec<Payment> = serde_wasm_bindgen::from_value(payments)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse payments: {}", e)))?;
+    validate::validate_debts(&debts)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
+
+    let report = verify_settlement(&debts, &payments);
+
+    serde_wasm_bindgen::to_value(&report)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Settle a trip directly from its expenses.
+///
+/// Takes a JSON array of finance-core Expense objects and a JSON array of
+/// Settlement objects already paid, and returns an ExpenseSettlement.
+/// Non-positive amounts and totals that overflow an i64 are rejected.
 #[wasm_bindgen]
-pub fn validate_debts(debts: JsValue) -> Result<bool, JsValue> {
+pub fn settle_trip_expenses(expenses: JsValue, settlements: JsValue) -> Result<JsValue, JsValue> {
+    let expenses: Vec<Expense> = serde_wasm_bindgen::from_value(expenses)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse expenses: {}", e)))?;
+    let settlements: Vec<Settlement> = serde_wasm_bindgen::from_value(settlements)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse settlements: {}", e)))?;
+    validate_trip(&expenses, &settlements)
+        .map_err(|e| JsValue::from_str(&format!("Invalid trip: {}", e)))?;
+
+    let settlement = settle_expenses(&expenses, &settlements);
+
+    serde_wasm_bindgen::to_value(&settlement)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Schedule settlements as installments over time.
+///
+/// Takes a JSON array of Debt objects, a JSON array of InstallmentTerms, and
+/// a JSON array of Payment objects already made, and returns a
+/// SettlementSchedule.
+#[wasm_bindgen]
+pub fn schedule_installments(
+    debts: JsValue,
+    terms: JsValue,
+    paid: JsValue,
+) -> Result<JsValue, JsValue> {
+    let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+    let terms: Vec<InstallmentTerms> = serde_wasm_bindgen::from_value(terms)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse terms: {}", e)))?;
+    let paid: Vec<Payment> = serde_wasm_bindgen::from_value(paid)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse paid payments: {}", e)))?;
+    validate::validate_debts(&debts)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
+
+    let schedule = schedule_settlements(&debts, &terms, &paid);
+
+    serde_wasm_bindgen::to_value(&schedule)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Optimize settlements between households instead of individuals.
+///
+/// Takes a JSON array of Debt objects and a JSON array of Household objects
+/// and returns a SimplificationResult with payments between household IDs.
+/// Households that share a member or whose ID names someone outside them are
+/// rejected.
+#[wasm_bindgen]
+pub fn optimize_household_settlements(
+    debts: JsValue,
+    households: JsValue,
+) -> Result<JsValue, JsValue> {
+    let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+    let households: Vec<Household> = serde_wasm_bindgen::from_value(households)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse households: {}", e)))?;
+    validate::validate_debts(&debts)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
+
+    let result = simplify_household_debts(&debts, &households)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Explain each household's net balance per member.
+///
+/// Takes a JSON array of Debt objects and a JSON array of Household objects
+/// and returns a JSON array of HouseholdBreakdown objects.
+#[wasm_bindgen]
+pub fn explain_households(debts: JsValue, households: JsValue) -> Result<JsValue, JsValue> {
+    let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+    let households: Vec<Household> = serde_wasm_bindgen::from_value(households)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse households: {}", e)))?;
+    validate::validate_debts(&debts)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
+
+    let breakdown = explain_household_balances(&debts, &households)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    serde_wasm_bindgen::to_value(&breakdown)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Render the original debt graph as DOT or Mermaid text.
+///
+/// Takes a JSON array of Debt objects and a format name (`"dot"` or
+/// `"mermaid"`) and returns the graph text. Invalid debts are rejected.
+#[wasm_bindgen]
+pub fn export_debt_graph(debts: JsValue, format: JsValue) -> Result<String, JsValue> {
     let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+    let format: GraphFormat = serde_wasm_bindgen::from_value(format)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse format: {}", e)))?;
+    validate::validate_debts(&debts)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
+
+    Ok(render_debt_graph(&debts, format))
+}
+
+/// Render a payment graph as DOT or Mermaid text.
+///
+/// Takes a JSON array of Payment objects and a format name (`"dot"` or
+/// `"mermaid"`) and returns the graph text.
+#[wasm_bindgen]
+pub fn export_payment_graph(payments: JsValue, format: JsValue) -> Result<String, JsValue> {
+    let payments: Vec<Payment> = serde_wasm_bindgen::from_value(payments)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse payments: {}", e)))?;
+    let format: GraphFormat = serde_wasm_bindgen::from_value(format)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse format: {}", e)))?;
+
+    Ok(render_payment_graph(&payments, format))
+}
+
+/// Rewrite payment reasons for a locale, currency, and display names.
+///
+/// Takes a JSON array of Payment objects, the JSON array of Debt objects
+/// they settle, and ReasonOptions, and returns the payments with localized
+/// reasons. Invalid debts are rejected.
+#[wasm_bindgen]
+pub fn localize_settlement_reasons(
+    payments: JsValue,
+    debts: JsValue,
+    options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let payments: Vec<Payment> = serde_wasm_bindgen::from_value(payments)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse payments: {}", e)))?;
+    let debts: Vec<Debt> = serde_wasm_bindgen::from_value(debts)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse debts: {}", e)))?;
+    let options: ReasonOptions = serde_wasm_bindgen::from_value(options)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+    validate::validate_debts(&debts)
+        .map_err(|e| JsValue::from_str(&format!("Invalid debts: {}", e)))?;
+
+    let localized = localize_reasons(&payments, &debts, &options);
+
+    serde_wasm_bindgen::to_value(&localized)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Check expenses and settlements before they are turned into debts.
+///
+/// Every amount must be positive, and all of them together must fit in an
+/// i64, which bounds every share, debt, and balance derived from them.
+fn validate_trip(expenses: &[Expense], settlements: &[Settlement]) -> Result<(), String> {
+    let mut total: i64 = 0;
+
+    for (index, expense) in expenses.iter().enumerate() {
+        if expense.amount_cents <= 0 {
+            return Err(format!(
+                "expense {}: amount_cents must be positive, got {}",
+                index, expense.amount_cents
+            ));
+        }
+        total = total
+            .checked_add(expense.amount_cents)
+            .ok_or_else(|| format!("expense {}: total amount overflows i64", index))?;
+    }
+    for (index, settlement) in settlements.iter().enumerate() {
+        if settlement.amount_cents <= 0 {
+            return Err(format!(
+                "settlement {}: amount_cents must be positive, got {}",
+                index, settlement.amount_cents
+            ));
+        }
+        total = total
+            .checked_add(settlement.amount_cents)
+            .ok_or_else(|| format!("settlement {}: total amount overflows i64", index))?;
+    }
 
-    Ok(debts.iter().all(|d| d.amount_cents > 0))
+    Ok(())
 }
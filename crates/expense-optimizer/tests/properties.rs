@@ -0,0 +1,112 @@
+//! Property tests for debt simplification.
+//!
+//! These check invariants that must hold for every input: plans are
+//! deterministic and independent of input order, money is conserved, and
+//! payments come back in canonical order.
+
+use std::collections::HashMap;
+
+use expense_optimizer::{
+    Debt, Objective, Payment, calculate_net_balances, simplify_debts_with_objective,
+};
+use proptest::prelude::*;
+
+const OBJECTIVES: [Objective; 4] = [
+    Objective::MinTransactions,
+    Objective::MinTotalTransferred,
+    Objective::MinMaxPayment,
+    Objective::MaxPaymentsPerPerson { cap: 2 },
+];
+
+/// Debts among a small pool of people, so equal balances and repeated pairs
+/// are common.
+fn debts_strategy() -> impl Strategy<Value = Vec<Debt>> {
+    let debt = (0..8usize, 0..8usize, 1..20_000i64).prop_map(|(debtor, creditor, amount)| Debt {
+        debtor: format!("person_{}", debtor),
+        creditor: format!("person_{}", creditor),
+        amount_cents: amount,
+        expense_ids: vec![],
+    });
+    prop::collection::vec(debt, 0..40)
+}
+
+/// Round amounts to a few values so ties between balances are frequent.
+fn tied_debts_strategy() -> impl Strategy<Value = Vec<Debt>> {
+    debts_strategy().prop_map(|debts| {
+        debts
+            .into_iter()
+            .map(|d| Debt {
+                amount_cents: (d.amount_cents % 3 + 1) * 1000,
+                ..d
+            })
+            .collect()
+    })
+}
+
+fn apply_payments(debts: &[Debt], payments: &[Payment]) -> HashMap<String, i64> {
+    let mut balances = calculate_net_balances(debts);
+    for payment in payments {
+        *balances.entry(payment.from.clone()).or_insert(0) += payment.amount_cents;
+        *balances.entry(payment.to.clone()).or_insert(0) -= payment.amount_cents;
+    }
+    balances
+}
+
+proptest! {
+    #[test]
+    fn plan_is_deterministic(debts in tied_debts_strategy()) {
+        for objective in OBJECTIVES {
+            let first = simplify_debts_with_objective(&debts, objective);
+            let second = simplify_debts_with_objective(&debts, objective);
+            prop_assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn plan_ignores_input_order(
+        (debts, shuffled) in tied_debts_strategy()
+            .prop_flat_map(|d| (Just(d.clone()), Just(d).prop_shuffle()))
+    ) {
+        for objective in OBJECTIVES {
+            let original = simplify_debts_with_objective(&debts, objective);
+            let reordered = simplify_debts_with_objective(&shuffled, objective);
+            prop_assert_eq!(original.payments, reordered.payments);
+        }
+    }
+
+    #[test]
+    fn plan_conserves_money(debts in debts_strategy()) {
+        for objective in OBJECTIVES {
+            let result = simplify_debts_with_objective(&debts, objective);
+            for (person, balance) in apply_payments(&debts, &result.payments) {
+                prop_assert_eq!(balance, 0, "{} unsettled under {:?}", person, objective);
+            }
+        }
+    }
+
+    #[test]
+    fn payments_are_valid_and_canonical(debts in debts_strategy()) {
+        for objective in OBJECTIVES {
+            let result = simplify_debts_with_objective(&debts, objective);
+
+            for payment in &result.payments {
+                prop_assert!(payment.amount_cents > 0);
+                prop_assert_ne!(&payment.from, &payment.to);
+            }
+            for pair in result.payments.windows(2) {
+                let key = |p: &Payment| (p.from.clone(), p.to.clone());
+                prop_assert!(key(&pair[0]) < key(&pair[1]), "payments out of order");
+            }
+        }
+    }
+
+    #[test]
+    fn min_transactions_uses_at_most_n_minus_one_payments(debts in debts_strategy()) {
+        let result = simplify_debts_with_objective(&debts, Objective::MinTransactions);
+        let unsettled = calculate_net_balances(&debts)
+            .values()
+            .filter(|b| **b != 0)
+            .count();
+        prop_assert!(result.optimized_count <= unsettled.saturating_sub(1));
+    }
+}
@@ -6,7 +6,7 @@
 use std::collections::HashMap;
 
 use crate::split::split_expense_impl;
-use crate::types::{BalanceSummary, Cents, Expense};
+use crate::types::{BalanceContribution, BalanceExplanation, BalanceSummary, Cents, Expense};
 
 /// Calculates the net balance for each person across all expenses.
 ///
@@ -64,6 +64,57 @@ pub fn calculate_all_balances_impl(expenses: &[Expense]) -> Vec<BalanceSummary>
         .collect()
 }
 
+/// Explains one person's net balance expense by expense.
+///
+/// Lists every expense the person paid for or participated in with what they
+/// paid, their share, and the difference. The totals match the person's
+/// entry from [`calculate_all_balances_impl`]; a person who appears in no
+/// expense gets an empty explanation with a zero balance.
+///
+/// # Arguments
+///
+/// * `expenses` - All expenses in the group
+/// * `attendee_id` - The person whose balance to explain
+pub fn explain_balance_impl(expenses: &[Expense], attendee_id: &str) -> BalanceExplanation {
+    let mut contributions = Vec::new();
+
+    for expense in expenses {
+        let is_payer = expense.payer_id == attendee_id;
+        if !is_payer && !expense.participants.iter().any(|p| p == attendee_id) {
+            continue;
+        }
+
+        let paid = if is_payer { expense.amount_cents } else { 0 };
+        // Someone listed twice owes both shares, as in the balance totals
+        let share: Cents = split_expense_impl(expense)
+            .shares
+            .iter()
+            .filter(|share| share.attendee_id == attendee_id)
+            .map(|share| share.share_cents)
+            .sum();
+
+        contributions.push(BalanceContribution {
+            expense_id: expense.id.clone(),
+            description: expense.description.clone(),
+            category: expense.category.clone(),
+            paid_cents: paid,
+            share_cents: share,
+            net_cents: paid - share,
+        });
+    }
+
+    let total_paid: Cents = contributions.iter().map(|c| c.paid_cents).sum();
+    let total_owed: Cents = contributions.iter().map(|c| c.share_cents).sum();
+
+    BalanceExplanation {
+        attendee_id: attendee_id.to_string(),
+        total_paid_cents: total_paid,
+        total_owed_cents: total_owed,
+        net_balance_cents: total_paid - total_owed,
+        contributions,
+    }
+}
+
 // ============================================================================
 // Unit Tests
 // ============================================================================
@@ -183,4 +234,45 @@ mod tests {
         let sum: Cents = balances.iter().map(|b| b.net_balance_cents).sum();
         assert_eq!(sum, 0);
     }
+
+    #[test]
+    fn test_explain_balance_matches_summary() {
+        let expenses = vec![
+            make_expense("1", "A", 10000, vec!["A", "B", "C"]),
+            make_expense("2", "B", 3000, vec!["A", "B"]),
+            make_expense("3", "C", 900, vec!["B", "C"]),
+        ];
+
+        let explanation = explain_balance_impl(&expenses, "A");
+
+        // Expense 3 does not involve A
+        let ids: Vec<&str> = explanation
+            .contributions
+            .iter()
+            .map(|c| c.expense_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["1", "2"]);
+
+        // A paid 10000 and owes 3334 for expense 1 (extra cent), owes 1500 for expense 2
+        assert_eq!(explanation.contributions[0].net_cents, 10000 - 3334);
+        assert_eq!(explanation.contributions[1].net_cents, -1500);
+
+        let summary = calculate_all_balances_impl(&expenses)
+            .into_iter()
+            .find(|b| b.attendee_id == "A")
+            .unwrap();
+        assert_eq!(explanation.total_paid_cents, summary.total_paid_cents);
+        assert_eq!(explanation.total_owed_cents, summary.total_owed_cents);
+        assert_eq!(explanation.net_balance_cents, summary.net_balance_cents);
+    }
+
+    #[test]
+    fn test_explain_balance_unknown_person() {
+        let expenses = vec![make_expense("1", "A", 1000, vec!["A", "B"])];
+
+        let explanation = explain_balance_impl(&expenses, "Z");
+
+        assert!(explanation.contributions.is_empty());
+        assert_eq!(explanation.net_balance_cents, 0);
+    }
 }
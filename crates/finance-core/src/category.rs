@@ -0,0 +1,128 @@
+//! Spending breakdown by category.
+//!
+//! This module totals expenses per category and shows how each category's
+//! spending is shared among participants.
+
+use std::collections::BTreeMap;
+
+use crate::split::split_expense_impl;
+use crate::types::{CategoryShare, CategoryTotal, Cents, Expense, PersonId};
+
+/// Totals expenses by category.
+///
+/// Each category's shares come from splitting its expenses exactly as
+/// [`split_expense_impl`] does, so they sum to the category total whenever
+/// every expense has participants.
+///
+/// Categories are sorted by total spending, largest first, with ties broken
+/// by category name.
+pub fn category_breakdown_impl(expenses: &[Expense]) -> Vec<CategoryTotal> {
+    // Per category: expense count, total, and each person's share
+    let mut categories: BTreeMap<&str, (usize, Cents, BTreeMap<PersonId, Cents>)> = BTreeMap::new();
+
+    for expense in expenses {
+        let (count, total, shares) = categories.entry(&expense.category).or_default();
+        *count += 1;
+        *total += expense.amount_cents;
+        for share in split_expense_impl(expense).shares {
+            *shares.entry(share.attendee_id).or_insert(0) += share.share_cents;
+        }
+    }
+
+    let mut breakdown: Vec<CategoryTotal> = categories
+        .into_iter()
+        .map(
+            |(category, (expense_count, total_cents, shares))| CategoryTotal {
+                category: category.to_string(),
+                expense_count,
+                total_cents,
+                shares: shares
+                    .into_iter()
+                    .map(|(attendee_id, share_cents)| CategoryShare {
+                        attendee_id,
+                        share_cents,
+                    })
+                    .collect(),
+            },
+        )
+        .collect();
+
+    // The sort is stable, so equal totals keep their alphabetical order
+    breakdown.sort_by_key(|category| std::cmp::Reverse(category.total_cents));
+    breakdown
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_expense(
+        id: &str,
+        category: &str,
+        amount_cents: Cents,
+        participants: Vec<&str>,
+    ) -> Expense {
+        Expense {
+            id: id.to_string(),
+            payer_id: "A".to_string(),
+            amount_cents,
+            participants: participants.into_iter().map(String::from).collect(),
+            category: category.to_string(),
+            description: "Test expense".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_categories_sorted_by_total() {
+        let expenses = vec![
+            make_expense("1", "food", 3000, vec!["A", "B"]),
+            make_expense("2", "transport", 9000, vec!["A", "B", "C"]),
+            make_expense("3", "food", 1001, vec!["B", "C"]),
+            make_expense("4", "drinks", 4000, vec!["C"]),
+        ];
+
+        let breakdown = category_breakdown_impl(&expenses);
+
+        let names: Vec<&str> = breakdown.iter().map(|c| c.category.as_str()).collect();
+        assert_eq!(names, vec!["transport", "food", "drinks"]);
+
+        let food = &breakdown[1];
+        assert_eq!(food.expense_count, 2);
+        assert_eq!(food.total_cents, 4001);
+        // B gets the extra cent from expense 3
+        assert_eq!(
+            food.shares,
+            vec![
+                CategoryShare {
+                    attendee_id: "A".to_string(),
+                    share_cents: 1500
+                },
+                CategoryShare {
+                    attendee_id: "B".to_string(),
+                    share_cents: 2001
+                },
+                CategoryShare {
+                    attendee_id: "C".to_string(),
+                    share_cents: 500
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_equal_totals_sorted_by_name() {
+        let expenses = vec![
+            make_expense("1", "lodging", 500, vec!["A"]),
+            make_expense("2", "activities", 500, vec!["A"]),
+        ];
+
+        let breakdown = category_breakdown_impl(&expenses);
+
+        assert_eq!(breakdown[0].category, "activities");
+        assert_eq!(breakdown[1].category, "lodging");
+    }
+}
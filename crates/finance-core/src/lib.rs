@@ -3,14 +3,19 @@
 pub mod types;
 pub mod split;
 pub mod balance;
+pub mod category;
 
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
 // Re-export main types and functions
-pub use types::{Cents, Expense, PersonShare, ShareResult, BalanceSummary};
+pub use types::{
+    Cents, Expense, PersonId, PersonShare, ShareResult, BalanceSummary, Settlement,
+    BalanceContribution, BalanceExplanation, CategoryShare, CategoryTotal,
+};
 pub use split::split_expense_impl;
-pub use balance::calculate_all_balances_impl;
+pub use balance::{calculate_all_balances_impl, explain_balance_impl};
+pub use category::category_breakdown_impl;
 
 #[cfg(feature = "wasm")]
 pub use wasm::{init, split_expense, calculate_all_balances};
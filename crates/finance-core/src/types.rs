@@ -9,17 +9,20 @@ use serde::{Deserialize, Serialize};
 /// Using i64 allows for negative balances and large amounts.
 pub type Cents = i64;
 
+/// Identifier of a trip attendee, shared by every crate that refers to people.
+pub type PersonId = String;
+
 /// Represents an expense paid by one person and split among participants.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Expense {
     /// Unique identifier for the expense.
     pub id: String,
     /// ID of the person who paid for this expense.
-    pub payer_id: String,
+    pub payer_id: PersonId,
     /// Total amount in cents.
     pub amount_cents: Cents,
     /// List of participant IDs who share this expense.
-    pub participants: Vec<String>,
+    pub participants: Vec<PersonId>,
     /// Category of the expense (e.g., "food", "transport").
     pub category: String,
     /// Human-readable description.
@@ -30,7 +33,7 @@ pub struct Expense {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PersonShare {
     /// ID of the participant.
-    pub attendee_id: String,
+    pub attendee_id: PersonId,
     /// Their share in cents.
     pub share_cents: Cents,
     /// Whether this person received an extra cent from remainder distribution.
@@ -52,7 +55,7 @@ pub struct ShareResult {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct BalanceSummary {
     /// ID of the person.
-    pub attendee_id: String,
+    pub attendee_id: PersonId,
     /// Total amount they paid for expenses.
     pub total_paid_cents: Cents,
     /// Total amount they owe for their shares.
@@ -60,3 +63,68 @@ pub struct BalanceSummary {
     /// Net balance: positive means others owe them, negative means they owe others.
     pub net_balance_cents: Cents,
 }
+
+/// A payment already made from one person to another to settle up.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Settlement {
+    /// ID of the person who paid.
+    pub from_id: PersonId,
+    /// ID of the person who received the payment.
+    pub to_id: PersonId,
+    /// Amount paid in cents.
+    pub amount_cents: Cents,
+}
+
+/// How one expense contributes to a person's balance.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BalanceContribution {
+    /// ID of the expense.
+    pub expense_id: String,
+    /// Human-readable description of the expense.
+    pub description: String,
+    /// Category of the expense.
+    pub category: String,
+    /// Amount the person paid: the full amount if they were the payer, else zero.
+    pub paid_cents: Cents,
+    /// The person's share of the expense, zero if they did not participate.
+    pub share_cents: Cents,
+    /// `paid_cents - share_cents`, the expense's effect on the net balance.
+    pub net_cents: Cents,
+}
+
+/// A person's balance broken down expense by expense.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BalanceExplanation {
+    /// ID of the person.
+    pub attendee_id: PersonId,
+    /// Total amount they paid for expenses.
+    pub total_paid_cents: Cents,
+    /// Total amount they owe for their shares.
+    pub total_owed_cents: Cents,
+    /// Net balance, the sum of every contribution's `net_cents`.
+    pub net_balance_cents: Cents,
+    /// The expenses the person paid for or shared, in input order.
+    pub contributions: Vec<BalanceContribution>,
+}
+
+/// One person's share of the spending in a category.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CategoryShare {
+    /// ID of the person.
+    pub attendee_id: PersonId,
+    /// Their combined share of the category's expenses.
+    pub share_cents: Cents,
+}
+
+/// Total spending in one expense category.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CategoryTotal {
+    /// The category (e.g., "food").
+    pub category: String,
+    /// Number of expenses in the category.
+    pub expense_count: usize,
+    /// Combined amount of those expenses.
+    pub total_cents: Cents,
+    /// Each participant's share of that amount, sorted by person.
+    pub shares: Vec<CategoryShare>,
+}
@@ -0,0 +1,48 @@
+//! The WebAssembly component export.
+//!
+//! Built for `wasm32`, this crate exports `steamboat:mcp-server/handler`
+//! from the `expense-optimizer-mcp` world in `../wit`. Both functions
+//! delegate to the native entry points, so a component host sees exactly
+//! the same behavior as Rust callers. Build the core module with
+//! `cargo build --target wasm32-unknown-unknown --lib` and wrap it with any
+//! component encoder, such as `wasm-tools component new`.
+
+use exports::steamboat::mcp_server::handler::{Guest, McpRequest, McpResponse};
+use steamboat::mcp_server::types::{ErrorCode, McpError};
+
+wit_bindgen::generate!({
+    world: "steamboat:expense-optimizer/expense-optimizer-mcp",
+    path: ["../wit/mcp-server.wit", "../wit/expense-optimizer.wit"],
+    generate_all,
+});
+
+struct Component;
+
+impl Guest for Component {
+    fn handle(request: McpRequest) -> McpResponse {
+        let response = match crate::McpRequest::decode(request.method, &request.params) {
+            Ok(request) => crate::handle(request),
+            Err(error) => crate::McpResponse::Err(error),
+        };
+        match response {
+            crate::McpResponse::Ok(output) => McpResponse::Ok(output),
+            crate::McpResponse::Err(error) => McpResponse::Err(McpError {
+                code: match error.code {
+                    mcp_protocol::ErrorCode::UnknownMethod => ErrorCode::UnknownMethod,
+                    mcp_protocol::ErrorCode::InvalidParams => ErrorCode::InvalidParams,
+                    mcp_protocol::ErrorCode::Validation => ErrorCode::Validation,
+                    mcp_protocol::ErrorCode::ToolFailed => ErrorCode::ToolFailed,
+                    mcp_protocol::ErrorCode::Internal => ErrorCode::Internal,
+                },
+                message: error.message,
+                pointer: error.pointer,
+            }),
+        }
+    }
+
+    fn handle_jsonrpc(message: String) -> Option<String> {
+        crate::handle_jsonrpc(&message)
+    }
+}
+
+export!(Component);
@@ -1,106 +1,578 @@
 //! Expense Optimizer MCP Server
 //!
 //! This crate provides an MCP (Model Context Protocol) server interface
-//! for the expense-optimizer debt simplification engine.
+//! for the expense-optimizer debt simplification engine. It speaks JSON-RPC
+//! 2.0 through [`handle_jsonrpc`] and keeps the older [`handle`] entry point
+//! for callers of the legacy request format. Built for `wasm32`, it is a
+//! WebAssembly component exporting the WIT `handler` interface.
+//!
+//! Debts saved with the `save_debts` tool are kept in a [`StateStore`] and
+//! exposed as resources (the settlement plan and each person's balance) and
+//! through prompts, so agents need not re-send them with every request.
+
+#[cfg(target_arch = "wasm32")]
+mod component;
+pub mod state;
+
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
 
-use expense_optimizer::{simplify_debts, Debt, SimplificationResult};
+use expense_optimizer::{
+    Debt, GraphFormat, Payment, SimplificationResult, SimplifyError, calculate_net_balances,
+    render_debt_graph, render_payment_graph, simplify_debts, try_simplify_debts, validate_debts,
+    verify_settlement,
+};
+use mcp_protocol::{
+    CallToolResult, GetPromptResult, Implementation, ItemResult, JsonRpcError, McpServer, Prompt,
+    PromptArgument, PromptMessage, ReadResourceResult, Resource, Tool, handle_legacy,
+    handle_message, parse_arguments, parse_item, required_argument,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+pub use mcp_protocol::{ErrorCode, McpError, McpRequest, McpResponse};
+pub use state::{FileStore, MemoryStore, StateStore, TripState};
+
+/// URI of the settlement plan resource.
+pub const PLAN_URI: &str = "trip://settlement-plan";
+
+/// URI prefix of the per-person balance resources, followed by the
+/// percent-encoded person.
+pub const BALANCE_URI_PREFIX: &str = "trip://balances/";
+
+/// The server used by [`handle_jsonrpc`] and [`handle`], so saved debts
+/// outlive a single call.
+///
+/// Its trip state is process-wide: every caller sees the same saved debts.
+/// That suits a component instance, which serves one host, but a server
+/// with several clients should give each its own [`ExpenseOptimizerServer`].
+static SERVER: LazyLock<ExpenseOptimizerServer> = LazyLock::new(ExpenseOptimizerServer::default);
+
+/// Parameters for the optimize_settlements tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OptimizeSettlementsParams {
+    /// The debts to simplify.
+    pub debts: Vec<Debt>,
+}
 
-/// An MCP request containing a method name and JSON-encoded parameters.
+/// Parameters for the optimize_settlements_many tool.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct McpRequest {
-    /// The method to invoke (e.g., "optimize_settlements").
-    pub method: String,
-    /// JSON-encoded parameters for the method.
-    pub params: String,
+pub struct OptimizeSettlementsManyParams {
+    /// Independent groups, each parsed as [`OptimizeSettlementsParams`] on
+    /// its own so one malformed group does not fail the rest.
+    pub groups: Vec<Value>,
 }
 
-/// An MCP response containing the result or error from a request.
+/// Result of the optimize_settlements_many tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizeSettlementsManyResult {
+    /// Each group's plan or error, in request order.
+    pub results: Vec<ItemResult<SimplificationResult>>,
+}
+
+/// Parameters for the save_debts tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveDebtsParams {
+    /// Every debt in the group, replacing any saved before.
+    pub debts: Vec<Debt>,
+}
+
+/// Parameters for the verify_settlement tool.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct McpResponse {
-    /// Whether the request was successful.
-    pub success: bool,
-    /// JSON-encoded result if successful.
-    pub result: Option<String>,
-    /// Error message if unsuccessful.
-    pub error: Option<String>,
+pub struct VerifySettlementParams {
+    /// The debts the proposed plan should settle.
+    pub debts: Vec<Debt>,
+    /// The proposed payments.
+    pub payments: Vec<Payment>,
+}
+
+/// Parameters for the export_graph tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportGraphParams {
+    /// The debts to render.
+    pub debts: Vec<Debt>,
+    /// Output format: "dot" or "mermaid".
+    pub format: GraphFormat,
+    /// Render the simplified payment graph instead of the debts themselves.
+    #[serde(default)]
+    pub simplified: bool,
+}
+
+/// One person's position in the saved trip, read as a balance resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonBalance {
+    /// The person.
+    pub person: String,
+    /// Net balance in cents. Positive means others owe the person.
+    pub net_balance_cents: i64,
+    /// Payments the person makes in the settlement plan.
+    pub pays: Vec<Payment>,
+    /// Payments the person receives in the settlement plan.
+    pub receives: Vec<Payment>,
+}
+
+/// The expense optimizer as an MCP server.
+#[derive(Debug)]
+pub struct ExpenseOptimizerServer {
+    store: Box<dyn StateStore>,
+}
+
+impl Default for ExpenseOptimizerServer {
+    /// A server that keeps saved debts in memory.
+    fn default() -> Self {
+        Self::with_store(MemoryStore::default())
+    }
+}
+
+impl ExpenseOptimizerServer {
+    /// Create a server that keeps saved debts in `store`.
+    ///
+    /// # Arguments
+    /// * `store` - Where to keep the trip state
+    pub fn with_store(store: impl StateStore + 'static) -> Self {
+        Self {
+            store: Box::new(store),
+        }
+    }
+
+    /// Load the saved trip state.
+    fn load(&self) -> Result<TripState, JsonRpcError> {
+        self.store.load().map_err(JsonRpcError::internal_error)
+    }
+
+    /// Run the save_debts tool.
+    fn save_debts(&self, params: SaveDebtsParams) -> Result<CallToolResult, JsonRpcError> {
+        let plan = match try_simplify_debts(&params.debts) {
+            Ok(plan) => plan,
+            Err(e) => return Ok(CallToolResult::validation_error(invalid_debts(&e))),
+        };
+        let state = TripState {
+            debts: params.debts,
+        };
+        match self.store.save(&state) {
+            Ok(()) => CallToolResult::json(&plan),
+            Err(e) => Ok(CallToolResult::error(format!("Storage error: {}", e))),
+        }
+    }
+
+    /// Fill in the explain_my_balance prompt.
+    fn explain_my_balance(&self, person: &str) -> Result<GetPromptResult, JsonRpcError> {
+        let state = self.load()?;
+        let balance = person_balances(&state.debts)
+            .into_iter()
+            .find(|balance| balance.person == person)
+            .ok_or_else(|| {
+                JsonRpcError::invalid_params(format!(
+                    "no saved debts involve {}; save them with save_debts first",
+                    person
+                ))
+            })?;
+        let debts: Vec<&Debt> = state
+            .debts
+            .iter()
+            .filter(|debt| debt.debtor == person || debt.creditor == person)
+            .collect();
+
+        Ok(GetPromptResult {
+            description: Some(format!("Explain {}'s balance", person)),
+            messages: vec![PromptMessage::user(format!(
+                "Explain to {person} in plain language where their trip balance comes from \
+                 and what they need to pay or will receive to settle up. Amounts are integer \
+                 cents; show them as dollars. Use these figures rather than recomputing \
+                 them.\n\nBalance and settlement payments:\n{}\n\nDebts involving \
+                 {person}:\n{}",
+                to_json(&balance)?,
+                to_json(&debts)?,
+            ))],
+        })
+    }
+
+    /// Fill in the draft_settle_up_message prompt.
+    fn draft_settle_up_message(&self, tone: Option<&str>) -> Result<GetPromptResult, JsonRpcError> {
+        let plan = simplify_debts(&self.load()?.debts);
+
+        Ok(GetPromptResult {
+            description: Some("Draft a settle-up message for the group".to_string()),
+            messages: vec![PromptMessage::user(format!(
+                "Draft a short {} message to the group chat telling everyone who pays whom \
+                 to settle up the trip, one line per payment with its reason. Amounts are \
+                 integer cents; show them as dollars. If there are no payments, say that \
+                 everyone is already square.\n\nPayments:\n{}",
+                tone.unwrap_or("friendly"),
+                to_json(&plan.payments)?,
+            ))],
+        })
+    }
 }
 
-/// Handle an MCP request and return an MCP response.
+impl McpServer for ExpenseOptimizerServer {
+    fn server_info(&self) -> Implementation {
+        Implementation::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    }
+
+    fn instructions(&self) -> Option<String> {
+        Some(
+            "Amounts are integer cents. Pass every debt in the group so balances net out \
+             correctly."
+                .to_string(),
+        )
+    }
+
+    fn tools(&self) -> Vec<Tool> {
+        vec![
+            Tool::new(
+                "optimize_settlements",
+                "Simplify a group's debts into the fewest payments that settle everyone.",
+                json!({
+                    "type": "object",
+                    "properties": {"debts": debts_schema()},
+                    "required": ["debts"]
+                }),
+            ),
+            Tool::new(
+                "optimize_settlements_many",
+                "Simplify several independent groups' debts at once. Each group gets its own \
+                 plan or error, so one bad group does not fail the rest.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "groups": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {"debts": debts_schema()},
+                                "required": ["debts"]
+                            }
+                        }
+                    },
+                    "required": ["groups"]
+                }),
+            ),
+            Tool::new(
+                "save_debts",
+                "Save a group's debts as the trip's current state, replacing any saved \
+                 before, and return their settlement plan. The plan and each person's \
+                 balance can then be read as resources.",
+                json!({
+                    "type": "object",
+                    "properties": {"debts": debts_schema()},
+                    "required": ["debts"]
+                }),
+            ),
+            Tool::new(
+                "verify_settlement",
+                "Check that proposed payments settle a group's debts exactly, reporting \
+                 anyone left over- or underpaid.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "debts": debts_schema(),
+                        "payments": {"type": "array", "items": payment_schema()}
+                    },
+                    "required": ["debts", "payments"]
+                }),
+            ),
+            Tool::new(
+                "export_graph",
+                "Render the debt graph, or the simplified payment graph, as Graphviz DOT \
+                 or Mermaid text.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "debts": debts_schema(),
+                        "format": {"type": "string", "enum": ["dot", "mermaid"]},
+                        "simplified": {
+                            "type": "boolean",
+                            "description": "Render the simplified payments instead of the debts",
+                            "default": false
+                        }
+                    },
+                    "required": ["debts", "format"]
+                }),
+            ),
+        ]
+    }
+
+    fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, JsonRpcError> {
+        match name {
+            "optimize_settlements" => optimize_settlements(parse_arguments(arguments)?),
+            "optimize_settlements_many" => {
+                CallToolResult::json(&optimize_settlements_many(parse_arguments(arguments)?))
+            }
+            "save_debts" => self.save_debts(parse_arguments(arguments)?),
+            "verify_settlement" => verify_settlement_tool(parse_arguments(arguments)?),
+            "export_graph" => Ok(export_graph(parse_arguments(arguments)?)),
+            _ => Err(JsonRpcError::unknown_tool(name)),
+        }
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        let mut resources = vec![Resource::json(
+            PLAN_URI,
+            "Settlement plan",
+            "The fewest payments that settle the saved debts",
+        )];
+        // A store that cannot be read still lists the plan, which reports the error
+        let debts = self
+            .store
+            .load()
+            .map(|state| state.debts)
+            .unwrap_or_default();
+        resources.extend(person_balances(&debts).into_iter().map(|balance| {
+            Resource::json(
+                &format!(
+                    "{}{}",
+                    BALANCE_URI_PREFIX,
+                    urlencoding::encode(&balance.person)
+                ),
+                &format!("{}'s balance", balance.person),
+                &format!(
+                    "What {} is owed or owes, and their payments in the plan",
+                    balance.person
+                ),
+            )
+        }));
+        resources
+    }
+
+    fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, JsonRpcError> {
+        let debts = self.load()?.debts;
+        if uri == PLAN_URI {
+            return ReadResourceResult::json(uri, &simplify_debts(&debts));
+        }
+
+        let person = uri
+            .strip_prefix(BALANCE_URI_PREFIX)
+            .and_then(|person| urlencoding::decode(person).ok());
+        match person {
+            Some(person) => match person_balances(&debts)
+                .into_iter()
+                .find(|balance| balance.person == person)
+            {
+                Some(balance) => ReadResourceResult::json(uri, &balance),
+                None => Err(JsonRpcError::resource_not_found(uri)),
+            },
+            None => Err(JsonRpcError::resource_not_found(uri)),
+        }
+    }
+
+    fn prompts(&self) -> Vec<Prompt> {
+        vec![
+            Prompt::new(
+                "explain_my_balance",
+                "Explain one person's balance from the saved debts",
+                vec![PromptArgument::required(
+                    "person",
+                    "The person whose balance to explain",
+                )],
+            ),
+            Prompt::new(
+                "draft_settle_up_message",
+                "Draft a group message listing the payments that settle the saved debts",
+                vec![PromptArgument::optional(
+                    "tone",
+                    "How the message should sound, such as \"friendly\" or \"brief\"",
+                )],
+            ),
+        ]
+    }
+
+    fn get_prompt(
+        &self,
+        name: &str,
+        arguments: &BTreeMap<String, String>,
+    ) -> Result<GetPromptResult, JsonRpcError> {
+        match name {
+            "explain_my_balance" => {
+                self.explain_my_balance(required_argument(arguments, "person")?)
+            }
+            "draft_settle_up_message" => {
+                self.draft_settle_up_message(arguments.get("tone").map(String::as_str))
+            }
+            _ => Err(JsonRpcError::unknown_prompt(name)),
+        }
+    }
+}
+
+/// Handle a JSON-RPC 2.0 message.
+///
+/// Every call in the process shares one server, which keeps saved debts in
+/// memory, so callers serving several clients should use their own
+/// [`ExpenseOptimizerServer`] instead.
 ///
 /// # Arguments
-/// * `request` - The MCP request to process
+/// * `message` - The raw JSON-RPC message
 ///
 /// # Returns
-/// An McpResponse containing either the result or an error
-pub fn handle(request: McpRequest) -> McpResponse {
-    handle_request(&request.method, &request.params)
+/// The JSON-encoded response, or `None` if the message was a notification
+pub fn handle_jsonrpc(message: &str) -> Option<String> {
+    handle_message(&*SERVER, message)
 }
 
-/// Internal handler for MCP requests.
+/// Handle a legacy MCP request and return an MCP response.
+///
+/// Shares the server, and so the saved debts, of [`handle_jsonrpc`].
 ///
 /// # Arguments
-/// * `method` - The method name to invoke
-/// * `params` - JSON-encoded parameters
+/// * `request` - The MCP request to process
 ///
 /// # Returns
 /// An McpResponse containing either the result or an error
-fn handle_request(method: &str, params: &str) -> McpResponse {
-    match method {
-        "optimize_settlements" => optimize_settlements(params),
-        _ => McpResponse {
-            success: false,
-            result: None,
-            error: Some(format!("Unknown method: {}", method)),
+pub fn handle(request: McpRequest) -> McpResponse {
+    // optimize_settlements used to take the bare list of debts
+    handle_legacy(&*SERVER, request, |method, params| match (method, params) {
+        ("optimize_settlements", debts @ Value::Array(_)) => json!({ "debts": debts }),
+        (_, params) => params,
+    })
+}
+
+/// JSON Schema for a list of debts.
+fn debts_schema() -> Value {
+    json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {
+                "debtor": {"type": "string", "description": "Person who owes money"},
+                "creditor": {"type": "string", "description": "Person who is owed money"},
+                "amount_cents": {"type": "integer", "minimum": 1},
+                "expense_ids": {"type": "array", "items": {"type": "string"}}
+            },
+            "required": ["debtor", "creditor", "amount_cents", "expense_ids"]
+        }
+    })
+}
+
+/// JSON Schema for a single payment.
+fn payment_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "from": {"type": "string"},
+            "to": {"type": "string"},
+            "amount_cents": {"type": "integer"},
+            "reason": {"type": "string"}
         },
+        "required": ["from", "to", "amount_cents", "reason"]
+    })
+}
+
+/// Serialize a value embedded in a prompt.
+fn to_json<T: Serialize>(value: &T) -> Result<String, JsonRpcError> {
+    serde_json::to_string(value).map_err(JsonRpcError::internal_error)
+}
+
+/// Each person's balance and settlement payments, sorted by person.
+fn person_balances(debts: &[Debt]) -> Vec<PersonBalance> {
+    let plan = simplify_debts(debts);
+    let balances: BTreeMap<String, i64> = calculate_net_balances(debts).into_iter().collect();
+
+    balances
+        .into_iter()
+        .map(|(person, net_balance_cents)| PersonBalance {
+            pays: plan
+                .payments
+                .iter()
+                .filter(|payment| payment.from == person)
+                .cloned()
+                .collect(),
+            receives: plan
+                .payments
+                .iter()
+                .filter(|payment| payment.to == person)
+                .cloned()
+                .collect(),
+            person,
+            net_balance_cents,
+        })
+        .collect()
+}
+
+/// Run the optimize_settlements tool.
+///
+/// Validates the debts, runs the simplification algorithm, and returns the
+/// result as JSON.
+fn optimize_settlements(params: OptimizeSettlementsParams) -> Result<CallToolResult, JsonRpcError> {
+    match try_simplify_debts(&params.debts) {
+        Ok(result) => CallToolResult::json(&result),
+        Err(e) => Ok(CallToolResult::validation_error(invalid_debts(&e))),
     }
 }
 
-/// Handle the optimize_settlements method.
+/// Run the optimize_settlements_many tool.
 ///
-/// Parses debts from JSON, runs the simplification algorithm,
-/// and returns the result as JSON.
-fn optimize_settlements(params: &str) -> McpResponse {
-    // Parse debts from JSON
-    let debts: Vec<Debt> = match serde_json::from_str(params) {
-        Ok(d) => d,
-        Err(e) => {
-            return McpResponse {
-                success: false,
-                result: None,
-                error: Some(format!("Parse error: {}", e)),
-            };
-        }
-    };
+/// Parses and simplifies each group on its own, reporting a group's
+/// malformed or invalid debts in its place.
+fn optimize_settlements_many(
+    params: OptimizeSettlementsManyParams,
+) -> OptimizeSettlementsManyResult {
+    let results = params
+        .groups
+        .into_iter()
+        .enumerate()
+        .map(|(i, group)| {
+            let pointer = format!("/groups/{}", i);
+            let group: OptimizeSettlementsParams = parse_item(&pointer, group)?;
+            try_simplify_debts(&group.debts).map_err(|e| invalid_debts(&e).within(&pointer))
+        })
+        .map(ItemResult::from)
+        .collect();
+    OptimizeSettlementsManyResult { results }
+}
 
-    // Call the simplification algorithm
-    let result: SimplificationResult = simplify_debts(&debts);
+/// Describe debts the simplifier rejected, pointing at the offending field.
+fn invalid_debts(error: &SimplifyError) -> McpError {
+    McpError::validation(format!("/debts/{}/{}", error.index(), error.field()), error)
+}
 
-    // Serialize the result to JSON
-    match serde_json::to_string(&result) {
-        Ok(json) => McpResponse {
-            success: true,
-            result: Some(json),
-            error: None,
-        },
-        Err(e) => McpResponse {
-            success: false,
-            result: None,
-            error: Some(format!("Serialization error: {}", e)),
-        },
+/// Run the verify_settlement tool.
+///
+/// Validates the debts, checks that the payments settle them, and returns
+/// the verification report as JSON.
+fn verify_settlement_tool(params: VerifySettlementParams) -> Result<CallToolResult, JsonRpcError> {
+    if let Err(e) = validate_debts(&params.debts) {
+        return Ok(CallToolResult::validation_error(invalid_debts(&e)));
+    }
+    CallToolResult::json(&verify_settlement(&params.debts, &params.payments))
+}
+
+/// Run the export_graph tool.
+///
+/// Validates the debts and returns the debt graph, or the simplified payment
+/// graph, as DOT or Mermaid text.
+fn export_graph(params: ExportGraphParams) -> CallToolResult {
+    let graph = if params.simplified {
+        try_simplify_debts(&params.debts)
+            .map(|plan| render_payment_graph(&plan.payments, params.format))
+    } else {
+        validate_debts(&params.debts).map(|()| render_debt_graph(&params.debts, params.format))
+    };
+
+    match graph {
+        Ok(graph) => CallToolResult::text(graph),
+        Err(e) => CallToolResult::validation_error(invalid_debts(&e)),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use expense_optimizer::{SimplificationResult, VerificationReport};
+
+    /// Run a legacy request with JSON-encoded params, as the WIT interface
+    /// does.
+    fn handle_request(method: &str, params: &str) -> McpResponse {
+        match McpRequest::decode(method.to_string(), params) {
+            Ok(request) => handle(request),
+            Err(error) => McpResponse::Err(error),
+        }
+    }
 
     #[test]
     fn test_empty_debts_returns_empty_payments() {
         let response = handle_request("optimize_settlements", "[]");
-        assert!(response.success);
         let result: SimplificationResult =
-            serde_json::from_str(&response.result.unwrap()).unwrap();
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert_eq!(result.payments.len(), 0);
     }
 
@@ -108,9 +580,8 @@ mod tests {
     fn test_single_debt_returns_single_payment() {
         let debts = r#"[{"debtor":"A","creditor":"B","amount_cents":5000,"expense_ids":[]}]"#;
         let response = handle_request("optimize_settlements", debts);
-        assert!(response.success);
         let result: SimplificationResult =
-            serde_json::from_str(&response.result.unwrap()).unwrap();
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert_eq!(result.payments.len(), 1);
     }
 
@@ -122,9 +593,8 @@ mod tests {
             {"debtor":"C","creditor":"A","amount_cents":10000,"expense_ids":[]}
         ]"#;
         let response = handle_request("optimize_settlements", debts);
-        assert!(response.success);
         let result: SimplificationResult =
-            serde_json::from_str(&response.result.unwrap()).unwrap();
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert_eq!(result.payments.len(), 0);
     }
 
@@ -135,9 +605,8 @@ mod tests {
             {"debtor":"B","creditor":"C","amount_cents":10000,"expense_ids":[]}
         ]"#;
         let response = handle_request("optimize_settlements", debts);
-        assert!(response.success);
         let result: SimplificationResult =
-            serde_json::from_str(&response.result.unwrap()).unwrap();
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert_eq!(result.payments.len(), 1);
         assert_eq!(result.payments[0].from, "A");
         assert_eq!(result.payments[0].to, "C");
@@ -146,15 +615,17 @@ mod tests {
     #[test]
     fn test_unknown_method_returns_error() {
         let response = handle_request("unknown_method", "{}");
-        assert!(!response.success);
-        assert!(response.error.unwrap().contains("Unknown method"));
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::UnknownMethod);
+        assert_eq!(error.message, "Unknown method: unknown_method");
     }
 
     #[test]
-    fn test_malformed_json_returns_parse_error() {
+    fn test_malformed_json_returns_invalid_params() {
         let response = handle_request("optimize_settlements", "not valid json");
-        assert!(!response.success);
-        assert!(response.error.unwrap().contains("Parse error"));
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(error.pointer.as_deref(), Some(""));
     }
 
     #[test]
@@ -171,9 +642,8 @@ mod tests {
         }
         let debts_json = serde_json::to_string(&debts).unwrap();
         let response = handle_request("optimize_settlements", &debts_json);
-        assert!(response.success);
         let result: SimplificationResult =
-            serde_json::from_str(&response.result.unwrap()).unwrap();
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         // At most n-1 payments for n people
         assert!(result.optimized_count <= 19);
     }
@@ -182,20 +652,417 @@ mod tests {
     fn test_handle_function_works() {
         let request = McpRequest {
             method: "optimize_settlements".to_string(),
-            params: "[]".to_string(),
+            params: json!([]),
         };
         let response = handle(request);
-        assert!(response.success);
+        assert!(response.is_ok());
     }
 
     #[test]
     fn test_handle_with_invalid_method() {
         let request = McpRequest {
             method: "invalid".to_string(),
-            params: "{}".to_string(),
+            params: json!({}),
         };
         let response = handle(request);
-        assert!(!response.success);
-        assert!(response.error.is_some());
+        assert_eq!(
+            response.into_result().unwrap_err().code,
+            ErrorCode::UnknownMethod
+        );
+    }
+
+    #[test]
+    fn test_verify_settlement_accepts_valid_plan() {
+        let params = r#"{
+            "debts": [{"debtor":"A","creditor":"B","amount_cents":5000,"expense_ids":[]}],
+            "payments": [{"from":"A","to":"B","amount_cents":5000,"reason":"Settlement"}]
+        }"#;
+        let response = handle_request("verify_settlement", params);
+        let report: VerificationReport =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
+        assert!(report.valid);
+    }
+
+    #[test]
+    fn test_verify_settlement_reports_residuals() {
+        let params = r#"{
+            "debts": [{"debtor":"A","creditor":"B","amount_cents":5000,"expense_ids":[]}],
+            "payments": [{"from":"A","to":"B","amount_cents":2000,"reason":"Settlement"}]
+        }"#;
+        let response = handle_request("verify_settlement", params);
+        let report: VerificationReport =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.residuals.len(), 2);
+    }
+
+    #[test]
+    fn test_verify_settlement_malformed_params() {
+        let response = handle_request("verify_settlement", "[]");
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(error.pointer.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_export_graph_renders_debts_as_dot() {
+        let params = r#"{
+            "debts": [{"debtor":"A","creditor":"B","amount_cents":5000,"expense_ids":[]}],
+            "format": "dot"
+        }"#;
+        let response = handle_request("export_graph", params);
+        let graph = response.into_result().unwrap();
+        assert!(graph.starts_with("digraph"));
+        assert!(graph.contains("\"A\" -> \"B\" [label=\"$50.00\"]"));
+    }
+
+    #[test]
+    fn test_export_graph_renders_simplified_mermaid() {
+        let params = r#"{
+            "debts": [
+                {"debtor":"A","creditor":"B","amount_cents":5000,"expense_ids":[]},
+                {"debtor":"B","creditor":"C","amount_cents":5000,"expense_ids":[]}
+            ],
+            "format": "mermaid",
+            "simplified": true
+        }"#;
+        let response = handle_request("export_graph", params);
+        let graph = response.into_result().unwrap();
+        assert!(graph.starts_with("flowchart LR"));
+        assert_eq!(graph.matches("-->").count(), 1);
+    }
+
+    #[test]
+    fn test_export_graph_rejects_unknown_format() {
+        let params = r#"{"debts": [], "format": "svg"}"#;
+        let response = handle_request("export_graph", params);
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(error.pointer.as_deref(), Some("/format"));
+    }
+
+    #[test]
+    fn test_export_graph_rejects_overflowing_debts() {
+        let params = r#"{
+            "debts": [
+                {"debtor":"A","creditor":"B","amount_cents":9223372036854775807,"expense_ids":[]},
+                {"debtor":"B","creditor":"C","amount_cents":9223372036854775807,"expense_ids":[]}
+            ],
+            "format": "dot",
+            "simplified": true
+        }"#;
+        let response = handle_request("export_graph", params);
+        assert_eq!(
+            response.into_result().unwrap_err(),
+            McpError::validation("/debts/1/amount_cents", "debt 1: total amount overflows i64")
+        );
+    }
+
+    #[test]
+    fn test_verify_settlement_rejects_invalid_debts() {
+        let params = r#"{
+            "debts": [{"debtor":"A","creditor":"A","amount_cents":500,"expense_ids":[]}],
+            "payments": []
+        }"#;
+        let response = handle_request("verify_settlement", params);
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::Validation);
+        assert_eq!(error.pointer.as_deref(), Some("/debts/0/creditor"));
+    }
+
+    #[test]
+    fn test_invalid_debt_returns_validation_error() {
+        let params = r#"[
+            {"debtor":"A","creditor":"B","amount_cents":5000,"expense_ids":[]},
+            {"debtor":"B","creditor":"C","amount_cents":-100,"expense_ids":[]}
+        ]"#;
+        let response = handle_request("optimize_settlements", params);
+        assert_eq!(
+            response.into_result().unwrap_err(),
+            McpError::validation(
+                "/debts/1/amount_cents",
+                "debt 1: amount_cents must be positive, got -100"
+            )
+        );
+    }
+
+    fn jsonrpc(message: serde_json::Value) -> serde_json::Value {
+        serde_json::from_str(&handle_jsonrpc(&message.to_string()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_jsonrpc_initialize_reports_server_info() {
+        let response = jsonrpc(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {"protocolVersion": "2025-06-18", "capabilities": {}}
+        }));
+        assert_eq!(response["result"]["protocolVersion"], "2025-06-18");
+        assert_eq!(
+            response["result"]["serverInfo"]["name"],
+            "expense-optimizer-mcp"
+        );
+        assert!(response["result"]["capabilities"]["tools"].is_object());
+        assert!(response["result"]["capabilities"]["resources"].is_object());
+        assert!(response["result"]["capabilities"]["prompts"].is_object());
+    }
+
+    #[test]
+    fn test_jsonrpc_tools_list_has_schemas() {
+        let response = jsonrpc(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}));
+        let tools = response["result"]["tools"].as_array().unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t["name"].as_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "optimize_settlements",
+                "optimize_settlements_many",
+                "save_debts",
+                "verify_settlement",
+                "export_graph"
+            ]
+        );
+        for tool in tools {
+            assert_eq!(tool["inputSchema"]["type"], "object");
+            assert!(
+                !tool["inputSchema"]["required"]
+                    .as_array()
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+    }
+
+    #[test]
+    fn test_jsonrpc_tools_call_returns_content() {
+        let response = jsonrpc(json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "tools/call",
+            "params": {
+                "name": "optimize_settlements",
+                "arguments": {"debts": [
+                    {"debtor": "A", "creditor": "B", "amount_cents": 10000, "expense_ids": []},
+                    {"debtor": "B", "creditor": "C", "amount_cents": 10000, "expense_ids": []}
+                ]}
+            }
+        }));
+        assert_eq!(response["id"], 3);
+        let result = &response["result"];
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["content"][0]["type"], "text");
+        let text: SimplificationResult =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(text.payments.len(), 1);
+        assert_eq!(result["structuredContent"]["payments"][0]["from"], "A");
+        assert_eq!(result["structuredContent"]["payments"][0]["to"], "C");
+    }
+
+    #[test]
+    fn test_jsonrpc_validation_failure_is_tool_error() {
+        let response = jsonrpc(json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "tools/call",
+            "params": {
+                "name": "optimize_settlements",
+                "arguments": {"debts": [
+                    {"debtor": "A", "creditor": "A", "amount_cents": 100, "expense_ids": []}
+                ]}
+            }
+        }));
+        assert_eq!(response["result"]["isError"], true);
+        assert_eq!(
+            response["result"]["content"][0]["text"],
+            "Validation error: debt 0: A cannot owe themselves"
+        );
+        assert_eq!(
+            response["result"]["structuredContent"],
+            json!({
+                "code": "validation",
+                "message": "debt 0: A cannot owe themselves",
+                "pointer": "/debts/0/creditor"
+            })
+        );
+    }
+
+    #[test]
+    fn test_jsonrpc_bad_arguments_are_invalid_params() {
+        let response = jsonrpc(json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "tools/call",
+            "params": {"name": "export_graph", "arguments": {"debts": [], "format": "svg"}}
+        }));
+        assert_eq!(response["error"]["code"], -32602);
+        assert_eq!(response["error"]["data"]["pointer"], "/format");
+
+        let response = jsonrpc(json!({
+            "jsonrpc": "2.0",
+            "id": 6,
+            "method": "tools/call",
+            "params": {"name": "optimize", "arguments": {}}
+        }));
+        assert_eq!(response["error"]["code"], -32602);
+        assert_eq!(response["error"]["message"], "Unknown tool: optimize");
+    }
+
+    /// Send a message to `server` and return the `result`, or the `error`.
+    fn rpc(server: &ExpenseOptimizerServer, method: &str, params: Value) -> Value {
+        let message = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        let response: Value =
+            serde_json::from_str(&handle_message(server, &message.to_string()).unwrap()).unwrap();
+        match response.get("result") {
+            Some(result) => result.clone(),
+            None => response["error"].clone(),
+        }
+    }
+
+    /// A server with A owing B 3000 and B owing "Cara Lee" 1000 saved.
+    fn saved_trip() -> ExpenseOptimizerServer {
+        let server = ExpenseOptimizerServer::default();
+        let result = rpc(
+            &server,
+            "tools/call",
+            json!({"name": "save_debts", "arguments": {"debts": [
+                {"debtor": "A", "creditor": "B", "amount_cents": 3000, "expense_ids": ["e1"]},
+                {"debtor": "B", "creditor": "Cara Lee", "amount_cents": 1000, "expense_ids": ["e2"]}
+            ]}}),
+        );
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["structuredContent"]["optimized_count"], 2);
+        server
+    }
+
+    #[test]
+    fn test_saved_debts_are_readable_resources() {
+        let server = saved_trip();
+
+        let listed = rpc(&server, "resources/list", json!({}));
+        let uris: Vec<&str> = listed["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["uri"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            uris,
+            vec![
+                PLAN_URI,
+                "trip://balances/A",
+                "trip://balances/B",
+                "trip://balances/Cara%20Lee"
+            ]
+        );
+
+        let read = rpc(&server, "resources/read", json!({"uri": PLAN_URI}));
+        let plan: SimplificationResult =
+            serde_json::from_str(read["contents"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(plan.original_count, 2);
+
+        let read = rpc(
+            &server,
+            "resources/read",
+            json!({"uri": "trip://balances/Cara%20Lee"}),
+        );
+        let balance: PersonBalance =
+            serde_json::from_str(read["contents"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(balance.net_balance_cents, 1000);
+        assert!(balance.pays.is_empty());
+        assert_eq!(balance.receives[0].from, "A");
+
+        let error = rpc(
+            &server,
+            "resources/read",
+            json!({"uri": "trip://balances/Zed"}),
+        );
+        assert_eq!(error["code"], -32002);
+    }
+
+    #[test]
+    fn test_prompts_embed_saved_state() {
+        let server = saved_trip();
+
+        let listed = rpc(&server, "prompts/list", json!({}));
+        assert_eq!(listed["prompts"][0]["name"], "explain_my_balance");
+        assert_eq!(listed["prompts"][1]["name"], "draft_settle_up_message");
+
+        let prompt = rpc(
+            &server,
+            "prompts/get",
+            json!({"name": "explain_my_balance", "arguments": {"person": "B"}}),
+        );
+        let text = prompt["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.contains("Explain to B"));
+        assert!(text.contains("\"net_balance_cents\":2000"));
+
+        let prompt = rpc(
+            &server,
+            "prompts/get",
+            json!({"name": "draft_settle_up_message", "arguments": {"tone": "brief"}}),
+        );
+        let text = prompt["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.starts_with("Draft a short brief message"));
+        assert!(text.contains("\"to\":\"Cara Lee\""));
+
+        let error = rpc(
+            &server,
+            "prompts/get",
+            json!({"name": "explain_my_balance", "arguments": {"person": "Zed"}}),
+        );
+        assert_eq!(error["code"], -32602);
+        let error = rpc(
+            &server,
+            "prompts/get",
+            json!({"name": "explain_my_balance"}),
+        );
+        assert_eq!(error["data"], "missing argument `person`");
+    }
+
+    #[test]
+    fn test_save_debts_rejects_invalid_debts() {
+        let server = saved_trip();
+        let result = rpc(
+            &server,
+            "tools/call",
+            json!({"name": "save_debts", "arguments": {"debts": [
+                {"debtor": "A", "creditor": "A", "amount_cents": 5, "expense_ids": []}
+            ]}}),
+        );
+        assert_eq!(result["isError"], true);
+        // The previously saved debts are kept
+        let listed = rpc(&server, "resources/list", json!({}));
+        assert_eq!(listed["resources"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_optimize_settlements_many_reports_each_group() {
+        let result = rpc(
+            &ExpenseOptimizerServer::default(),
+            "tools/call",
+            json!({"name": "optimize_settlements_many", "arguments": {"groups": [
+                {"debts": [
+                    {"debtor": "A", "creditor": "B", "amount_cents": 3000, "expense_ids": []},
+                    {"debtor": "B", "creditor": "C", "amount_cents": 3000, "expense_ids": []}
+                ]},
+                {"debts": [
+                    {"debtor": "A", "creditor": "B", "amount_cents": 100, "expense_ids": []},
+                    {"debtor": "B", "creditor": "C", "amount_cents": -5, "expense_ids": []}
+                ]},
+                {"debts": "none"}
+            ]}}),
+        );
+        assert_eq!(result["isError"], false);
+        let results = &result["structuredContent"]["results"];
+        assert_eq!(results[0]["ok"]["payments"][0]["from"], "A");
+        assert_eq!(results[0]["ok"]["payments"][0]["to"], "C");
+        assert_eq!(results[1]["err"]["code"], "validation");
+        assert_eq!(
+            results[1]["err"]["pointer"],
+            "/groups/1/debts/1/amount_cents"
+        );
+        assert_eq!(results[2]["err"]["code"], "invalid_params");
+        assert_eq!(results[2]["err"]["pointer"], "/groups/2/debts");
     }
 }
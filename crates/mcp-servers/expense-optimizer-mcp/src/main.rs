@@ -0,0 +1,66 @@
+//! Expense Optimizer MCP server.
+//!
+//! By default reads newline-delimited JSON-RPC messages from stdin and writes
+//! responses to stdout, logging to stderr. An MCP client can launch it with a
+//! config entry such as:
+//!
+//! ```json
+//! { "mcpServers": { "expense-optimizer": { "command": "expense-optimizer-mcp" } } }
+//! ```
+//!
+//! Saved debts are kept in memory unless `--state <file>` names a JSON file
+//! to keep them in across restarts.
+//!
+//! Built with the `http` feature, `expense-optimizer-mcp --http 127.0.0.1:8080`
+//! serves the streamable HTTP transport at `/mcp` instead. Each HTTP session
+//! keeps its own saved debts in memory, so clients never see each other's
+//! trips; `--state` cannot be combined with `--http`.
+
+use std::process::ExitCode;
+
+use expense_optimizer_mcp::{ExpenseOptimizerServer, FileStore};
+
+#[cfg(feature = "http")]
+const USAGE: &str = "usage: expense-optimizer-mcp [--state <file>] [--http <addr>]";
+#[cfg(not(feature = "http"))]
+const USAGE: &str = "usage: expense-optimizer-mcp [--state <file>]";
+
+fn main() -> ExitCode {
+    let mut state = None;
+    let mut http = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        match (flag.as_str(), args.next()) {
+            ("--state", Some(path)) if state.is_none() => state = Some(path),
+            ("--http", Some(addr)) if cfg!(feature = "http") && http.is_none() => http = Some(addr),
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let served = match (http, state) {
+        #[cfg(feature = "http")]
+        (Some(addr), None) => {
+            mcp_protocol::serve_http_per_session(ExpenseOptimizerServer::default, &addr)
+        }
+        (Some(_), Some(_)) => {
+            eprintln!("expense-optimizer-mcp: --state cannot be shared by HTTP sessions");
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+        (_, Some(path)) => {
+            mcp_protocol::serve_stdio(&ExpenseOptimizerServer::with_store(FileStore::new(path)))
+        }
+        (_, None) => mcp_protocol::serve_stdio(&ExpenseOptimizerServer::default()),
+    };
+
+    match served {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("expense-optimizer-mcp: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
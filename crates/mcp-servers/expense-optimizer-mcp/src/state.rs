@@ -0,0 +1,149 @@
+//! Saved trip state behind the server's resources and prompts.
+//!
+//! Agents save a group's debts once with the `save_debts` tool. The
+//! settlement plan and balance resources, and the prompts, are then worked
+//! out from the saved debts, so later requests need not re-send them. Where
+//! the debts are kept is up to the [`StateStore`]: [`MemoryStore`] for the
+//! life of the process or [`FileStore`] across restarts.
+
+use std::fmt::Debug;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+
+use expense_optimizer::Debt;
+use serde::{Deserialize, Serialize};
+
+/// Everything the server remembers about a trip.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TripState {
+    /// Every debt in the group, as last saved.
+    pub debts: Vec<Debt>,
+}
+
+/// Where the server keeps its trip state.
+pub trait StateStore: Debug + Send + Sync {
+    /// Load the saved state, or an empty state if nothing was saved.
+    ///
+    /// # Errors
+    /// Returns an error if saved state exists but cannot be read
+    fn load(&self) -> io::Result<TripState>;
+
+    /// Replace the saved state.
+    ///
+    /// # Errors
+    /// Returns an error if the state cannot be written
+    fn save(&self, state: &TripState) -> io::Result<()>;
+}
+
+/// Keeps trip state in memory for the life of the process.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    state: Mutex<TripState>,
+}
+
+impl StateStore for MemoryStore {
+    fn load(&self) -> io::Result<TripState> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone())
+    }
+
+    fn save(&self, state: &TripState) -> io::Result<()> {
+        *self.state.lock().unwrap_or_else(PoisonError::into_inner) = state.clone();
+        Ok(())
+    }
+}
+
+/// Keeps trip state in a JSON file.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    /// Store trip state at `path`, which need not exist yet.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Where [`save`](StateStore::save) writes before renaming over the
+    /// file: beside it, with `.tmp` appended to the whole name so that it
+    /// never names the file itself.
+    fn temp_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_owned();
+        name.push(".tmp");
+        self.path.with_file_name(name)
+    }
+}
+
+impl StateStore for FileStore {
+    fn load(&self) -> io::Result<TripState> {
+        match std::fs::read(&self.path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(TripState::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&self, state: &TripState) -> io::Result<()> {
+        // Writing beside the file and renaming keeps a crash from leaving
+        // half-written state behind
+        let temp = self.temp_path();
+        std::fs::write(&temp, serde_json::to_vec_pretty(state)?)?;
+        std::fs::rename(&temp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> TripState {
+        TripState {
+            debts: vec![Debt {
+                debtor: "A".to_string(),
+                creditor: "B".to_string(),
+                amount_cents: 1200,
+                expense_ids: vec!["e1".to_string()],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_memory_store_round_trip() {
+        let store = MemoryStore::default();
+        assert_eq!(store.load().unwrap(), TripState::default());
+        store.save(&state()).unwrap();
+        assert_eq!(store.load().unwrap(), state());
+    }
+
+    #[test]
+    fn test_file_store_round_trip() {
+        let path = std::env::temp_dir().join(format!("trip-state-{}.json", std::process::id()));
+        let store = FileStore::new(&path);
+        assert_eq!(store.load().unwrap(), TripState::default());
+
+        store.save(&state()).unwrap();
+        assert_eq!(FileStore::new(&path).load().unwrap(), state());
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(store.load().unwrap_err().kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_store_keeps_a_tmp_extension() {
+        let path = std::env::temp_dir().join(format!("trip-state-{}.tmp", std::process::id()));
+        let store = FileStore::new(&path);
+        assert_eq!(store.temp_path(), path.with_extension("tmp.tmp"));
+
+        store.save(&state()).unwrap();
+        assert_eq!(store.load().unwrap(), state());
+        assert!(!store.temp_path().exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
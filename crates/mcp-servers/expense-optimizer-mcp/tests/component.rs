@@ -0,0 +1,128 @@
+//! Runs the crate as a WebAssembly component in a local wasmtime host.
+//!
+//! The crate is built for `wasm32-unknown-unknown` once per test run,
+//! encoded as a component, and instantiated against the
+//! `expense-optimizer-mcp` world with no imports. The build needs that target
+//! installed, so these tests are ignored by default; CI runs them with
+//! `cargo test -- --ignored`.
+
+use std::sync::LazyLock;
+
+use mcp_test_support::build_component;
+use serde_json::{Value, json};
+use wasmtime::component::{Component, Linker, bindgen};
+use wasmtime::{Engine, Store};
+
+bindgen!({
+    world: "steamboat:expense-optimizer/expense-optimizer-mcp",
+    path: ["../wit/mcp-server.wit", "../wit/expense-optimizer.wit"],
+});
+
+/// The compiled component, shared by every test.
+static COMPONENT: LazyLock<(Engine, Component)> = LazyLock::new(|| {
+    let bytes = build_component(
+        env!("CARGO_MANIFEST_DIR"),
+        env!("CARGO_TARGET_TMPDIR"),
+        "expense_optimizer_mcp",
+    );
+    let engine = Engine::default();
+    let component = Component::new(&engine, bytes).unwrap();
+    (engine, component)
+});
+
+fn instantiate() -> (Store<()>, ExpenseOptimizerMcp) {
+    let (engine, component) = &*COMPONENT;
+    let mut store = Store::new(engine, ());
+    let instance = ExpenseOptimizerMcp::instantiate(&mut store, component, &Linker::new(engine))
+        .expect("the component should need no imports");
+    (store, instance)
+}
+
+#[test]
+#[ignore = "builds the crate for wasm32-unknown-unknown; run with --ignored"]
+fn test_legacy_handle() {
+    let (mut store, instance) = instantiate();
+    let handler = instance.steamboat_mcp_server_handler();
+
+    let request = McpRequest {
+        method: "optimize_settlements".to_string(),
+        params: json!([
+            {"debtor": "A", "creditor": "B", "amount_cents": 700, "expense_ids": []},
+            {"debtor": "B", "creditor": "C", "amount_cents": 700, "expense_ids": []}
+        ])
+        .to_string(),
+    };
+    let McpResponse::Ok(output) = handler.call_handle(&mut store, &request).unwrap() else {
+        panic!("optimize_settlements should succeed");
+    };
+    let result: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(result["payments"][0]["from"], "A");
+    assert_eq!(result["payments"][0]["to"], "C");
+
+    let request = McpRequest {
+        method: "unknown_method".to_string(),
+        params: "{}".to_string(),
+    };
+    let McpResponse::Err(error) = handler.call_handle(&mut store, &request).unwrap() else {
+        panic!("unknown_method should fail");
+    };
+    assert!(matches!(error.code, ErrorCode::UnknownMethod));
+    assert_eq!(error.message, "Unknown method: unknown_method");
+
+    let request = McpRequest {
+        method: "optimize_settlements".to_string(),
+        params: json!({"debts": [
+            {"debtor": "A", "creditor": "B", "amount_cents": 0, "expense_ids": []}
+        ]})
+        .to_string(),
+    };
+    let McpResponse::Err(error) = handler.call_handle(&mut store, &request).unwrap() else {
+        panic!("a zero amount should be rejected");
+    };
+    assert!(matches!(error.code, ErrorCode::Validation));
+    assert_eq!(error.pointer.as_deref(), Some("/debts/0/amount_cents"));
+}
+
+#[test]
+#[ignore = "builds the crate for wasm32-unknown-unknown; run with --ignored"]
+fn test_jsonrpc_messages() {
+    let (mut store, instance) = instantiate();
+    let handler = instance.steamboat_mcp_server_handler();
+    let mut call = |message: Value| {
+        handler
+            .call_handle_jsonrpc(&mut store, &message.to_string())
+            .unwrap()
+            .map(|response| serde_json::from_str::<Value>(&response).unwrap())
+    };
+
+    let response = call(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {"protocolVersion": "2025-06-18", "capabilities": {}}
+    }))
+    .unwrap();
+    assert_eq!(
+        response["result"]["serverInfo"]["name"],
+        "expense-optimizer-mcp"
+    );
+
+    let response = call(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}));
+    assert!(response.is_none());
+
+    let response = call(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "verify_settlement",
+            "arguments": {
+                "debts": [{"debtor": "A", "creditor": "B", "amount_cents": 500, "expense_ids": []}],
+                "payments": [{"from": "A", "to": "B", "amount_cents": 500, "reason": "dinner"}]
+            }
+        }
+    }))
+    .unwrap();
+    assert_eq!(response["id"], 2);
+    assert_eq!(response["result"]["structuredContent"]["valid"], true);
+}
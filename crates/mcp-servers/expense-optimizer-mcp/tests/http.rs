@@ -0,0 +1,329 @@
+//! Exercises the streamable HTTP transport over a loopback listener.
+
+use expense_optimizer_mcp::{ExpenseOptimizerServer, PLAN_URI};
+use mcp_protocol::http::{PROTOCOL_VERSION_HEADER, SESSION_ID_HEADER};
+use mcp_test_support::{ACCEPT, HttpSession, start_http};
+use mcp_protocol::{HttpTransport, JsonRpcRequest};
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+
+/// Start a server per session, as the binary does, on an ephemeral port and
+/// return its endpoint URL.
+async fn start() -> (String, HttpTransport<ExpenseOptimizerServer>) {
+    let transport = HttpTransport::per_session(ExpenseOptimizerServer::default);
+    (start_http(transport.clone()).await, transport)
+}
+
+/// Run `initialize` and return the new session.
+async fn initialize(url: &str) -> HttpSession {
+    let (session, body) = HttpSession::initialize(url, "2025-06-18").await;
+    assert_eq!(
+        body["result"]["serverInfo"]["name"],
+        "expense-optimizer-mcp"
+    );
+    session
+}
+
+#[tokio::test]
+async fn test_session_lifecycle() {
+    let (url, transport) = start().await;
+    let client = Client::new();
+
+    let session = initialize(&url).await;
+    assert_eq!(session.id().len(), 32);
+    assert_eq!(transport.session_count(), 1);
+
+    let response = session
+        .post(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+        .await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let response = session
+        .post(&json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "optimize_settlements",
+                "arguments": {"debts": [
+                    {"debtor": "A", "creditor": "B", "amount_cents": 700, "expense_ids": []},
+                    {"debtor": "B", "creditor": "C", "amount_cents": 700, "expense_ids": []}
+                ]}
+            }
+        }))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/json");
+    let body: Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(body["id"], 2);
+    assert_eq!(
+        body["result"]["structuredContent"]["payments"][0]["amount_cents"],
+        700
+    );
+
+    let response = client
+        .delete(&url)
+        .header(SESSION_ID_HEADER, session.id())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(transport.session_count(), 0);
+
+    let response = session
+        .post(&json!({"jsonrpc": "2.0", "id": 3, "method": "ping"}))
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_sessions_keep_their_own_trips() {
+    let (url, _transport) = start().await;
+    let alice = initialize(&url).await;
+    let mallory = initialize(&url).await;
+
+    let body = alice
+        .request(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "save_debts",
+                "arguments": {"debts": [
+                    {"debtor": "A", "creditor": "B", "amount_cents": 900, "expense_ids": []}
+                ]}
+            }
+        }))
+        .await;
+    assert_eq!(body["result"]["isError"], false);
+
+    let read_plan = json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "resources/read",
+        "params": {"uri": PLAN_URI}
+    });
+    for (session, original_count) in [(&alice, 1), (&mallory, 0)] {
+        let body = session.request(&read_plan).await;
+        let plan: Value =
+            serde_json::from_str(body["result"]["contents"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(plan["original_count"], original_count);
+    }
+}
+
+#[tokio::test]
+async fn test_requests_need_a_session() {
+    let (url, _transport) = start().await;
+    let client = Client::new();
+    let tools_list = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}).to_string();
+
+    let response = client
+        .post(&url)
+        .header("accept", ACCEPT)
+        .body(tools_list.clone())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .post(&url)
+        .header("accept", ACCEPT)
+        .header(SESSION_ID_HEADER, "not-a-session")
+        .body(tools_list)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_protocol_errors() {
+    let (url, _transport) = start().await;
+    let client = Client::new();
+    let session = initialize(&url).await;
+
+    let response = client
+        .post(&url)
+        .header(SESSION_ID_HEADER, session.id())
+        .body("{not json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(body["error"]["code"], -32700);
+
+    // Unsupported, and supported but not the version this session negotiated
+    for version in ["1999-01-01", "2025-03-26"] {
+        let response = client
+            .post(&url)
+            .header(SESSION_ID_HEADER, session.id())
+            .header(PROTOCOL_VERSION_HEADER, version)
+            .body(json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}).to_string())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", version);
+    }
+
+    // JSON-RPC errors on a valid request still travel in a 200 response
+    let body = session
+        .request(&json!({"jsonrpc": "2.0", "id": 4, "method": "roots/list"}))
+        .await;
+    assert_eq!(body["error"]["code"], -32601);
+}
+
+#[tokio::test]
+async fn test_browsers_need_an_allowed_origin() {
+    let (url, _transport) = start().await;
+    let client = Client::new();
+    let session = initialize(&url).await;
+    let ping = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}).to_string();
+
+    for (origin, status) in [
+        ("http://localhost:3000", StatusCode::OK),
+        ("http://127.0.0.1", StatusCode::OK),
+        ("http://localhost.evil.example", StatusCode::FORBIDDEN),
+        ("https://evil.example", StatusCode::FORBIDDEN),
+        ("null", StatusCode::FORBIDDEN),
+    ] {
+        let response = client
+            .post(&url)
+            .header("origin", origin)
+            .header(SESSION_ID_HEADER, session.id())
+            .body(ping.clone())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), status, "{}", origin);
+    }
+
+    let url = start_http(HttpTransport::with_allowed_origins(
+        ExpenseOptimizerServer::default(),
+        &["https://app.example.com"],
+    ))
+    .await;
+    let response = client
+        .post(&url)
+        .header("accept", ACCEPT)
+        .header("origin", "https://app.example.com")
+        .body(
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {"protocolVersion": "2025-06-18", "capabilities": {}}
+            })
+            .to_string(),
+        )
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_batches_share_one_response() {
+    let (url, _transport) = start().await;
+    // Batching exists only in 2025-03-26
+    let (session, _) = HttpSession::initialize(&url, "2025-03-26").await;
+
+    let body = session
+        .request(&json!([
+            {"jsonrpc": "2.0", "method": "notifications/initialized"},
+            {"jsonrpc": "2.0", "id": "a", "method": "ping"},
+            {
+                "jsonrpc": "2.0",
+                "id": "b",
+                "method": "tools/call",
+                "params": {
+                    "name": "optimize_settlements",
+                    "arguments": {"debts": [
+                        {"debtor": "A", "creditor": "B", "amount_cents": 400, "expense_ids": []}
+                    ]}
+                }
+            },
+            {"jsonrpc": "2.0", "id": "c", "method": "roots/list"}
+        ]))
+        .await;
+    let ids: Vec<&Value> = body.as_array().unwrap().iter().map(|r| &r["id"]).collect();
+    assert_eq!(ids, [&json!("a"), &json!("b"), &json!("c")]);
+    assert_eq!(
+        body[1]["result"]["structuredContent"]["payments"][0]["amount_cents"],
+        400
+    );
+    assert_eq!(body[2]["error"]["code"], -32601);
+
+    let response = session
+        .post(&json!([{"jsonrpc": "2.0", "method": "notifications/initialized"}]))
+        .await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let response = session.post(&json!([])).await;
+    let body: Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(body["error"]["code"], -32600);
+
+    let session = initialize(&url).await;
+    let response = session
+        .post(&json!([{"jsonrpc": "2.0", "id": 1, "method": "ping"}]))
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(body["error"]["code"], -32600);
+}
+
+#[tokio::test]
+async fn test_event_stream_delivers_notifications() {
+    let (url, transport) = start().await;
+    let client = Client::new();
+    let session = initialize(&url).await;
+
+    let response = client
+        .get(&url)
+        .header(SESSION_ID_HEADER, session.id())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+
+    let mut stream = client
+        .get(&url)
+        .header("accept", "text/event-stream")
+        .header(SESSION_ID_HEADER, session.id())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(stream.status(), StatusCode::OK);
+    assert_eq!(stream.headers()["content-type"], "text/event-stream");
+
+    let notification = JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        id: None,
+        method: "notifications/tools/list_changed".to_string(),
+        params: None,
+    };
+    assert_eq!(transport.notify(&notification), 1);
+
+    let mut received = String::new();
+    while !received.contains("\n\n") {
+        let chunk = stream.chunk().await.unwrap().unwrap();
+        received.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+    assert!(received.starts_with("event: message\n"));
+    let data = received
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .unwrap();
+    let message: Value = serde_json::from_str(data).unwrap();
+    assert_eq!(message["method"], "notifications/tools/list_changed");
+
+    // Closing the session ends its streams
+    client
+        .delete(&url)
+        .header(SESSION_ID_HEADER, session.id())
+        .send()
+        .await
+        .unwrap();
+    assert!(stream.chunk().await.unwrap().is_none());
+}
@@ -0,0 +1,49 @@
+//! Runs the stdio binary the way an MCP client would.
+
+use mcp_test_support::run_stdio;
+use serde_json::json;
+
+#[test]
+fn test_stdio_session() {
+    let run = run_stdio(
+        env!("CARGO_BIN_EXE_expense-optimizer-mcp"),
+        &[],
+        &[
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {},
+                    "clientInfo": {"name": "test", "version": "0"}
+                }
+            }),
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {
+                    "name": "optimize_settlements",
+                    "arguments": {"debts": [
+                        {"debtor": "A", "creditor": "B", "amount_cents": 500, "expense_ids": []},
+                        {"debtor": "B", "creditor": "C", "amount_cents": 500, "expense_ids": []}
+                    ]}
+                }
+            }),
+        ],
+    );
+
+    assert_eq!(run.responses.len(), 2);
+    assert_eq!(
+        run.responses[0]["result"]["serverInfo"]["name"],
+        "expense-optimizer-mcp"
+    );
+    assert_eq!(run.responses[1]["id"], 2);
+    assert_eq!(
+        run.responses[1]["result"]["structuredContent"]["payments"][0]["to"],
+        "C"
+    );
+    assert!(run.log.contains("serving MCP over stdio"));
+}
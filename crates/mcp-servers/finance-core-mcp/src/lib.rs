@@ -0,0 +1,441 @@
+//! Finance Core MCP Server
+//!
+//! This crate provides an MCP (Model Context Protocol) server interface for
+//! the finance-core expense calculations, so agents can split expenses and
+//! work out balances from raw `Expense` records. It speaks JSON-RPC 2.0
+//! through [`handle_jsonrpc`] and accepts the legacy request format through
+//! [`handle`]. Built for `wasm32`, it is a WebAssembly component exporting
+//! the WIT `handler` interface.
+
+#[cfg(target_arch = "wasm32")]
+mod component;
+
+use finance_core::{
+    BalanceSummary, CategoryTotal, Cents, Expense, calculate_all_balances_impl,
+    category_breakdown_impl, explain_balance_impl, split_expense_impl,
+};
+use mcp_protocol::{
+    CallToolResult, Implementation, JsonRpcError, McpServer, Tool, handle_legacy, handle_message,
+    parse_arguments,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+pub use mcp_protocol::{ErrorCode, McpError, McpRequest, McpResponse};
+
+/// Parameters for the split_expense tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SplitExpenseParams {
+    /// The expense to split.
+    pub expense: Expense,
+}
+
+/// Parameters for the calculate_balances and category_breakdown tools.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpensesParams {
+    /// All expenses in the group.
+    pub expenses: Vec<Expense>,
+}
+
+/// Parameters for the explain_balance tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExplainBalanceParams {
+    /// All expenses in the group.
+    pub expenses: Vec<Expense>,
+    /// The person whose balance to explain.
+    pub attendee_id: String,
+}
+
+/// Result of the calculate_balances tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BalancesResult {
+    /// Each person's balance, sorted by person.
+    pub balances: Vec<BalanceSummary>,
+}
+
+/// Result of the category_breakdown tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryBreakdownResult {
+    /// Spending per category, largest first.
+    pub categories: Vec<CategoryTotal>,
+}
+
+/// The finance core calculations as an MCP server.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FinanceCoreServer;
+
+impl McpServer for FinanceCoreServer {
+    fn server_info(&self) -> Implementation {
+        Implementation::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    }
+
+    fn instructions(&self) -> Option<String> {
+        Some(
+            "Amounts are integer cents. Pass every expense in the group so balances net out \
+             correctly."
+                .to_string(),
+        )
+    }
+
+    fn tools(&self) -> Vec<Tool> {
+        vec![
+            Tool::new(
+                "split_expense",
+                "Split one expense evenly among its participants, giving leftover cents to \
+                 the first participants so the shares add up exactly.",
+                json!({
+                    "type": "object",
+                    "properties": {"expense": expense_schema()},
+                    "required": ["expense"]
+                }),
+            ),
+            Tool::new(
+                "calculate_balances",
+                "Total what each person paid and owes across a group's expenses, giving \
+                 their net balance. Positive means others owe them.",
+                json!({
+                    "type": "object",
+                    "properties": {"expenses": expenses_schema()},
+                    "required": ["expenses"]
+                }),
+            ),
+            Tool::new(
+                "explain_balance",
+                "Break one person's net balance down expense by expense, showing what they \
+                 paid and their share of each.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "expenses": expenses_schema(),
+                        "attendee_id": {
+                            "type": "string",
+                            "description": "The person whose balance to explain"
+                        }
+                    },
+                    "required": ["expenses", "attendee_id"]
+                }),
+            ),
+            Tool::new(
+                "category_breakdown",
+                "Total a group's spending by category, largest first, with each \
+                 participant's share of every category.",
+                json!({
+                    "type": "object",
+                    "properties": {"expenses": expenses_schema()},
+                    "required": ["expenses"]
+                }),
+            ),
+        ]
+    }
+
+    fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, JsonRpcError> {
+        match name {
+            "split_expense" => split_expense(parse_arguments(arguments)?),
+            "calculate_balances" => calculate_balances(parse_arguments(arguments)?),
+            "explain_balance" => explain_balance(parse_arguments(arguments)?),
+            "category_breakdown" => category_breakdown(parse_arguments(arguments)?),
+            _ => Err(JsonRpcError::unknown_tool(name)),
+        }
+    }
+}
+
+/// Handle a JSON-RPC 2.0 message.
+///
+/// # Arguments
+/// * `message` - The raw JSON-RPC message
+///
+/// # Returns
+/// The JSON-encoded response, or `None` if the message was a notification
+pub fn handle_jsonrpc(message: &str) -> Option<String> {
+    handle_message(&FinanceCoreServer, message)
+}
+
+/// Handle a legacy MCP request and return an MCP response.
+///
+/// # Arguments
+/// * `request` - The MCP request to process
+///
+/// # Returns
+/// An McpResponse containing either the result or an error
+pub fn handle(request: McpRequest) -> McpResponse {
+    handle_legacy(&FinanceCoreServer, request, |_, params| params)
+}
+
+/// JSON Schema for a single expense.
+fn expense_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "id": {"type": "string"},
+            "payer_id": {"type": "string", "description": "Person who paid"},
+            "amount_cents": {"type": "integer", "minimum": 1},
+            "participants": {
+                "type": "array",
+                "items": {"type": "string"},
+                "minItems": 1,
+                "description": "People sharing the expense, including the payer if they do"
+            },
+            "category": {"type": "string", "description": "e.g., \"food\" or \"transport\""},
+            "description": {"type": "string"}
+        },
+        "required": ["id", "payer_id", "amount_cents", "participants", "category", "description"]
+    })
+}
+
+/// JSON Schema for a list of expenses.
+fn expenses_schema() -> Value {
+    json!({"type": "array", "items": expense_schema()})
+}
+
+/// Check that every expense can be split.
+///
+/// An expense without participants would leave its payer owed money that
+/// nobody owes, and a non-positive amount is never a real expense. The
+/// amounts must also add up to less than `i64::MAX` cents, which bounds
+/// every total, share, and balance worked out from them.
+///
+/// # Arguments
+/// * `expenses` - The expenses to check
+/// * `pointer` - JSON pointer to the expense at an index in the arguments
+///
+/// # Errors
+/// Returns a validation error for the first invalid expense, pointing at
+/// the offending field
+fn validate_expenses(
+    expenses: &[Expense],
+    pointer: impl Fn(usize) -> String,
+) -> Result<(), McpError> {
+    let mut total: Cents = 0;
+    for (index, expense) in expenses.iter().enumerate() {
+        if expense.amount_cents <= 0 {
+            return Err(McpError::validation(
+                format!("{}/amount_cents", pointer(index)),
+                format!(
+                    "expense {}: amount must be positive, got {} cents",
+                    index, expense.amount_cents
+                ),
+            ));
+        }
+        if expense.participants.is_empty() {
+            return Err(McpError::validation(
+                format!("{}/participants", pointer(index)),
+                format!("expense {}: no participants", index),
+            ));
+        }
+        total = total.checked_add(expense.amount_cents).ok_or_else(|| {
+            McpError::validation(
+                format!("{}/amount_cents", pointer(index)),
+                format!("expense {}: total amount overflows i64", index),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Run `tool` on the validated `expenses` argument, reporting validation
+/// failures as a tool error.
+fn with_valid_expenses<T: Serialize>(
+    expenses: &[Expense],
+    tool: impl FnOnce() -> T,
+) -> Result<CallToolResult, JsonRpcError> {
+    match validate_expenses(expenses, |index| format!("/expenses/{}", index)) {
+        Ok(()) => CallToolResult::json(&tool()),
+        Err(e) => Ok(CallToolResult::validation_error(e)),
+    }
+}
+
+/// Run the split_expense tool.
+fn split_expense(params: SplitExpenseParams) -> Result<CallToolResult, JsonRpcError> {
+    let expenses = std::slice::from_ref(&params.expense);
+    match validate_expenses(expenses, |_| "/expense".to_string()) {
+        Ok(()) => CallToolResult::json(&split_expense_impl(&params.expense)),
+        Err(e) => Ok(CallToolResult::validation_error(e)),
+    }
+}
+
+/// Run the calculate_balances tool.
+fn calculate_balances(params: ExpensesParams) -> Result<CallToolResult, JsonRpcError> {
+    with_valid_expenses(&params.expenses, || BalancesResult {
+        balances: calculate_all_balances_impl(&params.expenses),
+    })
+}
+
+/// Run the explain_balance tool.
+fn explain_balance(params: ExplainBalanceParams) -> Result<CallToolResult, JsonRpcError> {
+    with_valid_expenses(&params.expenses, || {
+        explain_balance_impl(&params.expenses, &params.attendee_id)
+    })
+}
+
+/// Run the category_breakdown tool.
+fn category_breakdown(params: ExpensesParams) -> Result<CallToolResult, JsonRpcError> {
+    with_valid_expenses(&params.expenses, || CategoryBreakdownResult {
+        categories: category_breakdown_impl(&params.expenses),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use finance_core::{BalanceExplanation, ShareResult};
+
+    fn expenses() -> Value {
+        json!([
+            {
+                "id": "e1", "payer_id": "A", "amount_cents": 10000,
+                "participants": ["A", "B", "C"], "category": "food", "description": "Dinner"
+            },
+            {
+                "id": "e2", "payer_id": "B", "amount_cents": 3000,
+                "participants": ["A", "B"], "category": "transport", "description": "Taxi"
+            }
+        ])
+    }
+
+    fn call(method: &str, params: Value) -> McpResponse {
+        handle(McpRequest {
+            method: method.to_string(),
+            params,
+        })
+    }
+
+    #[test]
+    fn test_split_expense_distributes_remainder() {
+        let response = call("split_expense", json!({"expense": expenses()[0]}));
+        let result: ShareResult = serde_json::from_str(&response.into_result().unwrap()).unwrap();
+        assert_eq!(result.per_person_cents, 3333);
+        assert_eq!(result.remainder_cents, 1);
+        let total: i64 = result.shares.iter().map(|s| s.share_cents).sum();
+        assert_eq!(total, 10000);
+    }
+
+    #[test]
+    fn test_calculate_balances_sum_to_zero() {
+        let response = call("calculate_balances", json!({"expenses": expenses()}));
+        let BalancesResult { balances } =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
+        assert_eq!(balances.len(), 3);
+        assert_eq!(balances.iter().map(|b| b.net_balance_cents).sum::<i64>(), 0);
+        // A paid 10000 and owes 3334 + 1500
+        assert_eq!(balances[0].net_balance_cents, 10000 - 3334 - 1500);
+    }
+
+    #[test]
+    fn test_explain_balance_lists_contributions() {
+        let response = call(
+            "explain_balance",
+            json!({"expenses": expenses(), "attendee_id": "C"}),
+        );
+        let explanation: BalanceExplanation =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
+        assert_eq!(explanation.contributions.len(), 1);
+        assert_eq!(explanation.contributions[0].expense_id, "e1");
+        assert_eq!(explanation.net_balance_cents, -3333);
+    }
+
+    #[test]
+    fn test_category_breakdown_largest_first() {
+        let response = call("category_breakdown", json!({"expenses": expenses()}));
+        let CategoryBreakdownResult { categories } =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
+        assert_eq!(categories[0].category, "food");
+        assert_eq!(categories[1].category, "transport");
+        assert_eq!(categories[1].total_cents, 3000);
+    }
+
+    #[test]
+    fn test_invalid_expense_returns_validation_error() {
+        let mut expenses = expenses();
+        expenses[1]["participants"] = json!([]);
+        let response = call("calculate_balances", json!({"expenses": expenses}));
+        assert_eq!(
+            response.into_result().unwrap_err(),
+            McpError::validation("/expenses/1/participants", "expense 1: no participants")
+        );
+
+        let mut expense = expenses[0].clone();
+        expense["amount_cents"] = json!(0);
+        let response = call("split_expense", json!({"expense": expense}));
+        assert_eq!(
+            response.into_result().unwrap_err(),
+            McpError::validation(
+                "/expense/amount_cents",
+                "expense 0: amount must be positive, got 0 cents"
+            )
+        );
+
+        let mut expenses = self::expenses();
+        expenses[0]["amount_cents"] = json!(i64::MAX);
+        let response = call("category_breakdown", json!({"expenses": expenses}));
+        assert_eq!(
+            response.into_result().unwrap_err(),
+            McpError::validation(
+                "/expenses/1/amount_cents",
+                "expense 1: total amount overflows i64"
+            )
+        );
+    }
+
+    #[test]
+    fn test_malformed_params_return_coded_errors() {
+        let response = call("explain_balance", json!({"expenses": expenses()}));
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(error.message, "missing field `attendee_id`");
+        assert_eq!(error.pointer.as_deref(), Some(""));
+
+        let mut expenses = expenses();
+        expenses[1]["amount_cents"] = json!("30.00");
+        let response = call("calculate_balances", json!({"expenses": expenses}));
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.pointer.as_deref(), Some("/expenses/1/amount_cents"));
+
+        let response = call("settle", json!({}));
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::UnknownMethod);
+        assert_eq!(error.message, "Unknown method: settle");
+    }
+
+    fn jsonrpc(message: Value) -> Value {
+        serde_json::from_str(&handle_jsonrpc(&message.to_string()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_jsonrpc_tools_list_has_schemas() {
+        let response = jsonrpc(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}));
+        let tools = response["result"]["tools"].as_array().unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t["name"].as_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "split_expense",
+                "calculate_balances",
+                "explain_balance",
+                "category_breakdown"
+            ]
+        );
+        for tool in tools {
+            assert_eq!(tool["inputSchema"]["type"], "object");
+        }
+    }
+
+    #[test]
+    fn test_jsonrpc_tools_call_returns_structured_content() {
+        let response = jsonrpc(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "explain_balance",
+                "arguments": {"expenses": expenses(), "attendee_id": "B"}
+            }
+        }));
+        let result = &response["result"];
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["structuredContent"]["attendee_id"], "B");
+        assert_eq!(
+            result["structuredContent"]["net_balance_cents"],
+            3000 - 3333 - 1500
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Finance Core MCP server.
+//!
+//! By default reads newline-delimited JSON-RPC messages from stdin and writes
+//! responses to stdout, logging to stderr. An MCP client can launch it with a
+//! config entry such as:
+//!
+//! ```json
+//! { "mcpServers": { "finance-core": { "command": "finance-core-mcp" } } }
+//! ```
+//!
+//! Built with the `http` feature, `finance-core-mcp --http 127.0.0.1:8080`
+//! serves the streamable HTTP transport at `/mcp` instead.
+
+use std::process::ExitCode;
+
+use finance_core_mcp::FinanceCoreServer;
+
+#[cfg(feature = "http")]
+const USAGE: &str = "usage: finance-core-mcp [--http <addr>]";
+#[cfg(not(feature = "http"))]
+const USAGE: &str = "usage: finance-core-mcp";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let served = match args.as_slice() {
+        [] => mcp_protocol::serve_stdio(&FinanceCoreServer),
+        #[cfg(feature = "http")]
+        [flag, addr] if flag == "--http" => mcp_protocol::serve_http(FinanceCoreServer, addr),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match served {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("finance-core-mcp: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
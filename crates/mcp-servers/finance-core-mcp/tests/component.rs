@@ -0,0 +1,115 @@
+//! Runs the crate as a WebAssembly component in a local wasmtime host.
+//!
+//! The crate is built for `wasm32-unknown-unknown` once per test run,
+//! encoded as a component, and instantiated against the
+//! `finance-core-mcp` world with no imports. The build needs that target
+//! installed, so these tests are ignored by default; CI runs them with
+//! `cargo test -- --ignored`.
+
+use std::sync::LazyLock;
+
+use mcp_test_support::build_component;
+use serde_json::{Value, json};
+use wasmtime::component::{Component, Linker, bindgen};
+use wasmtime::{Engine, Store};
+
+bindgen!({
+    world: "steamboat:finance-core/finance-core-mcp",
+    path: ["../wit/mcp-server.wit", "../wit/finance-core.wit"],
+});
+
+/// The compiled component, shared by every test.
+static COMPONENT: LazyLock<(Engine, Component)> = LazyLock::new(|| {
+    let bytes = build_component(
+        env!("CARGO_MANIFEST_DIR"),
+        env!("CARGO_TARGET_TMPDIR"),
+        "finance_core_mcp",
+    );
+    let engine = Engine::default();
+    let component = Component::new(&engine, bytes).unwrap();
+    (engine, component)
+});
+
+fn instantiate() -> (Store<()>, FinanceCoreMcp) {
+    let (engine, component) = &*COMPONENT;
+    let mut store = Store::new(engine, ());
+    let instance = FinanceCoreMcp::instantiate(&mut store, component, &Linker::new(engine))
+        .expect("the component should need no imports");
+    (store, instance)
+}
+
+fn expenses() -> Value {
+    json!([
+        {
+            "id": "e1", "payer_id": "A", "amount_cents": 1000,
+            "participants": ["A", "B", "C"], "category": "food", "description": "Brunch"
+        },
+        {
+            "id": "e2", "payer_id": "C", "amount_cents": 600,
+            "participants": ["B", "C"], "category": "transport", "description": "Parking"
+        }
+    ])
+}
+
+#[test]
+#[ignore = "builds the crate for wasm32-unknown-unknown; run with --ignored"]
+fn test_legacy_handle() {
+    let (mut store, instance) = instantiate();
+    let handler = instance.steamboat_mcp_server_handler();
+
+    let request = McpRequest {
+        method: "split_expense".to_string(),
+        params: json!({"expense": expenses()[0]}).to_string(),
+    };
+    let McpResponse::Ok(output) = handler.call_handle(&mut store, &request).unwrap() else {
+        panic!("split_expense should succeed");
+    };
+    let result: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(result["per_person_cents"], 333);
+    assert_eq!(result["shares"][0]["extra_cent"], true);
+
+    let request = McpRequest {
+        method: "calculate_balances".to_string(),
+        params: json!({"expenses": [{
+            "id": "e3", "payer_id": "A", "amount_cents": 100,
+            "participants": [], "category": "food", "description": "Gum"
+        }]})
+        .to_string(),
+    };
+    let McpResponse::Err(error) = handler.call_handle(&mut store, &request).unwrap() else {
+        panic!("an expense without participants should be rejected");
+    };
+    assert!(matches!(error.code, ErrorCode::Validation));
+    assert_eq!(error.message, "expense 0: no participants");
+    assert_eq!(error.pointer.as_deref(), Some("/expenses/0/participants"));
+}
+
+#[test]
+#[ignore = "builds the crate for wasm32-unknown-unknown; run with --ignored"]
+fn test_jsonrpc_messages() {
+    let (mut store, instance) = instantiate();
+    let handler = instance.steamboat_mcp_server_handler();
+    let mut call = |message: Value| {
+        handler
+            .call_handle_jsonrpc(&mut store, &message.to_string())
+            .unwrap()
+            .map(|response| serde_json::from_str::<Value>(&response).unwrap())
+    };
+
+    let response = call(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"})).unwrap();
+    assert_eq!(response["result"]["tools"].as_array().unwrap().len(), 4);
+
+    let response = call(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "explain_balance",
+            "arguments": {"expenses": expenses(), "attendee_id": "B"}
+        }
+    }))
+    .unwrap();
+    let explanation = &response["result"]["structuredContent"];
+    assert_eq!(explanation["contributions"].as_array().unwrap().len(), 2);
+    assert_eq!(explanation["net_balance_cents"], -333 - 300);
+}
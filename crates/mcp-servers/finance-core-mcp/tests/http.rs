@@ -0,0 +1,39 @@
+//! Exercises the streamable HTTP transport over a loopback listener.
+
+use finance_core_mcp::FinanceCoreServer;
+use mcp_protocol::HttpTransport;
+use mcp_test_support::{HttpSession, start_http};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_category_breakdown_over_http() {
+    let url = start_http(HttpTransport::new(FinanceCoreServer)).await;
+    let (session, _) = HttpSession::initialize(&url, "2025-06-18").await;
+
+    let body = session
+        .request(&json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "category_breakdown",
+                "arguments": {"expenses": [
+                    {
+                        "id": "e1", "payer_id": "A", "amount_cents": 2000,
+                        "participants": ["A", "B"], "category": "drinks",
+                        "description": "Bar tab"
+                    },
+                    {
+                        "id": "e2", "payer_id": "B", "amount_cents": 5000,
+                        "participants": ["A", "B"], "category": "lodging",
+                        "description": "Cabin"
+                    }
+                ]}
+            }
+        }))
+        .await;
+    let breakdown = &body["result"]["structuredContent"]["categories"];
+    assert_eq!(breakdown[0]["category"], "lodging");
+    assert_eq!(breakdown[0]["shares"][1]["share_cents"], 2500);
+    assert_eq!(breakdown[1]["category"], "drinks");
+}
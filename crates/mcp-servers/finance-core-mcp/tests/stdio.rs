@@ -0,0 +1,46 @@
+//! Runs the stdio binary the way an MCP client would.
+
+use mcp_test_support::run_stdio;
+use serde_json::json;
+
+#[test]
+fn test_stdio_session() {
+    let run = run_stdio(
+        env!("CARGO_BIN_EXE_finance-core-mcp"),
+        &[],
+        &[
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {"protocolVersion": "2025-06-18", "capabilities": {}}
+            }),
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {
+                    "name": "calculate_balances",
+                    "arguments": {"expenses": [{
+                        "id": "e1", "payer_id": "A", "amount_cents": 900,
+                        "participants": ["A", "B", "C"], "category": "food",
+                        "description": "Lunch"
+                    }]}
+                }
+            }),
+        ],
+    );
+
+    assert_eq!(run.responses.len(), 2);
+    assert_eq!(
+        run.responses[0]["result"]["serverInfo"]["name"],
+        "finance-core-mcp"
+    );
+    assert_eq!(run.responses[1]["id"], 2);
+    assert_eq!(
+        run.responses[1]["result"]["structuredContent"]["balances"][0]["net_balance_cents"],
+        600
+    );
+    assert!(run.log.contains("serving MCP over stdio"));
+}
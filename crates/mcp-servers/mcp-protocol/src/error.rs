@@ -0,0 +1,154 @@
+//! Machine-readable request errors.
+//!
+//! JSON-RPC error codes say which step of handling a message failed, and a
+//! failed tool call only carries text for the model. [`McpError`] gives
+//! callers that branch on failures a code for what went wrong and, where
+//! there is one, a JSON pointer to the offending parameter.
+
+use serde::{Deserialize, Serialize};
+
+use crate::jsonrpc::{
+    INVALID_PARAMS, INVALID_REQUEST, JsonRpcError, METHOD_NOT_FOUND, PARSE_ERROR,
+};
+
+/// What kind of failure an [`McpError`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// No tool has the requested name.
+    UnknownMethod,
+    /// The parameters are not JSON or do not match the tool's schema.
+    InvalidParams,
+    /// The parameters are well-formed but the tool rejected their values,
+    /// such as a negative amount.
+    Validation,
+    /// The tool ran but could not finish, such as on an undecodable image.
+    ToolFailed,
+    /// The server failed while handling a valid request.
+    Internal,
+}
+
+/// A failed request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct McpError {
+    /// What kind of failure this is.
+    pub code: ErrorCode,
+    /// Human-readable description.
+    pub message: String,
+    /// JSON pointer (RFC 6901) to the offending value in the parameters,
+    /// such as `/debts/1/amount_cents`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pointer: Option<String>,
+}
+
+impl McpError {
+    /// An error with no pointer.
+    pub fn new(code: ErrorCode, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            pointer: None,
+        }
+    }
+
+    /// A validation error for the value at `pointer`.
+    pub fn validation(pointer: impl Into<String>, message: impl ToString) -> Self {
+        Self {
+            pointer: Some(pointer.into()),
+            ..Self::new(ErrorCode::Validation, message)
+        }
+    }
+
+    /// The same error for parameters found at `pointer` in a larger
+    /// document, such as one item of a bulk request.
+    pub fn within(self, pointer: &str) -> Self {
+        Self {
+            pointer: Some(format!("{}{}", pointer, self.pointer.unwrap_or_default())),
+            ..self
+        }
+    }
+}
+
+impl From<&JsonRpcError> for McpError {
+    fn from(error: &JsonRpcError) -> Self {
+        let code = match error.code {
+            METHOD_NOT_FOUND => ErrorCode::UnknownMethod,
+            PARSE_ERROR | INVALID_REQUEST | INVALID_PARAMS => ErrorCode::InvalidParams,
+            _ => ErrorCode::Internal,
+        };
+        Self {
+            code,
+            message: error.detail().to_string(),
+            pointer: error.pointer().map(str::to_string),
+        }
+    }
+}
+
+impl std::fmt::Display for McpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.pointer {
+            Some(pointer) => write!(f, "{} (at {:?})", self.message, pointer),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for McpError {}
+
+/// Build a JSON pointer from path segments, escaping `~` and `/`.
+pub fn json_pointer<I>(segments: I) -> String
+where
+    I: IntoIterator,
+    I::Item: std::fmt::Display,
+{
+    segments
+        .into_iter()
+        .map(|segment| {
+            format!(
+                "/{}",
+                segment.to_string().replace('~', "~0").replace('/', "~1")
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_error_serialization() {
+        let error = McpError::validation("/debts/0/amount_cents", "must be positive");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "validation",
+                "message": "must be positive",
+                "pointer": "/debts/0/amount_cents"
+            })
+        );
+        assert_eq!(
+            error.to_string(),
+            "must be positive (at \"/debts/0/amount_cents\")"
+        );
+
+        let error = McpError::from(&JsonRpcError::method_not_found("nope"));
+        assert_eq!(error, McpError::new(ErrorCode::UnknownMethod, "nope"));
+    }
+
+    #[test]
+    fn test_within_prefixes_the_pointer() {
+        let error = McpError::validation("/amount", "must be positive").within("/items/2");
+        assert_eq!(error.pointer.as_deref(), Some("/items/2/amount"));
+        let error = McpError::new(ErrorCode::ToolFailed, "failed").within("/items/0");
+        assert_eq!(error.pointer.as_deref(), Some("/items/0"));
+    }
+
+    #[test]
+    fn test_json_pointer_escapes_segments() {
+        assert_eq!(json_pointer(["debts", "0"]), "/debts/0");
+        assert_eq!(json_pointer(["a/b", "m~n"]), "/a~1b/m~0n");
+        assert_eq!(json_pointer(Vec::<String>::new()), "");
+    }
+}
@@ -0,0 +1,582 @@
+//! The streamable HTTP transport.
+//!
+//! Everything happens on one endpoint, [`ENDPOINT`]:
+//!
+//! * `POST` carries one JSON-RPC message from the client, or a batch in
+//!   sessions on protocol version 2025-03-26. Requests are answered with an
+//!   `application/json` response, an array of them for a batch;
+//!   notifications and responses are acknowledged with `202 Accepted`.
+//! * `GET` opens a server-sent events stream on which the server can send
+//!   notifications to the client at any time.
+//! * `DELETE` ends the session.
+//!
+//! A successful `initialize` opens a session and returns its ID in the
+//! `Mcp-Session-Id` header, which the client must send with every later
+//! message, along with the protocol version `initialize` negotiated.
+//! Sessions idle for longer than [`SESSION_IDLE_TIMEOUT`] are closed, and at
+//! most [`MAX_SESSIONS`] are open at once. Dispatch goes through the same
+//! [`handle_request`] as the other transports.
+//!
+//! Sessions share one server unless the transport is built with
+//! [`HttpTransport::per_session`], which gives each session a server of its
+//! own so that servers holding state do not leak it between clients.
+//!
+//! Browsers are only let in from loopback origins and those passed to
+//! [`HttpTransport::with_allowed_origins`], so a web page cannot reach a
+//! local server through DNS rebinding. Requests without an `Origin` header
+//! come from other programs and are allowed.
+//!
+//! This module is only built with the `http` feature.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::jsonrpc::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, RequestId};
+use crate::server::{
+    Implementation, McpServer, SUPPORTED_PROTOCOL_VERSIONS, batching_unsupported, handle_request,
+    respond_value, supports_batching,
+};
+
+/// Path of the MCP endpoint.
+pub const ENDPOINT: &str = "/mcp";
+
+/// Header carrying the session ID.
+pub const SESSION_ID_HEADER: &str = "mcp-session-id";
+
+/// Header carrying the negotiated protocol version.
+pub const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// How long a session may go without a message before it is closed.
+///
+/// Sessions with an open event stream are kept however long they are idle.
+pub const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Most sessions open at once; `initialize` is refused beyond this.
+pub const MAX_SESSIONS: usize = 1024;
+
+/// Messages buffered per event stream before a slow client misses some.
+const STREAM_CAPACITY: usize = 64;
+
+/// An HTTP-level refusal with a plain-text explanation.
+type Rejection = (StatusCode, &'static str);
+
+/// Serves an [`McpServer`] over streamable HTTP.
+///
+/// Cloning is cheap: clones share the servers and sessions, so one clone
+/// can serve while another sends notifications.
+pub struct HttpTransport<S> {
+    shared: Arc<Shared<S>>,
+}
+
+/// Makes the server for a new session.
+type ServerFactory<S> = Box<dyn Fn() -> Arc<S> + Send + Sync>;
+
+struct Shared<S> {
+    make_server: ServerFactory<S>,
+    /// Browser origins allowed besides loopback ones.
+    allowed_origins: Vec<String>,
+    /// Open sessions by ID.
+    sessions: Mutex<HashMap<String, Session<S>>>,
+}
+
+/// One open session.
+struct Session<S> {
+    /// Serves the session's requests.
+    server: Arc<S>,
+    /// Feeds the session's event streams.
+    sender: broadcast::Sender<String>,
+    /// The protocol version negotiated by `initialize`.
+    protocol_version: String,
+    /// When the client last sent a message for the session.
+    last_seen: Instant,
+}
+
+impl<S> Clone for Session<S> {
+    fn clone(&self) -> Self {
+        Self {
+            server: Arc::clone(&self.server),
+            sender: self.sender.clone(),
+            protocol_version: self.protocol_version.clone(),
+            last_seen: self.last_seen,
+        }
+    }
+}
+
+impl<S> Session<S> {
+    /// Whether the session has been idle too long, with no stream open.
+    fn expired(&self, now: Instant) -> bool {
+        self.sender.receiver_count() == 0
+            && now.duration_since(self.last_seen) > SESSION_IDLE_TIMEOUT
+    }
+}
+
+impl<S> Clone for HttpTransport<S> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<S> Shared<S> {
+    fn sessions(&self) -> MutexGuard<'_, HashMap<String, Session<S>>> {
+        // The map stays consistent even if a holder panicked
+        self.sessions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<S: McpServer + Send + Sync + 'static> HttpTransport<S> {
+    /// Wrap a server shared by every session, accepting browsers only from
+    /// loopback origins.
+    pub fn new(server: S) -> Self {
+        Self::with_allowed_origins(server, &[])
+    }
+
+    /// Wrap a server shared by every session that also accepts browsers
+    /// from `origins`, such as `https://app.example.com`.
+    pub fn with_allowed_origins(server: S, origins: &[&str]) -> Self {
+        let server = Arc::new(server);
+        Self::with_factory(Box::new(move || Arc::clone(&server)), origins)
+    }
+
+    /// Give each session its own server from `make_server`, accepting
+    /// browsers only from loopback origins.
+    ///
+    /// Use this for servers that keep state between requests, so one client
+    /// cannot see or change another's.
+    pub fn per_session(make_server: impl Fn() -> S + Send + Sync + 'static) -> Self {
+        Self::with_factory(Box::new(move || Arc::new(make_server())), &[])
+    }
+
+    /// Build a transport whose sessions get servers from `make_server`.
+    fn with_factory(make_server: ServerFactory<S>, origins: &[&str]) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                make_server,
+                allowed_origins: origins.iter().map(|origin| origin.to_string()).collect(),
+                sessions: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// An axum router serving [`ENDPOINT`], for embedding in a larger app.
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(
+                ENDPOINT,
+                get(open_stream::<S>)
+                    .post(post_message::<S>)
+                    .delete(close_session::<S>),
+            )
+            .with_state(Arc::clone(&self.shared))
+    }
+
+    /// Number of open sessions.
+    pub fn session_count(&self) -> usize {
+        self.shared.sessions().len()
+    }
+
+    /// Send a notification to every open event stream.
+    ///
+    /// # Returns
+    /// The number of streams that received it
+    pub fn notify(&self, notification: &JsonRpcRequest) -> usize {
+        let Ok(message) = serde_json::to_string(notification) else {
+            return 0;
+        };
+        self.shared
+            .sessions()
+            .values()
+            .map(|session| session.sender.send(message.clone()).unwrap_or(0))
+            .sum()
+    }
+
+    /// Serve connections from `listener` until the process exits.
+    ///
+    /// # Errors
+    /// Returns an error if accepting connections fails
+    pub async fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        axum::serve(listener, self.router()).await
+    }
+}
+
+/// Serve `server` over HTTP at `addr` (e.g. `127.0.0.1:8080`), blocking the
+/// calling thread.
+///
+/// # Errors
+/// Returns an error if the runtime cannot start or `addr` cannot be bound
+pub fn serve_http<S: McpServer + Send + Sync + 'static>(server: S, addr: &str) -> io::Result<()> {
+    let info = server.server_info();
+    serve_transport(HttpTransport::new(server), info, addr)
+}
+
+/// Serve a server per session, made by `make_server`, over HTTP at `addr`,
+/// blocking the calling thread.
+///
+/// # Errors
+/// Returns an error if the runtime cannot start or `addr` cannot be bound
+pub fn serve_http_per_session<S: McpServer + Send + Sync + 'static>(
+    make_server: impl Fn() -> S + Send + Sync + 'static,
+    addr: &str,
+) -> io::Result<()> {
+    let info = make_server().server_info();
+    serve_transport(HttpTransport::per_session(make_server), info, addr)
+}
+
+/// Serve `transport` at `addr`, announcing it on stderr as `info`.
+fn serve_transport<S: McpServer + Send + Sync + 'static>(
+    transport: HttpTransport<S>,
+    info: Implementation,
+    addr: &str,
+) -> io::Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let listener = TcpListener::bind(addr).await?;
+        eprintln!(
+            "{} {}: serving MCP at http://{}{}",
+            info.name,
+            info.version,
+            listener.local_addr()?,
+            ENDPOINT
+        );
+        transport.serve(listener).await
+    })
+}
+
+/// `POST`: handle one message or batch from the client.
+async fn post_message<S: McpServer + Send + Sync + 'static>(
+    State(shared): State<Arc<Shared<S>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(rejection) = check_origin(&shared, &headers) {
+        return rejection.into_response();
+    }
+
+    let value: Value = match serde_json::from_slice(&body) {
+        Ok(value) => value,
+        Err(e) => return rpc_error(None, JsonRpcError::parse_error(e)),
+    };
+    // The server never sends requests, so client responses need no action
+    if is_client_response(&value) {
+        return StatusCode::ACCEPTED.into_response();
+    }
+    if let Value::Array(batch) = value {
+        return post_batch(&shared, &headers, batch).await;
+    }
+    let request = match JsonRpcRequest::from_value(value) {
+        Ok(request) => request,
+        Err((id, error)) => return rpc_error(id, error),
+    };
+
+    // `initialize` gets a server for the session it is about to open
+    let initializing = request.method == "initialize";
+    let server = if initializing {
+        check_protocol_version(&headers, None).map(|()| (shared.make_server)())
+    } else {
+        session(&shared, &headers).map(|session| session.server)
+    };
+    let server = match server {
+        Ok(server) => server,
+        Err(rejection) => return rejection.into_response(),
+    };
+
+    // Tools can be CPU-heavy, so keep them off the async workers
+    let id = request.id.clone();
+    let session_server = Arc::clone(&server);
+    let response = match tokio::task::spawn_blocking(move || {
+        handle_request(&*session_server, request)
+    })
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => Some(JsonRpcResponse::failure(
+            id,
+            JsonRpcError::internal_error(e),
+        )),
+    };
+    let Some(response) = response else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+
+    let negotiated = response
+        .result
+        .as_ref()
+        .and_then(|result| result.get("protocolVersion"))
+        .and_then(Value::as_str)
+        .filter(|_| initializing)
+        .map(str::to_string);
+    let session_id = match negotiated {
+        Some(protocol_version) => match open_session(&shared, server, protocol_version) {
+            Ok(session_id) => Some(session_id),
+            Err(rejection) => return rejection.into_response(),
+        },
+        None => None,
+    };
+
+    let mut http_response = Json(response).into_response();
+    if let Some(value) = session_id.and_then(|id| HeaderValue::from_str(&id).ok()) {
+        http_response.headers_mut().insert(SESSION_ID_HEADER, value);
+    }
+    http_response
+}
+
+/// Open a session served by `server` speaking `protocol_version` and return
+/// its ID.
+///
+/// Expired sessions are closed first; if [`MAX_SESSIONS`] are still open,
+/// the client is told to retry later.
+fn open_session<S>(
+    shared: &Shared<S>,
+    server: Arc<S>,
+    protocol_version: String,
+) -> Result<String, Rejection> {
+    let session_id = new_session_id().map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Cannot create a session ID",
+        )
+    })?;
+    let now = Instant::now();
+    let mut sessions = shared.sessions();
+    sessions.retain(|_, session| !session.expired(now));
+    if sessions.len() >= MAX_SESSIONS {
+        return Err((StatusCode::SERVICE_UNAVAILABLE, "Too many open sessions"));
+    }
+
+    let (sender, _) = broadcast::channel(STREAM_CAPACITY);
+    sessions.insert(
+        session_id.clone(),
+        Session {
+            server,
+            sender,
+            protocol_version,
+            last_seen: now,
+        },
+    );
+    Ok(session_id)
+}
+
+/// Handle a batch, which belongs to an existing session on a protocol
+/// version that allows batches.
+async fn post_batch<S: McpServer + Send + Sync + 'static>(
+    shared: &Shared<S>,
+    headers: &HeaderMap,
+    batch: Vec<Value>,
+) -> Response {
+    let session = match session(shared, headers) {
+        Ok(session) => session,
+        Err(rejection) => return rejection.into_response(),
+    };
+    if !supports_batching(&session.protocol_version) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(batching_unsupported(&session.protocol_version)),
+        )
+            .into_response();
+    }
+    if batch.is_empty() {
+        return rpc_error(None, JsonRpcError::invalid_request("empty batch"));
+    }
+    let batch: Vec<_> = batch
+        .into_iter()
+        .filter(|value| !is_client_response(value))
+        .collect();
+    if batch.is_empty() {
+        return StatusCode::ACCEPTED.into_response();
+    }
+
+    let server = session.server;
+    let reply = tokio::task::spawn_blocking(move || respond_value(&*server, Value::Array(batch)));
+    match reply.await {
+        Ok(Some(reply)) => Json(reply).into_response(),
+        Ok(None) => StatusCode::ACCEPTED.into_response(),
+        Err(e) => Json(JsonRpcResponse::failure(
+            None,
+            JsonRpcError::internal_error(e),
+        ))
+        .into_response(),
+    }
+}
+
+/// Whether a message is a client's response to a server request.
+fn is_client_response(value: &Value) -> bool {
+    value.get("method").is_none() && (value.get("result").is_some() || value.get("error").is_some())
+}
+
+/// `GET`: open an event stream for server-initiated messages.
+async fn open_stream<S: McpServer + Send + Sync + 'static>(
+    State(shared): State<Arc<Shared<S>>>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(rejection) = check_origin(&shared, &headers) {
+        return rejection.into_response();
+    }
+    let accepts_events = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"));
+    if !accepts_events {
+        return (
+            StatusCode::NOT_ACCEPTABLE,
+            "Accept must include text/event-stream",
+        )
+            .into_response();
+    }
+    let sender = match session(&shared, &headers) {
+        Ok(session) => session.sender,
+        Err(rejection) => return rejection.into_response(),
+    };
+
+    // The stream ends when the session is closed and its sender dropped;
+    // messages a lagging client missed are skipped
+    let events = BroadcastStream::new(sender.subscribe()).filter_map(|message| {
+        message
+            .ok()
+            .map(|data| Ok::<_, Infallible>(Event::default().event("message").data(data)))
+    });
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// `DELETE`: end the session.
+async fn close_session<S: McpServer + Send + Sync + 'static>(
+    State(shared): State<Arc<Shared<S>>>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(rejection) = check_origin(&shared, &headers) {
+        return rejection.into_response();
+    }
+    match session(&shared, &headers) {
+        Ok(_) => {
+            if let Some(session_id) = session_id(&headers) {
+                shared.sessions().remove(session_id);
+            }
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(rejection) => rejection.into_response(),
+    }
+}
+
+/// The session ID the client sent, if any.
+fn session_id(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(SESSION_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Look up the client's session, check its protocol version header, and
+/// mark it as seen.
+///
+/// A missing session ID is a bad request; an unknown or expired one gets
+/// `404 Not Found`, which tells the client to start a new session.
+fn session<S>(shared: &Shared<S>, headers: &HeaderMap) -> Result<Session<S>, Rejection> {
+    let Some(session_id) = session_id(headers) else {
+        return Err((StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header"));
+    };
+    let now = Instant::now();
+    let mut sessions = shared.sessions();
+    let session = match sessions.get_mut(session_id) {
+        Some(session) if !session.expired(now) => session,
+        Some(_) => {
+            sessions.remove(session_id);
+            return Err((StatusCode::NOT_FOUND, "Unknown session"));
+        }
+        None => return Err((StatusCode::NOT_FOUND, "Unknown session")),
+    };
+    check_protocol_version(headers, Some(&session.protocol_version))?;
+    session.last_seen = now;
+    Ok(session.clone())
+}
+
+/// Reject a protocol version header other than the session's.
+///
+/// Before a session exists, any version the server can speak is accepted.
+/// A missing header is allowed, since clients on older revisions never send
+/// it.
+fn check_protocol_version(headers: &HeaderMap, negotiated: Option<&str>) -> Result<(), Rejection> {
+    let Some(version) = headers.get(PROTOCOL_VERSION_HEADER) else {
+        return Ok(());
+    };
+    let accepted = version.to_str().is_ok_and(|version| match negotiated {
+        Some(negotiated) => version == negotiated,
+        None => SUPPORTED_PROTOCOL_VERSIONS.contains(&version),
+    });
+    if accepted {
+        Ok(())
+    } else if negotiated.is_some() {
+        Err((
+            StatusCode::BAD_REQUEST,
+            "MCP-Protocol-Version differs from the negotiated version",
+        ))
+    } else {
+        Err((StatusCode::BAD_REQUEST, "Unsupported MCP-Protocol-Version"))
+    }
+}
+
+/// Reject browsers on origins that are neither loopback nor allowed.
+fn check_origin<S>(shared: &Shared<S>, headers: &HeaderMap) -> Result<(), Rejection> {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return Ok(());
+    };
+    let allowed = origin.to_str().is_ok_and(|origin| {
+        is_loopback_origin(origin) || shared.allowed_origins.iter().any(|o| o == origin)
+    });
+    if allowed {
+        Ok(())
+    } else {
+        Err((StatusCode::FORBIDDEN, "Origin not allowed"))
+    }
+}
+
+/// Whether an origin is `http` or `https` on `localhost`, `127.0.0.1`, or
+/// `[::1]`, on any port.
+fn is_loopback_origin(origin: &str) -> bool {
+    let Some(authority) = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+    else {
+        return false;
+    };
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => host,
+        _ => authority,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// A `400 Bad Request` carrying a JSON-RPC error.
+fn rpc_error(id: Option<RequestId>, error: JsonRpcError) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(JsonRpcResponse::failure(id, error)),
+    )
+        .into_response()
+}
+
+/// A new, unguessable session ID: 128 bits from the operating system's
+/// secure random number generator, as 32 hex digits.
+fn new_session_id() -> Result<String, getrandom::Error> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes)?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
@@ -0,0 +1,382 @@
+//! JSON-RPC 2.0 messages.
+//!
+//! MCP frames every message as a JSON-RPC 2.0 request, notification, or
+//! response, or as a batch: an array of them answered by an array of
+//! responses. Requests are parsed by hand rather than derived so that a
+//! missing `id` (a notification) can be told apart from an invalid one.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The only JSON-RPC version MCP uses.
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// Invalid JSON was received.
+pub const PARSE_ERROR: i32 = -32700;
+/// The JSON sent is not a valid request object.
+pub const INVALID_REQUEST: i32 = -32600;
+/// The method does not exist.
+pub const METHOD_NOT_FOUND: i32 = -32601;
+/// Invalid method parameters, including unknown tools.
+pub const INVALID_PARAMS: i32 = -32602;
+/// Internal server error.
+pub const INTERNAL_ERROR: i32 = -32603;
+/// MCP's code for `resources/read` of an unknown URI.
+pub const RESOURCE_NOT_FOUND: i32 = -32002;
+
+/// A request ID. MCP allows strings and integers but not `null`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RequestId {
+    /// Integer ID.
+    Number(i64),
+    /// String ID.
+    String(String),
+}
+
+impl std::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestId::Number(id) => write!(f, "{}", id),
+            RequestId::String(id) => write!(f, "{:?}", id),
+        }
+    }
+}
+
+/// A request or notification sent to the server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    /// Always `"2.0"`.
+    pub jsonrpc: String,
+    /// Request ID, or `None` for a notification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<RequestId>,
+    /// The method to invoke (e.g., "tools/call").
+    pub method: String,
+    /// Method parameters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcRequest {
+    /// Create a request with the given ID.
+    pub fn new(id: RequestId, method: &str, params: Option<Value>) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id),
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    /// Whether this is a notification, which must not be answered.
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+
+    /// Parse a request from a decoded JSON value.
+    ///
+    /// # Arguments
+    /// * `value` - The decoded message
+    ///
+    /// # Returns
+    /// The request, or an Invalid Request error paired with whatever ID
+    /// could be recovered so the error can still be answered
+    pub fn from_value(value: Value) -> Result<Self, (Option<RequestId>, JsonRpcError)> {
+        let Value::Object(mut object) = value else {
+            return Err((None, JsonRpcError::invalid_request("expected an object")));
+        };
+
+        let id = match object.remove("id") {
+            None => None,
+            Some(id) => match serde_json::from_value::<RequestId>(id) {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    return Err((
+                        None,
+                        JsonRpcError::invalid_request("id must be a string or an integer"),
+                    ));
+                }
+            },
+        };
+        let invalid = |detail: &str| Err((id.clone(), JsonRpcError::invalid_request(detail)));
+
+        if object.get("jsonrpc").and_then(Value::as_str) != Some(JSONRPC_VERSION) {
+            return invalid("jsonrpc must be \"2.0\"");
+        }
+        let Some(Value::String(method)) = object.remove("method") else {
+            return invalid("method must be a string");
+        };
+        let params = match object.remove("params") {
+            None => None,
+            Some(params @ (Value::Object(_) | Value::Array(_))) => Some(params),
+            Some(_) => return invalid("params must be an object or an array"),
+        };
+
+        Ok(Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            method,
+            params,
+        })
+    }
+}
+
+/// An error object carried by a failed response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    /// One of the standard error codes.
+    pub code: i32,
+    /// Short description of the error.
+    pub message: String,
+    /// Additional detail, such as the underlying parse error. Invalid
+    /// params errors that know the offending value carry an object with
+    /// `detail` and `pointer` instead of a string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl JsonRpcError {
+    fn with_detail(code: i32, message: &str, detail: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            data: Some(Value::String(detail.to_string())),
+        }
+    }
+
+    /// The message was not valid JSON.
+    pub fn parse_error(detail: impl ToString) -> Self {
+        Self::with_detail(PARSE_ERROR, "Parse error", detail)
+    }
+
+    /// The message was JSON but not a valid request.
+    pub fn invalid_request(detail: impl ToString) -> Self {
+        Self::with_detail(INVALID_REQUEST, "Invalid Request", detail)
+    }
+
+    /// No method with this name exists.
+    pub fn method_not_found(method: &str) -> Self {
+        Self::with_detail(METHOD_NOT_FOUND, "Method not found", method)
+    }
+
+    /// The method's parameters are missing or have the wrong shape.
+    pub fn invalid_params(detail: impl ToString) -> Self {
+        Self::with_detail(INVALID_PARAMS, "Invalid params", detail)
+    }
+
+    /// The parameter value at `pointer` (a JSON pointer into the
+    /// parameters) is missing or has the wrong shape.
+    pub fn invalid_params_at(pointer: &str, detail: impl ToString) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: "Invalid params".to_string(),
+            data: Some(serde_json::json!({"detail": detail.to_string(), "pointer": pointer})),
+        }
+    }
+
+    /// `tools/call` named a tool the server does not have.
+    pub fn unknown_tool(name: &str) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: format!("Unknown tool: {}", name),
+            data: None,
+        }
+    }
+
+    /// `prompts/get` named a prompt the server does not have.
+    pub fn unknown_prompt(name: &str) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: format!("Unknown prompt: {}", name),
+            data: None,
+        }
+    }
+
+    /// `resources/read` named a URI the server does not have.
+    pub fn resource_not_found(uri: &str) -> Self {
+        Self::with_detail(RESOURCE_NOT_FOUND, "Resource not found", uri)
+    }
+
+    /// The server failed while handling a valid request.
+    pub fn internal_error(detail: impl ToString) -> Self {
+        Self::with_detail(INTERNAL_ERROR, "Internal error", detail)
+    }
+
+    /// The detail string if there is one, otherwise the message.
+    pub fn detail(&self) -> &str {
+        match &self.data {
+            Some(Value::String(detail)) => detail,
+            Some(Value::Object(data)) => data
+                .get("detail")
+                .and_then(Value::as_str)
+                .unwrap_or(&self.message),
+            _ => &self.message,
+        }
+    }
+
+    /// JSON pointer to the offending parameter, if the error has one.
+    pub fn pointer(&self) -> Option<&str> {
+        self.data.as_ref()?.get("pointer")?.as_str()
+    }
+}
+
+impl std::fmt::Display for JsonRpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.data, self.pointer()) {
+            (Some(_), Some(pointer)) => {
+                write!(f, "{}: {} (at {:?})", self.message, self.detail(), pointer)
+            }
+            (Some(Value::String(detail)), None) => write!(f, "{}: {}", self.message, detail),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for JsonRpcError {}
+
+/// A response to a request.
+///
+/// Exactly one of `result` and `error` is set. The ID is `None` (serialized
+/// as `null`) only when the request's own ID could not be read.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    /// Always `"2.0"`.
+    pub jsonrpc: String,
+    /// ID of the request being answered.
+    pub id: Option<RequestId>,
+    /// The method's result if it succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// The error if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    /// A successful response.
+    pub fn success(id: Option<RequestId>, result: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    /// A failed response.
+    pub fn failure(id: Option<RequestId>, error: JsonRpcError) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// The answer to one message: a response, or an array of responses to a
+/// batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JsonRpcReply {
+    /// The response to a single request.
+    Single(JsonRpcResponse),
+    /// The responses to a batch's requests, in the batch's order.
+    Batch(Vec<JsonRpcResponse>),
+}
+
+impl JsonRpcReply {
+    /// Every response in the reply.
+    pub fn responses(&self) -> &[JsonRpcResponse] {
+        match self {
+            JsonRpcReply::Single(response) => std::slice::from_ref(response),
+            JsonRpcReply::Batch(responses) => responses,
+        }
+    }
+}
+
+/// An empty result object, as returned by `ping`.
+pub fn empty_result() -> Value {
+    Value::Object(Map::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_and_notification() {
+        let request = JsonRpcRequest::from_value(json!({
+            "jsonrpc": "2.0", "id": 7, "method": "tools/list"
+        }))
+        .unwrap();
+        assert_eq!(request.id, Some(RequestId::Number(7)));
+        assert!(!request.is_notification());
+
+        let notification = JsonRpcRequest::from_value(json!({
+            "jsonrpc": "2.0", "method": "notifications/initialized"
+        }))
+        .unwrap();
+        assert!(notification.is_notification());
+    }
+
+    #[test]
+    fn test_invalid_requests_keep_their_id() {
+        let (id, error) = JsonRpcRequest::from_value(json!({
+            "jsonrpc": "1.0", "id": "abc", "method": "ping"
+        }))
+        .unwrap_err();
+        assert_eq!(id, Some(RequestId::String("abc".to_string())));
+        assert_eq!(error.code, INVALID_REQUEST);
+
+        let (id, error) = JsonRpcRequest::from_value(json!({
+            "jsonrpc": "2.0", "id": 1, "method": "ping", "params": 5
+        }))
+        .unwrap_err();
+        assert_eq!(id, Some(RequestId::Number(1)));
+        assert_eq!(error.code, INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_null_id_is_rejected() {
+        let (id, error) = JsonRpcRequest::from_value(json!({
+            "jsonrpc": "2.0", "id": null, "method": "ping"
+        }))
+        .unwrap_err();
+        assert_eq!(id, None);
+        assert_eq!(error.code, INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_response_serialization() {
+        let success = JsonRpcResponse::success(Some(RequestId::Number(1)), empty_result());
+        assert_eq!(
+            serde_json::to_value(&success).unwrap(),
+            json!({"jsonrpc": "2.0", "id": 1, "result": {}})
+        );
+
+        let failure = JsonRpcResponse::failure(None, JsonRpcError::parse_error("EOF"));
+        assert_eq!(
+            serde_json::to_value(&failure).unwrap(),
+            json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": {"code": -32700, "message": "Parse error", "data": "EOF"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_params_pointer() {
+        let error = JsonRpcError::invalid_params_at("/debts/0", "missing field `debtor`");
+        assert_eq!(error.detail(), "missing field `debtor`");
+        assert_eq!(error.pointer(), Some("/debts/0"));
+        assert_eq!(
+            error.to_string(),
+            "Invalid params: missing field `debtor` (at \"/debts/0\")"
+        );
+        assert_eq!(JsonRpcError::invalid_params("bad").pointer(), None);
+    }
+}
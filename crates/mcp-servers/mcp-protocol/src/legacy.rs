@@ -0,0 +1,218 @@
+//! The pre-JSON-RPC request format.
+//!
+//! Before the servers spoke MCP they took a method name plus parameters and
+//! answered with the tool's output or an error. The WIT `handler` interface
+//! and existing callers still use that shape, so it is kept as a thin layer
+//! over [`McpServer::call_tool`]. Failures are [`McpError`]s, so callers can
+//! tell an unknown method from bad parameters or a rejected value without
+//! parsing messages.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{ErrorCode, McpError};
+use crate::server::McpServer;
+
+/// A legacy request containing a method name and its parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpRequest {
+    /// The tool to invoke (e.g., "optimize_settlements").
+    pub method: String,
+    /// Parameters for the tool.
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl McpRequest {
+    /// A request whose parameters arrive JSON-encoded, as over the WIT
+    /// interface.
+    ///
+    /// # Errors
+    /// Returns an [`ErrorCode::InvalidParams`] error pointing at the whole
+    /// document if `params` is not JSON
+    pub fn decode(method: String, params: &str) -> Result<Self, McpError> {
+        match serde_json::from_str(params) {
+            Ok(params) => Ok(Self { method, params }),
+            Err(e) => Err(McpError {
+                pointer: Some(String::new()),
+                ..McpError::new(ErrorCode::InvalidParams, e)
+            }),
+        }
+    }
+}
+
+/// A legacy response: the tool's text output or why the request failed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpResponse {
+    /// The tool's text output.
+    Ok(String),
+    /// Why the request failed.
+    Err(McpError),
+}
+
+impl McpResponse {
+    /// Whether the request succeeded.
+    pub fn is_ok(&self) -> bool {
+        matches!(self, McpResponse::Ok(_))
+    }
+
+    /// Convert into a [`Result`].
+    pub fn into_result(self) -> Result<String, McpError> {
+        self.into()
+    }
+}
+
+impl From<Result<String, McpError>> for McpResponse {
+    fn from(result: Result<String, McpError>) -> Self {
+        match result {
+            Ok(output) => McpResponse::Ok(output),
+            Err(error) => McpResponse::Err(error),
+        }
+    }
+}
+
+impl From<McpResponse> for Result<String, McpError> {
+    fn from(response: McpResponse) -> Self {
+        match response {
+            McpResponse::Ok(output) => Ok(output),
+            McpResponse::Err(error) => Err(error),
+        }
+    }
+}
+
+/// Run a legacy request as a tool call.
+///
+/// The method names a tool and the parameters are its arguments. `adapt`
+/// may rewrite the parameters first, for tools whose legacy parameters
+/// differ from their input schema.
+///
+/// # Arguments
+/// * `server` - The server whose tools to call
+/// * `request` - The legacy request
+/// * `adapt` - Maps (method, params) to tool arguments
+///
+/// # Returns
+/// The tool's text output, or an error coded as an unknown method, invalid
+/// params, a validation failure, a tool failure, or an internal error
+pub fn handle_legacy<S: McpServer + ?Sized>(
+    server: &S,
+    request: McpRequest,
+    adapt: impl FnOnce(&str, Value) -> Value,
+) -> McpResponse {
+    let method = request.method.as_str();
+    if !server.tools().iter().any(|tool| tool.name == method) {
+        return McpResponse::Err(McpError::new(
+            ErrorCode::UnknownMethod,
+            format!("Unknown method: {}", method),
+        ));
+    }
+
+    match server.call_tool(method, adapt(method, request.params)) {
+        Ok(result) => match result.mcp_error() {
+            Some(error) => McpResponse::Err(error),
+            None => McpResponse::Ok(result.text_content()),
+        },
+        Err(e) => McpResponse::Err(McpError::from(&e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonrpc::JsonRpcError;
+    use crate::server::Implementation;
+    use crate::tools::{CallToolResult, Tool, parse_arguments};
+    use serde_json::json;
+
+    /// Doubles a positive number.
+    struct Double;
+
+    impl McpServer for Double {
+        fn server_info(&self) -> Implementation {
+            Implementation::new("double", "1.0.0")
+        }
+
+        fn tools(&self) -> Vec<Tool> {
+            vec![Tool::new(
+                "double",
+                "Double a number",
+                json!({"type": "object"}),
+            )]
+        }
+
+        fn call_tool(&self, _: &str, arguments: Value) -> Result<CallToolResult, JsonRpcError> {
+            #[derive(Deserialize)]
+            struct Args {
+                n: i64,
+            }
+
+            let args: Args = parse_arguments(arguments)?;
+            if args.n <= 0 {
+                return Ok(CallToolResult::validation_error(McpError::validation(
+                    "/n",
+                    "n must be positive",
+                )));
+            }
+            Ok(CallToolResult::text((args.n * 2).to_string()))
+        }
+    }
+
+    fn call(method: &str, params: Value) -> McpResponse {
+        let request = McpRequest {
+            method: method.to_string(),
+            params,
+        };
+        handle_legacy(&Double, request, |_, params| params)
+    }
+
+    #[test]
+    fn test_results_and_coded_errors() {
+        assert_eq!(
+            call("double", json!({"n": 4})),
+            McpResponse::Ok("8".to_string())
+        );
+
+        let error = call("triple", json!({"n": 4})).into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::UnknownMethod);
+        assert_eq!(error.message, "Unknown method: triple");
+
+        let error = call("double", json!({"n": "four"}))
+            .into_result()
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(error.pointer.as_deref(), Some("/n"));
+
+        let error = call("double", json!({"n": -1})).into_result().unwrap_err();
+        assert_eq!(error, McpError::validation("/n", "n must be positive"));
+    }
+
+    #[test]
+    fn test_response_serialization() {
+        let request: McpRequest = serde_json::from_value(json!({
+            "method": "double",
+            "params": {"n": 2}
+        }))
+        .unwrap();
+        assert_eq!(request.params, json!({"n": 2}));
+
+        assert_eq!(
+            serde_json::to_value(McpResponse::Ok("4".to_string())).unwrap(),
+            json!({"ok": "4"})
+        );
+        assert_eq!(
+            serde_json::to_value(call("nope", Value::Null)).unwrap(),
+            json!({"err": {"code": "unknown_method", "message": "Unknown method: nope"}})
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_json() {
+        let request = McpRequest::decode("double".to_string(), r#"{"n": 3}"#).unwrap();
+        assert_eq!(request.params, json!({"n": 3}));
+
+        let error = McpRequest::decode("double".to_string(), "{n: 3}").unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(error.pointer.as_deref(), Some(""));
+    }
+}
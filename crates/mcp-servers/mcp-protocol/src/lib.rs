@@ -0,0 +1,37 @@
+//! Model Context Protocol support shared by the MCP servers.
+//!
+//! This crate implements the JSON-RPC 2.0 side of MCP: message framing,
+//! standard error codes, the `initialize` handshake, tool listing and
+//! calling, and resources and prompts, plus the stdio transport and, with the
+//! `http` feature, the streamable HTTP transport. Each server only describes
+//! and runs its tools, resources, and prompts through the [`McpServer`]
+//! trait.
+
+pub mod error;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod jsonrpc;
+pub mod legacy;
+pub mod prompts;
+pub mod resources;
+pub mod server;
+pub mod stdio;
+pub mod tools;
+
+// Re-export main types for convenience
+pub use error::{ErrorCode, McpError, json_pointer};
+pub use jsonrpc::{JsonRpcError, JsonRpcReply, JsonRpcRequest, JsonRpcResponse, RequestId};
+pub use legacy::{McpRequest, McpResponse, handle_legacy};
+pub use prompts::{
+    GetPromptResult, Prompt, PromptArgument, PromptMessage, Role, required_argument,
+};
+pub use resources::{ReadResourceResult, Resource, ResourceContents};
+pub use server::{
+    Implementation, LATEST_PROTOCOL_VERSION, McpServer, SUPPORTED_PROTOCOL_VERSIONS,
+    handle_message, handle_request, respond, respond_value, supports_batching,
+};
+pub use stdio::{serve, serve_stdio};
+pub use tools::{CallToolResult, Content, ItemResult, Tool, parse_arguments, parse_item};
+
+#[cfg(feature = "http")]
+pub use http::{HttpTransport, serve_http, serve_http_per_session};
@@ -0,0 +1,170 @@
+//! MCP prompts: message templates a server offers to clients.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::jsonrpc::JsonRpcError;
+use crate::tools::Content;
+
+/// A prompt as advertised by `prompts/list`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Prompt {
+    /// Name used in `prompts/get`.
+    pub name: String,
+    /// What the prompt is for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Arguments the prompt accepts.
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+}
+
+impl Prompt {
+    /// Describe a prompt.
+    pub fn new(name: &str, description: &str, arguments: Vec<PromptArgument>) -> Self {
+        Self {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            arguments,
+        }
+    }
+}
+
+/// An argument of a prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptArgument {
+    /// The argument's name.
+    pub name: String,
+    /// What the argument means.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the argument must be given.
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl PromptArgument {
+    /// Describe an argument that must be given.
+    pub fn required(name: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            required: true,
+        }
+    }
+
+    /// Describe an argument that may be left out.
+    pub fn optional(name: &str, description: &str) -> Self {
+        Self {
+            required: false,
+            ..Self::required(name, description)
+        }
+    }
+}
+
+/// Parameters of `prompts/get`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetPromptParams {
+    /// The prompt to fill in.
+    pub name: String,
+    /// Values for the prompt's arguments.
+    #[serde(default)]
+    pub arguments: BTreeMap<String, String>,
+}
+
+/// Who a prompt message is from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// The user.
+    User,
+    /// The model.
+    Assistant,
+}
+
+/// One message of a filled-in prompt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptMessage {
+    /// Who the message is from.
+    pub role: Role,
+    /// The message's content.
+    pub content: Content,
+}
+
+impl PromptMessage {
+    /// A plain-text message from the user.
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: Content::Text { text: text.into() },
+        }
+    }
+}
+
+/// The result of `prompts/get`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetPromptResult {
+    /// What the filled-in prompt is for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The messages to send to the model.
+    pub messages: Vec<PromptMessage>,
+}
+
+/// Look up a prompt argument that must be given.
+///
+/// # Errors
+/// Returns an Invalid params error if the argument is missing
+pub fn required_argument<'a>(
+    arguments: &'a BTreeMap<String, String>,
+    name: &str,
+) -> Result<&'a str, JsonRpcError> {
+    arguments
+        .get(name)
+        .map(String::as_str)
+        .ok_or_else(|| JsonRpcError::invalid_params(format!("missing argument `{}`", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_prompt_serialization() {
+        let prompt = Prompt::new(
+            "greet",
+            "Greet someone",
+            vec![
+                PromptArgument::required("name", "Who to greet"),
+                PromptArgument::optional("tone", "How to sound"),
+            ],
+        );
+        assert_eq!(
+            serde_json::to_value(&prompt).unwrap(),
+            json!({
+                "name": "greet",
+                "description": "Greet someone",
+                "arguments": [
+                    {"name": "name", "description": "Who to greet", "required": true},
+                    {"name": "tone", "description": "How to sound", "required": false}
+                ]
+            })
+        );
+
+        let message = PromptMessage::user("Hello");
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            json!({"role": "user", "content": {"type": "text", "text": "Hello"}})
+        );
+    }
+
+    #[test]
+    fn test_required_argument() {
+        let arguments = BTreeMap::from([("name".to_string(), "Ana".to_string())]);
+        assert_eq!(required_argument(&arguments, "name").unwrap(), "Ana");
+        let error = required_argument(&arguments, "tone").unwrap_err();
+        assert_eq!(error.detail(), "missing argument `tone`");
+    }
+}
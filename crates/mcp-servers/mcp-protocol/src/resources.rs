@@ -0,0 +1,137 @@
+//! MCP resources: read-only data a server exposes by URI.
+
+use serde::{Deserialize, Serialize};
+
+use crate::jsonrpc::JsonRpcError;
+
+/// A resource as advertised by `resources/list`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    /// URI used in `resources/read`.
+    pub uri: String,
+    /// Human-readable name.
+    pub name: String,
+    /// What the resource holds, for the model choosing what to read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME type of the resource's contents.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+impl Resource {
+    /// Describe a JSON resource.
+    pub fn json(uri: &str, name: &str, description: &str) -> Self {
+        Self {
+            uri: uri.to_string(),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            mime_type: Some("application/json".to_string()),
+        }
+    }
+}
+
+/// Parameters of `resources/read`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadResourceParams {
+    /// The resource to read.
+    pub uri: String,
+}
+
+/// The contents of a resource, as text or as base64-encoded binary data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceContents {
+    /// The resource's URI.
+    pub uri: String,
+    /// MIME type of the contents.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// The contents, if they are text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// The contents base64-encoded, if they are binary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+}
+
+/// The result of `resources/read`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadResourceResult {
+    /// The resource's contents.
+    pub contents: Vec<ResourceContents>,
+}
+
+impl ReadResourceResult {
+    /// A resource whose contents are `value` as JSON.
+    ///
+    /// # Errors
+    /// Returns an internal error if `value` cannot be serialized
+    pub fn json<T: Serialize>(uri: &str, value: &T) -> Result<Self, JsonRpcError> {
+        let text = serde_json::to_string(value).map_err(JsonRpcError::internal_error)?;
+        Ok(Self {
+            contents: vec![ResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some("application/json".to_string()),
+                text: Some(text),
+                blob: None,
+            }],
+        })
+    }
+
+    /// A binary resource whose contents are `blob`, already base64-encoded.
+    pub fn blob(uri: &str, mime_type: &str, blob: String) -> Self {
+        Self {
+            contents: vec![ResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(mime_type.to_string()),
+                text: None,
+                blob: Some(blob),
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_resource_serializes_camel_case() {
+        let resource = Resource::json("trip://plan", "Plan", "The current plan");
+        assert_eq!(
+            serde_json::to_value(&resource).unwrap(),
+            json!({
+                "uri": "trip://plan",
+                "name": "Plan",
+                "description": "The current plan",
+                "mimeType": "application/json"
+            })
+        );
+
+        let result = ReadResourceResult::json("trip://plan", &json!({"payments": []})).unwrap();
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({"contents": [{
+                "uri": "trip://plan",
+                "mimeType": "application/json",
+                "text": "{\"payments\":[]}"
+            }]})
+        );
+    }
+
+    #[test]
+    fn test_blob_resource_omits_text() {
+        let result = ReadResourceResult::blob("file:///a.png", "image/png", "iVBORw==".to_string());
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({"contents": [{
+                "uri": "file:///a.png",
+                "mimeType": "image/png",
+                "blob": "iVBORw=="
+            }]})
+        );
+    }
+}
@@ -0,0 +1,684 @@
+//! MCP server dispatch.
+//!
+//! A server implements [`McpServer`] by describing its tools and running
+//! them, and optionally by offering resources and prompts.
+//! [`handle_message`] does everything else: JSON-RPC framing, `initialize`
+//! with protocol version negotiation, `ping`, and routing the `tools/*`,
+//! `resources/*`, and `prompts/*` methods to the server.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::jsonrpc::{JsonRpcError, JsonRpcReply, JsonRpcRequest, JsonRpcResponse, empty_result};
+use crate::prompts::{GetPromptParams, GetPromptResult, Prompt};
+use crate::resources::{ReadResourceParams, ReadResourceResult, Resource};
+use crate::tools::{CallToolResult, Tool, parse_arguments};
+
+/// The newest protocol version this crate implements.
+pub const LATEST_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Every protocol version this crate can speak, newest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Name and version of an MCP client or server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Implementation {
+    /// Program name.
+    pub name: String,
+    /// Program version.
+    pub version: String,
+}
+
+impl Implementation {
+    /// Describe an implementation.
+    pub fn new(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+        }
+    }
+}
+
+/// Parameters of `initialize`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeParams {
+    /// The protocol version the client wants to use.
+    pub protocol_version: String,
+    /// Capabilities the client supports.
+    #[serde(default)]
+    pub capabilities: Value,
+    /// The client's name and version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_info: Option<Implementation>,
+}
+
+/// The server's support for tools.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolsCapability {
+    /// Whether the server notifies clients when its tool list changes.
+    pub list_changed: bool,
+}
+
+/// The server's support for resources.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesCapability {
+    /// Whether clients can subscribe to resource updates.
+    pub subscribe: bool,
+    /// Whether the server notifies clients when its resource list changes.
+    pub list_changed: bool,
+}
+
+/// The server's support for prompts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptsCapability {
+    /// Whether the server notifies clients when its prompt list changes.
+    pub list_changed: bool,
+}
+
+/// Features the server offers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerCapabilities {
+    /// Present if the server offers tools.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<ToolsCapability>,
+    /// Present if the server offers resources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourcesCapability>,
+    /// Present if the server offers prompts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<PromptsCapability>,
+}
+
+/// Result of `initialize`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeResult {
+    /// The protocol version the session will use.
+    pub protocol_version: String,
+    /// Features the server offers.
+    pub capabilities: ServerCapabilities,
+    /// The server's name and version.
+    pub server_info: Implementation,
+    /// Hints for the model on how to use the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+}
+
+/// Parameters of `tools/call`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallToolParams {
+    /// The tool to run.
+    pub name: String,
+    /// The tool's arguments, matching its input schema.
+    #[serde(default)]
+    pub arguments: Option<Value>,
+}
+
+/// An MCP server that offers tools, and optionally resources and prompts.
+///
+/// There is no session to initialize: requests are answered whether or not
+/// `initialize` was sent. Resources and prompts are advertised by
+/// `initialize` only if the server lists any.
+pub trait McpServer {
+    /// The server's name and version, reported by `initialize`.
+    fn server_info(&self) -> Implementation;
+
+    /// Hints for the model on how to use the server.
+    fn instructions(&self) -> Option<String> {
+        None
+    }
+
+    /// Every tool the server offers.
+    fn tools(&self) -> Vec<Tool>;
+
+    /// Run a tool.
+    ///
+    /// `arguments` is the `arguments` object of the call, or an empty object
+    /// if the client sent none.
+    ///
+    /// # Errors
+    /// Returns [`JsonRpcError::unknown_tool`] for an unknown name and an
+    /// Invalid params error for arguments that do not match the schema.
+    /// Failures of the tool itself belong in [`CallToolResult::error`].
+    fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, JsonRpcError>;
+
+    /// Every resource the server currently offers.
+    fn resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    /// Read a resource.
+    ///
+    /// # Errors
+    /// Returns [`JsonRpcError::resource_not_found`] for an unknown URI
+    fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, JsonRpcError> {
+        Err(JsonRpcError::resource_not_found(uri))
+    }
+
+    /// Every prompt the server offers.
+    fn prompts(&self) -> Vec<Prompt> {
+        Vec::new()
+    }
+
+    /// Fill in a prompt.
+    ///
+    /// # Errors
+    /// Returns [`JsonRpcError::unknown_prompt`] for an unknown name and an
+    /// Invalid params error for missing or invalid arguments
+    fn get_prompt(
+        &self,
+        name: &str,
+        _arguments: &BTreeMap<String, String>,
+    ) -> Result<GetPromptResult, JsonRpcError> {
+        Err(JsonRpcError::unknown_prompt(name))
+    }
+}
+
+/// Choose the protocol version for a session.
+///
+/// Per the MCP lifecycle, the client's version is accepted if supported;
+/// otherwise the server answers with its latest version and the client
+/// decides whether to continue.
+pub fn negotiate_protocol_version(requested: &str) -> &'static str {
+    SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .find(|&&version| version == requested)
+        .copied()
+        .unwrap_or(LATEST_PROTOCOL_VERSION)
+}
+
+/// Whether sessions on `protocol_version` may send JSON-RPC batches.
+///
+/// Batching arrived in 2025-03-26 and was dropped again in 2025-06-18.
+pub fn supports_batching(protocol_version: &str) -> bool {
+    protocol_version == "2025-03-26"
+}
+
+/// Reject a batch sent in a session on `protocol_version`, which has none.
+pub(crate) fn batching_unsupported(protocol_version: &str) -> JsonRpcResponse {
+    JsonRpcResponse::failure(
+        None,
+        JsonRpcError::invalid_request(format!(
+            "protocol version {} does not support batches",
+            protocol_version
+        )),
+    )
+}
+
+/// Handle one JSON-RPC message.
+///
+/// # Arguments
+/// * `server` - The server to dispatch to
+/// * `message` - The raw JSON-RPC message or batch
+///
+/// # Returns
+/// The JSON-encoded response, or `None` if the message was a notification
+/// or a batch of only notifications
+pub fn handle_message<S: McpServer + ?Sized>(server: &S, message: &str) -> Option<String> {
+    // A response is plain data, so serializing it cannot fail
+    respond(server, message).map(|reply| serde_json::to_string(&reply).unwrap_or_default())
+}
+
+/// Answer one raw JSON-RPC message without serializing the response.
+///
+/// # Arguments
+/// * `server` - The server to dispatch to
+/// * `message` - The raw JSON-RPC message or batch
+///
+/// # Returns
+/// The reply, or `None` if nothing needs answering
+pub fn respond<S: McpServer + ?Sized>(server: &S, message: &str) -> Option<JsonRpcReply> {
+    match serde_json::from_str::<Value>(message) {
+        Err(e) => Some(JsonRpcReply::Single(JsonRpcResponse::failure(
+            None,
+            JsonRpcError::parse_error(e),
+        ))),
+        Ok(value) => respond_value(server, value),
+    }
+}
+
+/// Answer one decoded JSON-RPC message.
+///
+/// An array is a batch. Its requests are answered in order in one array,
+/// leaving out notifications. `initialize` must be sent on its own, since
+/// it starts the session the rest of the batch would belong to.
+///
+/// Without a session this cannot know the negotiated version, so batches
+/// are always accepted here. The stdio and HTTP transports refuse them in
+/// sessions whose version has none; see [`supports_batching`].
+///
+/// # Arguments
+/// * `server` - The server to dispatch to
+/// * `value` - The decoded message or batch
+///
+/// # Returns
+/// The reply, or `None` if nothing needs answering
+pub fn respond_value<S: McpServer + ?Sized>(server: &S, value: Value) -> Option<JsonRpcReply> {
+    let Value::Array(batch) = value else {
+        return respond_one(server, value, false).map(JsonRpcReply::Single);
+    };
+    if batch.is_empty() {
+        return Some(JsonRpcReply::Single(JsonRpcResponse::failure(
+            None,
+            JsonRpcError::invalid_request("empty batch"),
+        )));
+    }
+
+    let responses: Vec<_> = batch
+        .into_iter()
+        .filter_map(|value| respond_one(server, value, true))
+        .collect();
+    (!responses.is_empty()).then_some(JsonRpcReply::Batch(responses))
+}
+
+/// Answer one decoded message that is not a batch.
+fn respond_one<S: McpServer + ?Sized>(
+    server: &S,
+    value: Value,
+    in_batch: bool,
+) -> Option<JsonRpcResponse> {
+    match JsonRpcRequest::from_value(value) {
+        Err((id, error)) => Some(JsonRpcResponse::failure(id, error)),
+        Ok(request) if in_batch && request.method == "initialize" => {
+            Some(JsonRpcResponse::failure(
+                request.id,
+                JsonRpcError::invalid_request("initialize cannot be batched"),
+            ))
+        }
+        Ok(request) => handle_request(server, request),
+    }
+}
+
+/// Handle one parsed request or notification.
+///
+/// # Arguments
+/// * `server` - The server to dispatch to
+/// * `request` - The request to answer
+///
+/// # Returns
+/// The response, or `None` if the request was a notification
+pub fn handle_request<S: McpServer + ?Sized>(
+    server: &S,
+    request: JsonRpcRequest,
+) -> Option<JsonRpcResponse> {
+    // Notifications such as `notifications/initialized` need no action
+    let id = request.id?;
+
+    Some(match dispatch(server, &request.method, request.params) {
+        Ok(result) => JsonRpcResponse::success(Some(id), result),
+        Err(error) => JsonRpcResponse::failure(Some(id), error),
+    })
+}
+
+/// Run a request method and return its result object.
+fn dispatch<S: McpServer + ?Sized>(
+    server: &S,
+    method: &str,
+    params: Option<Value>,
+) -> Result<Value, JsonRpcError> {
+    match method {
+        "initialize" => {
+            let params: InitializeParams = parse_arguments(params.unwrap_or(Value::Null))?;
+            to_value(&InitializeResult {
+                protocol_version: negotiate_protocol_version(&params.protocol_version).to_string(),
+                capabilities: ServerCapabilities {
+                    tools: Some(ToolsCapability::default()),
+                    resources: (!server.resources().is_empty()).then(ResourcesCapability::default),
+                    prompts: (!server.prompts().is_empty()).then(PromptsCapability::default),
+                },
+                server_info: server.server_info(),
+                instructions: server.instructions(),
+            })
+        }
+        "ping" => Ok(empty_result()),
+        "tools/list" => Ok(serde_json::json!({ "tools": server.tools() })),
+        "tools/call" => {
+            let params: CallToolParams = parse_arguments(params.unwrap_or(Value::Null))?;
+            let arguments = params.arguments.unwrap_or_else(empty_result);
+            to_value(&server.call_tool(&params.name, arguments)?)
+        }
+        "resources/list" => Ok(serde_json::json!({ "resources": server.resources() })),
+        "resources/read" => {
+            let params: ReadResourceParams = parse_arguments(params.unwrap_or(Value::Null))?;
+            to_value(&server.read_resource(&params.uri)?)
+        }
+        "prompts/list" => Ok(serde_json::json!({ "prompts": server.prompts() })),
+        "prompts/get" => {
+            let params: GetPromptParams = parse_arguments(params.unwrap_or(Value::Null))?;
+            to_value(&server.get_prompt(&params.name, &params.arguments)?)
+        }
+        _ => Err(JsonRpcError::method_not_found(method)),
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, JsonRpcError> {
+    serde_json::to_value(value).map_err(JsonRpcError::internal_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonrpc::{
+        INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR, RESOURCE_NOT_FOUND,
+    };
+    use crate::prompts::{PromptArgument, PromptMessage, required_argument};
+    use serde_json::json;
+
+    struct Echo;
+
+    impl McpServer for Echo {
+        fn server_info(&self) -> Implementation {
+            Implementation::new("echo", "1.0.0")
+        }
+
+        fn tools(&self) -> Vec<Tool> {
+            vec![Tool::new(
+                "echo",
+                "Echo text",
+                json!({
+                    "type": "object",
+                    "properties": {"text": {"type": "string"}},
+                    "required": ["text"]
+                }),
+            )]
+        }
+
+        fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, JsonRpcError> {
+            #[derive(Deserialize)]
+            struct Args {
+                text: String,
+            }
+
+            match name {
+                "echo" => {
+                    let args: Args = parse_arguments(arguments)?;
+                    if args.text.is_empty() {
+                        return Ok(CallToolResult::error("text is empty"));
+                    }
+                    Ok(CallToolResult::text(args.text))
+                }
+                _ => Err(JsonRpcError::unknown_tool(name)),
+            }
+        }
+    }
+
+    fn call(message: Value) -> Value {
+        let response = handle_message(&Echo, &message.to_string()).unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[test]
+    fn test_initialize_negotiates_version() {
+        let response = call(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": {"name": "test", "version": "0.1"}
+            }
+        }));
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(response["result"]["serverInfo"]["name"], "echo");
+        assert_eq!(
+            response["result"]["capabilities"],
+            json!({"tools": {"listChanged": false}})
+        );
+
+        let response = call(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "initialize",
+            "params": {"protocolVersion": "1999-01-01", "capabilities": {}}
+        }));
+        assert_eq!(
+            response["result"]["protocolVersion"],
+            LATEST_PROTOCOL_VERSION
+        );
+    }
+
+    #[test]
+    fn test_notifications_get_no_response() {
+        let message = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        assert_eq!(handle_message(&Echo, &message.to_string()), None);
+    }
+
+    #[test]
+    fn test_batches_answer_each_request_in_order() {
+        let response = call(json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"},
+            {
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {"name": "echo", "arguments": {"text": ""}}
+            },
+            {"jsonrpc": "2.0", "id": 3, "method": "nope"},
+            {"jsonrpc": "2.0", "id": 4, "method": "initialize", "params": {}},
+            5
+        ]));
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 5);
+        assert_eq!(
+            responses[0],
+            json!({"jsonrpc": "2.0", "id": 1, "result": {}})
+        );
+        // A failing item does not fail the batch
+        assert_eq!(responses[1]["result"]["isError"], true);
+        assert_eq!(responses[2]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[3]["id"], 4);
+        assert_eq!(responses[3]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(responses[4]["id"], Value::Null);
+
+        let notifications = json!([{"jsonrpc": "2.0", "method": "notifications/initialized"}]);
+        assert_eq!(handle_message(&Echo, &notifications.to_string()), None);
+
+        let response = call(json!([]));
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+        assert_eq!(response["error"]["data"], "empty batch");
+    }
+
+    #[test]
+    fn test_ping() {
+        let response = call(json!({"jsonrpc": "2.0", "id": "p", "method": "ping"}));
+        assert_eq!(response, json!({"jsonrpc": "2.0", "id": "p", "result": {}}));
+    }
+
+    #[test]
+    fn test_tools_list() {
+        let response = call(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}));
+        assert_eq!(response["result"]["tools"][0]["name"], "echo");
+        assert_eq!(
+            response["result"]["tools"][0]["inputSchema"]["type"],
+            "object"
+        );
+    }
+
+    #[test]
+    fn test_tools_call() {
+        let response = call(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "echo", "arguments": {"text": "hi"}}
+        }));
+        assert_eq!(
+            response["result"],
+            json!({"content": [{"type": "text", "text": "hi"}], "isError": false})
+        );
+
+        let response = call(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {"name": "echo", "arguments": {"text": ""}}
+        }));
+        assert_eq!(response["result"]["isError"], true);
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let response: Value =
+            serde_json::from_str(&handle_message(&Echo, "{not json").unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+
+        let response = call(json!({"jsonrpc": "2.0", "id": 1}));
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+
+        let response = call(json!({"jsonrpc": "2.0", "id": 1, "method": "nope"}));
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = call(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "missing"}
+        }));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        assert_eq!(response["error"]["message"], "Unknown tool: missing");
+
+        let response = call(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "echo", "arguments": {"text": 5}}
+        }));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    /// A server with one resource and one prompt.
+    struct Notes;
+
+    impl McpServer for Notes {
+        fn server_info(&self) -> Implementation {
+            Implementation::new("notes", "1.0.0")
+        }
+
+        fn tools(&self) -> Vec<Tool> {
+            Vec::new()
+        }
+
+        fn call_tool(&self, name: &str, _: Value) -> Result<CallToolResult, JsonRpcError> {
+            Err(JsonRpcError::unknown_tool(name))
+        }
+
+        fn resources(&self) -> Vec<Resource> {
+            vec![Resource::json("notes://today", "Today", "Today's notes")]
+        }
+
+        fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, JsonRpcError> {
+            match uri {
+                "notes://today" => ReadResourceResult::json(uri, &json!(["buy milk"])),
+                _ => Err(JsonRpcError::resource_not_found(uri)),
+            }
+        }
+
+        fn prompts(&self) -> Vec<Prompt> {
+            vec![Prompt::new(
+                "summarize",
+                "Summarize a topic",
+                vec![PromptArgument::required("topic", "What to summarize")],
+            )]
+        }
+
+        fn get_prompt(
+            &self,
+            name: &str,
+            arguments: &BTreeMap<String, String>,
+        ) -> Result<GetPromptResult, JsonRpcError> {
+            match name {
+                "summarize" => {
+                    let topic = required_argument(arguments, "topic")?;
+                    Ok(GetPromptResult {
+                        description: None,
+                        messages: vec![PromptMessage::user(format!("Summarize {}", topic))],
+                    })
+                }
+                _ => Err(JsonRpcError::unknown_prompt(name)),
+            }
+        }
+    }
+
+    fn call_notes(message: Value) -> Value {
+        serde_json::from_str(&handle_message(&Notes, &message.to_string()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_resources_and_prompts() {
+        let response = call_notes(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {"protocolVersion": LATEST_PROTOCOL_VERSION, "capabilities": {}}
+        }));
+        assert_eq!(
+            response["result"]["capabilities"],
+            json!({
+                "tools": {"listChanged": false},
+                "resources": {"subscribe": false, "listChanged": false},
+                "prompts": {"listChanged": false}
+            })
+        );
+
+        let response = call_notes(json!({"jsonrpc": "2.0", "id": 2, "method": "resources/list"}));
+        assert_eq!(response["result"]["resources"][0]["uri"], "notes://today");
+
+        let response = call_notes(json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "resources/read",
+            "params": {"uri": "notes://today"}
+        }));
+        assert_eq!(response["result"]["contents"][0]["text"], "[\"buy milk\"]");
+
+        let response = call_notes(json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "prompts/get",
+            "params": {"name": "summarize", "arguments": {"topic": "groceries"}}
+        }));
+        assert_eq!(
+            response["result"]["messages"][0]["content"]["text"],
+            "Summarize groceries"
+        );
+
+        let response = call_notes(json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "prompts/get",
+            "params": {"name": "summarize"}
+        }));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_servers_without_resources_or_prompts() {
+        let response = call(json!({"jsonrpc": "2.0", "id": 1, "method": "resources/list"}));
+        assert_eq!(response["result"], json!({"resources": []}));
+
+        let response = call(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "resources/read",
+            "params": {"uri": "notes://today"}
+        }));
+        assert_eq!(response["error"]["code"], RESOURCE_NOT_FOUND);
+
+        let response = call(json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "prompts/get",
+            "params": {"name": "summarize"}
+        }));
+        assert_eq!(response["error"]["message"], "Unknown prompt: summarize");
+    }
+}
@@ -0,0 +1,234 @@
+//! The stdio transport.
+//!
+//! The client launches the server as a subprocess and exchanges
+//! newline-delimited JSON-RPC messages over its stdin and stdout. Standard
+//! output carries nothing but protocol messages, so logging goes to stderr.
+//! The streams carry one session, so batches are refused once `initialize`
+//! negotiates a protocol version without them.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+use crate::jsonrpc::JsonRpcReply;
+use crate::server::{McpServer, batching_unsupported, respond, respond_value, supports_batching};
+
+/// Serve `server` over the process's stdin and stdout until stdin closes.
+///
+/// # Errors
+/// Returns an error if reading stdin or writing stdout fails
+pub fn serve_stdio<S: McpServer + ?Sized>(server: &S) -> io::Result<()> {
+    let info = server.server_info();
+    let mut log = io::stderr().lock();
+    writeln!(
+        log,
+        "{} {}: serving MCP over stdio",
+        info.name, info.version
+    )?;
+
+    serve(server, io::stdin().lock(), io::stdout().lock(), &mut log)?;
+
+    writeln!(log, "{}: stdin closed, shutting down", info.name)
+}
+
+/// Serve `server` over arbitrary streams until `input` is exhausted.
+///
+/// Each non-blank input line is one message or batch. Each reply is written
+/// as one line and flushed immediately, since the client waits for it. Failed
+/// requests are also logged.
+///
+/// # Arguments
+/// * `server` - The server to dispatch to
+/// * `input` - Newline-delimited JSON-RPC messages
+/// * `output` - Where responses are written
+/// * `log` - Where diagnostics are written
+///
+/// # Errors
+/// Returns an error if reading `input` or writing `output` fails
+pub fn serve<S, R, W, L>(server: &S, input: R, mut output: W, mut log: L) -> io::Result<()>
+where
+    S: McpServer + ?Sized,
+    R: BufRead,
+    W: Write,
+    L: Write,
+{
+    let mut session = Session::default();
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let reply = match serde_json::from_str(&line) {
+            Ok(value) => session.respond(server, value),
+            // `respond` answers with the parse error
+            Err(_) => respond(server, &line),
+        };
+        let Some(reply) = reply else {
+            continue;
+        };
+        for response in reply.responses() {
+            if let Some(error) = &response.error {
+                // Logging is best effort; a closed stderr must not stop the server
+                let _ = match &response.id {
+                    Some(id) => writeln!(log, "request {} failed: {}", id, error),
+                    None => writeln!(log, "message rejected: {}", error),
+                };
+            }
+        }
+
+        // serde_json never emits raw newlines, so the reply stays one line
+        let json = serde_json::to_string(&reply).unwrap_or_default();
+        writeln!(output, "{}", json)?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+/// What the transport remembers about the session on its streams.
+#[derive(Default)]
+struct Session {
+    /// The protocol version `initialize` negotiated, once it has.
+    protocol_version: Option<String>,
+}
+
+impl Session {
+    /// Answer one decoded message or batch, refusing batches the negotiated
+    /// version does not allow.
+    fn respond<S: McpServer + ?Sized>(&mut self, server: &S, value: Value) -> Option<JsonRpcReply> {
+        if value.is_array()
+            && let Some(version) = &self.protocol_version
+            && !supports_batching(version)
+        {
+            return Some(JsonRpcReply::Single(batching_unsupported(version)));
+        }
+
+        let initializing = value.get("method").and_then(Value::as_str) == Some("initialize");
+        let reply = respond_value(server, value);
+        if initializing
+            && let Some(JsonRpcReply::Single(response)) = &reply
+            && let Some(version) = response
+                .result
+                .as_ref()
+                .and_then(|result| result.get("protocolVersion"))
+                .and_then(Value::as_str)
+        {
+            self.protocol_version = Some(version.to_string());
+        }
+        reply
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonrpc::JsonRpcError;
+    use crate::server::Implementation;
+    use crate::tools::{CallToolResult, Tool};
+    use serde_json::{Value, json};
+
+    struct NoTools;
+
+    impl McpServer for NoTools {
+        fn server_info(&self) -> Implementation {
+            Implementation::new("none", "0.0.0")
+        }
+
+        fn tools(&self) -> Vec<Tool> {
+            Vec::new()
+        }
+
+        fn call_tool(&self, name: &str, _: Value) -> Result<CallToolResult, JsonRpcError> {
+            Err(JsonRpcError::unknown_tool(name))
+        }
+    }
+
+    fn run(input: &str) -> (String, String) {
+        let mut output = Vec::new();
+        let mut log = Vec::new();
+        serve(&NoTools, input.as_bytes(), &mut output, &mut log).unwrap();
+        (
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(log).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_one_response_line_per_request() {
+        let (output, log) = run(concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#,
+            "\n\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+            "\n",
+        ));
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"jsonrpc":"2.0","id":1,"result":{}}"#,
+                r#"{"jsonrpc":"2.0","id":2,"result":{"tools":[]}}"#,
+            ]
+        );
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn test_errors_are_answered_and_logged() {
+        let (output, log) = run("not json\n{\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"x\"}");
+
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["error"]["code"], -32700);
+        assert_eq!(lines[1]["error"]["code"], -32601);
+        assert_eq!(log.lines().count(), 2);
+        assert!(log.contains("message rejected: Parse error"));
+        assert!(log.contains("request 3 failed: Method not found: x"));
+    }
+
+    #[test]
+    fn test_batch_reply_is_one_line() {
+        let (output, log) = run(concat!(
+            r#"[{"jsonrpc":"2.0","id":1,"method":"ping"},"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"x"}]"#,
+            "\n",
+        ));
+
+        assert_eq!(
+            output,
+            concat!(
+                r#"[{"jsonrpc":"2.0","id":1,"result":{}},"#,
+                r#"{"jsonrpc":"2.0","id":2,"error":"#,
+                r#"{"code":-32601,"message":"Method not found","data":"x"}}]"#,
+                "\n"
+            )
+        );
+        assert!(log.contains("request 2 failed"));
+    }
+
+    #[test]
+    fn test_batches_follow_the_negotiated_version() {
+        for (version, batched) in [("2025-03-26", true), ("2025-06-18", false)] {
+            let initialize = json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "method": "initialize",
+                "params": {"protocolVersion": version, "capabilities": {}}
+            });
+            let batch = json!([{"jsonrpc": "2.0", "id": 1, "method": "ping"}]);
+            let (output, _) = run(&format!("{}\n{}\n", initialize, batch));
+
+            let reply: Value = serde_json::from_str(output.lines().nth(1).unwrap()).unwrap();
+            assert_eq!(reply.is_array(), batched, "{}", version);
+            if !batched {
+                assert_eq!(reply["error"]["code"], -32600);
+            }
+        }
+    }
+}
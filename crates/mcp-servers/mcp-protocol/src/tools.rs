@@ -0,0 +1,325 @@
+//! MCP tool descriptions and results.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_path_to_error::Segment;
+
+use crate::error::{ErrorCode, McpError, json_pointer};
+use crate::jsonrpc::JsonRpcError;
+
+/// A tool as advertised by `tools/list`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tool {
+    /// Name used in `tools/call`.
+    pub name: String,
+    /// What the tool does, for the model choosing between tools.
+    pub description: String,
+    /// JSON Schema of the `arguments` object.
+    pub input_schema: Value,
+}
+
+impl Tool {
+    /// Describe a tool.
+    pub fn new(name: &str, description: &str, input_schema: Value) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            input_schema,
+        }
+    }
+}
+
+/// A block of content returned by a tool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Content {
+    /// Plain text, which may itself be JSON.
+    Text {
+        /// The text.
+        text: String,
+    },
+    /// An image, such as a thumbnail.
+    Image {
+        /// The base64-encoded image bytes.
+        data: String,
+        /// The image's MIME type (e.g., "image/jpeg").
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+}
+
+/// The result of `tools/call`.
+///
+/// Failures of the tool itself, such as rejected input, are reported here
+/// with `is_error` set so the model can see them and correct its call.
+/// Protocol problems, such as an unknown tool, are JSON-RPC errors instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallToolResult {
+    /// Unstructured content blocks.
+    pub content: Vec<Content>,
+    /// The same result as a JSON object, for clients that can use it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+    /// Whether the tool failed.
+    #[serde(default)]
+    pub is_error: bool,
+}
+
+impl CallToolResult {
+    /// A successful plain-text result.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            content: vec![Content::Text { text: text.into() }],
+            structured_content: None,
+            is_error: false,
+        }
+    }
+
+    /// A successful JSON result, returned as both text and structured
+    /// content.
+    ///
+    /// # Errors
+    /// Returns an internal error if `value` cannot be serialized
+    pub fn json<T: Serialize>(value: &T) -> Result<Self, JsonRpcError> {
+        let structured = serde_json::to_value(value).map_err(JsonRpcError::internal_error)?;
+        Ok(Self {
+            content: vec![Content::Text {
+                text: structured.to_string(),
+            }],
+            structured_content: Some(structured),
+            is_error: false,
+        })
+    }
+
+    /// A successful image result described by `metadata`, which is returned
+    /// as both text and structured content alongside the image.
+    ///
+    /// # Arguments
+    /// * `data` - The base64-encoded image bytes
+    /// * `mime_type` - The image's MIME type
+    /// * `metadata` - Facts about the image, such as its dimensions
+    ///
+    /// # Errors
+    /// Returns an internal error if `metadata` cannot be serialized
+    pub fn image<T: Serialize>(
+        data: String,
+        mime_type: &str,
+        metadata: &T,
+    ) -> Result<Self, JsonRpcError> {
+        let mut result = Self::json(metadata)?;
+        result.content.insert(
+            0,
+            Content::Image {
+                data,
+                mime_type: mime_type.to_string(),
+            },
+        );
+        Ok(result)
+    }
+
+    /// A failed result carrying an error message for the model.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            is_error: true,
+            ..Self::text(message)
+        }
+    }
+
+    /// A failed result for arguments that are well-formed but rejected by
+    /// the tool. The text reads "Validation error: ..." and the structured
+    /// content is `error`, so clients can find the offending value.
+    pub fn validation_error(error: McpError) -> Self {
+        let mut result = Self::error(format!("Validation error: {}", error.message));
+        result.structured_content = serde_json::to_value(&error).ok();
+        result
+    }
+
+    /// The error a failed result reports: its structured error if it has
+    /// one, otherwise its text as a [`ErrorCode::ToolFailed`] error.
+    ///
+    /// # Returns
+    /// `None` if the result is not an error
+    pub fn mcp_error(&self) -> Option<McpError> {
+        if !self.is_error {
+            return None;
+        }
+        let structured = self
+            .structured_content
+            .clone()
+            .and_then(|content| serde_json::from_value(content).ok());
+        Some(
+            structured.unwrap_or_else(|| McpError::new(ErrorCode::ToolFailed, self.text_content())),
+        )
+    }
+
+    /// All text content joined by newlines. Images are left out.
+    pub fn text_content(&self) -> String {
+        self.content
+            .iter()
+            .filter_map(|block| match block {
+                Content::Text { text } => Some(text.as_str()),
+                Content::Image { .. } => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The outcome of one item of a bulk tool, which reports each item's
+/// success or failure instead of failing the whole call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemResult<T> {
+    /// The item's result.
+    Ok(T),
+    /// Why the item failed.
+    Err(McpError),
+}
+
+impl<T> From<Result<T, McpError>> for ItemResult<T> {
+    fn from(result: Result<T, McpError>) -> Self {
+        match result {
+            Ok(value) => ItemResult::Ok(value),
+            Err(error) => ItemResult::Err(error),
+        }
+    }
+}
+
+/// Deserialize one item of a bulk tool's arguments into its typed form.
+///
+/// # Arguments
+/// * `pointer` - JSON pointer to the item in the arguments
+/// * `item` - The item
+///
+/// # Errors
+/// Returns an [`ErrorCode::InvalidParams`] error pointing at the value in
+/// the item that did not match
+pub fn parse_item<T: DeserializeOwned>(pointer: &str, item: Value) -> Result<T, McpError> {
+    parse_arguments(item).map_err(|e| McpError::from(&e).within(pointer))
+}
+
+/// Deserialize tool arguments into their typed form.
+///
+/// # Errors
+/// Returns an Invalid params error describing the mismatch, with a JSON
+/// pointer to the value that did not match. A missing field is reported
+/// at the object that lacks it.
+pub fn parse_arguments<T: DeserializeOwned>(arguments: Value) -> Result<T, JsonRpcError> {
+    serde_path_to_error::deserialize(arguments).map_err(|e| {
+        let pointer = json_pointer(e.path().iter().map_while(|segment| match segment {
+            Segment::Seq { index } => Some(index.to_string()),
+            Segment::Map { key } => Some(key.clone()),
+            Segment::Enum { variant } => Some(variant.clone()),
+            Segment::Unknown => None,
+        }));
+        JsonRpcError::invalid_params_at(&pointer, e.into_inner())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tool_serializes_camel_case() {
+        let tool = Tool::new("echo", "Echo text", json!({"type": "object"}));
+        assert_eq!(
+            serde_json::to_value(&tool).unwrap(),
+            json!({"name": "echo", "description": "Echo text", "inputSchema": {"type": "object"}})
+        );
+    }
+
+    #[test]
+    fn test_json_result_has_text_and_structured_content() {
+        let result = CallToolResult::json(&json!({"total": 5})).unwrap();
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({
+                "content": [{"type": "text", "text": "{\"total\":5}"}],
+                "structuredContent": {"total": 5},
+                "isError": false
+            })
+        );
+    }
+
+    #[test]
+    fn test_image_result_puts_image_first() {
+        let result =
+            CallToolResult::image("AAEC".to_string(), "image/png", &json!({"width": 2})).unwrap();
+        assert_eq!(
+            serde_json::to_value(&result).unwrap()["content"],
+            json!([
+                {"type": "image", "data": "AAEC", "mimeType": "image/png"},
+                {"type": "text", "text": "{\"width\":2}"}
+            ])
+        );
+        assert_eq!(result.text_content(), "{\"width\":2}");
+    }
+
+    #[test]
+    fn test_error_result() {
+        let result = CallToolResult::error("bad input");
+        assert!(result.is_error);
+        assert_eq!(result.text_content(), "bad input");
+        assert_eq!(
+            result.mcp_error(),
+            Some(McpError::new(ErrorCode::ToolFailed, "bad input"))
+        );
+        assert_eq!(CallToolResult::text("fine").mcp_error(), None);
+
+        let error = McpError::validation("/amount", "must be positive");
+        let result = CallToolResult::validation_error(error.clone());
+        assert!(result.is_error);
+        assert_eq!(result.text_content(), "Validation error: must be positive");
+        assert_eq!(result.mcp_error(), Some(error));
+    }
+
+    #[test]
+    fn test_parse_arguments_points_at_the_bad_value() {
+        #[derive(Debug, Deserialize)]
+        struct Item {
+            #[allow(dead_code)]
+            amount: i64,
+        }
+        #[derive(Debug, Deserialize)]
+        struct Params {
+            #[allow(dead_code)]
+            items: Vec<Item>,
+        }
+
+        let error = parse_arguments::<Params>(json!({"items": [{"amount": 1}, {"amount": "x"}]}))
+            .unwrap_err();
+        assert_eq!(error.pointer(), Some("/items/1/amount"));
+        assert!(error.detail().starts_with("invalid type: string \"x\""));
+
+        let error = parse_arguments::<Params>(json!({"items": [{}]})).unwrap_err();
+        assert_eq!(error.pointer(), Some("/items/0"));
+        assert_eq!(error.detail(), "missing field `amount`");
+
+        let error = parse_arguments::<Params>(json!([])).unwrap_err();
+        assert_eq!(error.pointer(), Some(""));
+
+        let error = parse_item::<Item>("/items/3", json!({"amount": null})).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(error.pointer.as_deref(), Some("/items/3/amount"));
+    }
+
+    #[test]
+    fn test_item_results_serialize_as_ok_or_err() {
+        let results: Vec<ItemResult<i64>> = vec![
+            Ok(5).into(),
+            Err(McpError::validation("/n", "n must be positive")).into(),
+        ];
+        assert_eq!(
+            serde_json::to_value(&results).unwrap(),
+            json!([
+                {"ok": 5},
+                {"err": {"code": "validation", "message": "n must be positive", "pointer": "/n"}}
+            ])
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! Harness for testing MCP servers over each transport.
+//!
+//! Every server crate runs the same kinds of end-to-end tests: a session
+//! over the streamable HTTP transport, its binary over stdio, and its
+//! WebAssembly component in a local host. This crate holds the plumbing so
+//! their tests only describe tool calls and what they should return. It is
+//! not published and only serves as a dev-dependency.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use reqwest::{Client, Response, StatusCode};
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+
+use mcp_protocol::McpServer;
+use mcp_protocol::http::{ENDPOINT, HttpTransport, PROTOCOL_VERSION_HEADER, SESSION_ID_HEADER};
+
+/// The `Accept` header of a streamable HTTP client.
+pub const ACCEPT: &str = "application/json, text/event-stream";
+
+/// Serve `transport` on an ephemeral loopback port and return its endpoint
+/// URL.
+pub async fn start_http<S: McpServer + Send + Sync + 'static>(
+    transport: HttpTransport<S>,
+) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("a loopback port should be free");
+    let address = listener
+        .local_addr()
+        .expect("the listener should have an address");
+    let url = format!("http://{}{}", address, ENDPOINT);
+    tokio::spawn(async move {
+        transport
+            .serve(listener)
+            .await
+            .expect("the HTTP transport should serve")
+    });
+    url
+}
+
+/// A client session with a server on the streamable HTTP transport.
+pub struct HttpSession {
+    client: Client,
+    url: String,
+    id: String,
+    protocol_version: String,
+}
+
+impl HttpSession {
+    /// Run `initialize` against `url`, asking for `protocol_version`.
+    ///
+    /// # Returns
+    /// The session and the `initialize` response
+    ///
+    /// # Panics
+    /// Panics if `initialize` fails or opens no session
+    pub async fn initialize(url: &str, protocol_version: &str) -> (Self, Value) {
+        let client = Client::new();
+        let response = client
+            .post(url)
+            .header("accept", ACCEPT)
+            .body(
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 0,
+                    "method": "initialize",
+                    "params": {"protocolVersion": protocol_version, "capabilities": {}}
+                })
+                .to_string(),
+            )
+            .send()
+            .await
+            .expect("initialize should reach the server");
+        assert_eq!(response.status(), StatusCode::OK);
+        let id = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .expect("initialize should open a session")
+            .to_str()
+            .expect("the session ID should be visible ASCII")
+            .to_string();
+        let body = json_body(response).await;
+        let session = Self {
+            client,
+            url: url.to_string(),
+            id,
+            protocol_version: body["result"]["protocolVersion"]
+                .as_str()
+                .expect("initialize should negotiate a protocol version")
+                .to_string(),
+        };
+        (session, body)
+    }
+
+    /// The session ID the server assigned.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Post a message in this session, with the negotiated protocol version.
+    pub async fn post(&self, message: &Value) -> Response {
+        self.client
+            .post(&self.url)
+            .header("accept", ACCEPT)
+            .header(SESSION_ID_HEADER, &self.id)
+            .header(PROTOCOL_VERSION_HEADER, &self.protocol_version)
+            .body(message.to_string())
+            .send()
+            .await
+            .expect("the message should reach the server")
+    }
+
+    /// Post a request in this session and return the decoded response.
+    ///
+    /// # Panics
+    /// Panics unless the server answers `200 OK` with JSON
+    pub async fn request(&self, message: &Value) -> Value {
+        let response = self.post(message).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        json_body(response).await
+    }
+}
+
+/// Decode a response body as JSON.
+async fn json_body(response: Response) -> Value {
+    let body = response
+        .text()
+        .await
+        .expect("the response body should be readable");
+    serde_json::from_str(&body).expect("the response body should be JSON")
+}
+
+/// What a server binary wrote while serving stdio.
+pub struct StdioRun {
+    /// Each line of stdout, decoded.
+    pub responses: Vec<Value>,
+    /// Everything written to stderr.
+    pub log: String,
+}
+
+/// Run a server binary over stdio, the way an MCP client would.
+///
+/// Writes each message on its own line, closes stdin, and waits for the
+/// server to exit.
+///
+/// # Arguments
+/// * `binary` - Path of the binary, e.g. `env!("CARGO_BIN_EXE_<name>")`
+/// * `args` - Command-line arguments
+/// * `messages` - The messages to send, in order
+///
+/// # Panics
+/// Panics if the binary cannot run, exits unsuccessfully, or writes
+/// anything to stdout that is not a JSON message
+pub fn run_stdio(binary: &str, args: &[&str], messages: &[Value]) -> StdioRun {
+    let mut child = Command::new(binary)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the server binary should start");
+
+    let mut stdin = child.stdin.take().expect("stdin should be piped");
+    for message in messages {
+        writeln!(stdin, "{}", message).expect("the server should read stdin");
+    }
+    drop(stdin);
+
+    let output = child.wait_with_output().expect("the server should exit");
+    assert!(
+        output.status.success(),
+        "the server exited with {}",
+        output.status
+    );
+    StdioRun {
+        responses: String::from_utf8(output.stdout)
+            .expect("stdout should be UTF-8")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each stdout line should be JSON"))
+            .collect(),
+        log: String::from_utf8(output.stderr).expect("stderr should be UTF-8"),
+    }
+}
+
+/// Build a crate for `wasm32-unknown-unknown` and encode it as a component.
+///
+/// # Arguments
+/// * `manifest_dir` - The crate's `CARGO_MANIFEST_DIR`
+/// * `target_tmpdir` - The test's `CARGO_TARGET_TMPDIR`, under which the
+///   build gets its own target directory
+/// * `lib_name` - The crate's library name, e.g. `finance_core_mcp`
+///
+/// # Returns
+/// The component's bytes
+///
+/// # Panics
+/// Panics if the build or the encoding fails
+pub fn build_component(manifest_dir: &str, target_tmpdir: &str, lib_name: &str) -> Vec<u8> {
+    let target_dir = PathBuf::from(target_tmpdir).join("components");
+    let status = Command::new(env!("CARGO"))
+        .args([
+            "build",
+            "--quiet",
+            "--lib",
+            "--target",
+            "wasm32-unknown-unknown",
+        ])
+        .arg("--manifest-path")
+        .arg(PathBuf::from(manifest_dir).join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("cargo should run");
+    assert!(
+        status.success(),
+        "building for wasm32-unknown-unknown failed"
+    );
+
+    let module = std::fs::read(
+        target_dir
+            .join("wasm32-unknown-unknown/debug")
+            .join(format!("{}.wasm", lib_name)),
+    )
+    .expect("the build should produce a wasm module");
+    wit_component::ComponentEncoder::default()
+        .module(&module)
+        .expect("the module should embed its WIT world")
+        .validate(true)
+        .encode()
+        .expect("the module should encode as a component")
+}
@@ -0,0 +1,733 @@
+//! Media Processor MCP Server
+//!
+//! This crate provides an MCP (Model Context Protocol) server interface for
+//! the media-processor image pipeline, so agents organizing a gallery can
+//! read EXIF data, find duplicate photos, and make thumbnails without the
+//! browser bindings. Images arrive as an [`ImageSource`]: inline base64 or a
+//! `file://` URI beneath the server's roots, which the server also lists as
+//! resources. It speaks JSON-RPC 2.0 through
+//! [`handle_jsonrpc`] and accepts the legacy request format through
+//! [`handle`]. Built for `wasm32`, it is a WebAssembly component exporting
+//! the WIT `handler` interface.
+
+#[cfg(target_arch = "wasm32")]
+mod component;
+pub mod source;
+
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use image::ImageReader;
+use mcp_protocol::{
+    CallToolResult, Implementation, JsonRpcError, McpServer, ReadResourceResult, Resource, Tool,
+    handle_legacy, handle_message, parse_arguments,
+};
+use media_processor::thumbnail::THUMB_MEDIUM;
+use media_processor::{DuplicateGroup, OutputFormat, compute_hashes, extract_exif};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+pub use mcp_protocol::{ErrorCode, McpError, McpRequest, McpResponse};
+pub use source::ImageSource;
+
+/// Default largest perceptual hash distance counted as a duplicate.
+pub const DEFAULT_MAX_DISTANCE: u32 = 10;
+
+/// Largest thumbnail size in pixels; thumbnails never need to be bigger,
+/// and upscaling to an arbitrary size could exhaust memory.
+pub const MAX_THUMBNAIL_SIZE: u32 = 1024;
+
+/// Default output quality for resized images.
+pub const DEFAULT_QUALITY: u8 = 85;
+
+/// Parameters for the extract_exif and compute_hashes tools.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageParams {
+    /// The image to inspect.
+    pub image: ImageSource,
+}
+
+/// One image in a find_duplicates batch.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NamedImage {
+    /// The caller's ID for the image, used in the returned groups.
+    pub id: String,
+    /// The image itself.
+    pub image: ImageSource,
+}
+
+/// Parameters for the find_duplicates tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FindDuplicatesParams {
+    /// The images to compare.
+    pub images: Vec<NamedImage>,
+    /// Largest perceptual hash distance counted as a duplicate (0-64).
+    #[serde(default = "default_max_distance")]
+    pub max_distance: u32,
+}
+
+/// Parameters for the generate_thumbnail tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThumbnailParams {
+    /// The image to shrink.
+    pub image: ImageSource,
+    /// Size of the thumbnail's larger side in pixels.
+    #[serde(default = "default_thumbnail_size")]
+    pub size: u32,
+    /// Whether to crop to a center square first.
+    #[serde(default)]
+    pub crop_to_square: bool,
+}
+
+/// Parameters for the resize_image tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResizeParams {
+    /// The image to resize.
+    pub image: ImageSource,
+    /// Largest allowed width or height in pixels.
+    pub max_dimension: u32,
+    /// Output quality (1-100).
+    #[serde(default = "default_quality")]
+    pub quality: u8,
+    /// Output format.
+    #[serde(default)]
+    pub format: OutputFormat,
+}
+
+/// Result of the find_duplicates tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicatesResult {
+    /// Groups of duplicate images, ordered by their first image.
+    pub groups: Vec<DuplicateGroup>,
+}
+
+/// Facts about an image returned by generate_thumbnail or resize_image,
+/// alongside the image itself.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+    /// Size of the encoded image in bytes.
+    pub size_bytes: usize,
+    /// The image's MIME type.
+    pub mime_type: String,
+}
+
+/// The media processing pipeline as an MCP server.
+///
+/// Tools read `file://` URIs only beneath the server's roots, and the images
+/// there are listed as resources. The default server has no roots, so it
+/// accepts inline base64 images only.
+#[derive(Debug, Clone, Default)]
+pub struct MediaProcessorServer {
+    roots: Vec<PathBuf>,
+}
+
+impl MediaProcessorServer {
+    /// Create a server that may read images beneath `roots`.
+    ///
+    /// # Arguments
+    /// * `roots` - Directories that `file://` URIs may point into
+    pub fn with_roots(roots: Vec<PathBuf>) -> Self {
+        Self { roots }
+    }
+
+    /// Read the image a tool was given.
+    fn read(&self, image: &ImageSource) -> Result<Vec<u8>, CallToolResult> {
+        image
+            .read(&self.roots)
+            .map_err(|e| CallToolResult::error(format!("Image error: {}", e)))
+    }
+}
+
+impl McpServer for MediaProcessorServer {
+    fn server_info(&self) -> Implementation {
+        Implementation::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    }
+
+    fn instructions(&self) -> Option<String> {
+        Some(
+            "Pass images as {\"data\": <base64>} or {\"uri\": \"file://...\"}. The images \
+             beneath the server's roots are listed by resources/list, and their URIs can be \
+             passed as they are. Only file:// URIs beneath the roots can be read; send \
+             anything else, including your own resource URIs, as base64."
+                .to_string(),
+        )
+    }
+
+    fn tools(&self) -> Vec<Tool> {
+        vec![
+            Tool::new(
+                "extract_exif",
+                "Read an image's EXIF metadata: when and with what camera it was taken, \
+                 GPS coordinates, orientation, and dimensions.",
+                image_schema(json!({})),
+            ),
+            Tool::new(
+                "compute_hashes",
+                "Compute an image's SHA-256 hash, which identifies exact copies, and its \
+                 perceptual hash, which stays close for visually similar images.",
+                image_schema(json!({})),
+            ),
+            Tool::new(
+                "find_duplicates",
+                "Group images that are exact copies or look alike. Images without a match \
+                 are left out.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "images": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "id": {"type": "string"},
+                                    "image": source::image_source_schema()
+                                },
+                                "required": ["id", "image"]
+                            }
+                        },
+                        "max_distance": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "maximum": 64,
+                            "default": DEFAULT_MAX_DISTANCE,
+                            "description": "Largest perceptual hash distance counted as a \
+                                            duplicate; lower is stricter"
+                        }
+                    },
+                    "required": ["images"]
+                }),
+            ),
+            Tool::new(
+                "generate_thumbnail",
+                "Shrink an image to a JPEG thumbnail, optionally cropped to a center square.",
+                image_schema(json!({
+                    "size": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_THUMBNAIL_SIZE,
+                        "default": THUMB_MEDIUM,
+                        "description": "Size of the larger side in pixels"
+                    },
+                    "crop_to_square": {"type": "boolean", "default": false}
+                })),
+            ),
+            Tool::new(
+                "resize_image",
+                "Resize an image to fit within a maximum width and height, keeping its \
+                 aspect ratio.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "image": source::image_source_schema(),
+                        "max_dimension": {"type": "integer", "minimum": 1},
+                        "quality": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "maximum": 100,
+                            "default": DEFAULT_QUALITY
+                        },
+                        "format": {
+                            "type": "string",
+                            "enum": ["jpeg", "webp", "png"],
+                            "default": "jpeg"
+                        }
+                    },
+                    "required": ["image", "max_dimension"]
+                }),
+            ),
+        ]
+    }
+
+    fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, JsonRpcError> {
+        match name {
+            "extract_exif" => self.extract_exif(parse_arguments(arguments)?),
+            "compute_hashes" => self.compute_hashes(parse_arguments(arguments)?),
+            "find_duplicates" => self.find_duplicates(parse_arguments(arguments)?),
+            "generate_thumbnail" => self.generate_thumbnail(parse_arguments(arguments)?),
+            "resize_image" => self.resize_image(parse_arguments(arguments)?),
+            _ => Err(JsonRpcError::unknown_tool(name)),
+        }
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        source::list_images(&self.roots)
+            .iter()
+            .filter_map(|path| {
+                Some(Resource {
+                    uri: source::file_uri(path)?,
+                    name: path.file_name()?.to_string_lossy().into_owned(),
+                    description: None,
+                    mime_type: source::image_mime_type(path).map(str::to_string),
+                })
+            })
+            .collect()
+    }
+
+    fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, JsonRpcError> {
+        let mime_type = source::image_mime_type(std::path::Path::new(uri))
+            .ok_or_else(|| JsonRpcError::resource_not_found(uri))?;
+        let data = ImageSource::Uri(uri.to_string())
+            .read(&self.roots)
+            .map_err(|_| JsonRpcError::resource_not_found(uri))?;
+        Ok(ReadResourceResult::blob(
+            uri,
+            mime_type,
+            STANDARD.encode(data),
+        ))
+    }
+}
+
+impl MediaProcessorServer {
+    /// Run the extract_exif tool.
+    fn extract_exif(&self, params: ImageParams) -> Result<CallToolResult, JsonRpcError> {
+        let data = match self.read(&params.image) {
+            Ok(data) => data,
+            Err(error) => return Ok(error),
+        };
+        match extract_exif(&data) {
+            Ok(exif) => CallToolResult::json(&exif),
+            Err(e) => Ok(CallToolResult::error(e.to_string())),
+        }
+    }
+
+    /// Run the compute_hashes tool.
+    fn compute_hashes(&self, params: ImageParams) -> Result<CallToolResult, JsonRpcError> {
+        let data = match self.read(&params.image) {
+            Ok(data) => data,
+            Err(error) => return Ok(error),
+        };
+        match compute_hashes(&data) {
+            Ok(hashes) => CallToolResult::json(&hashes),
+            Err(e) => Ok(CallToolResult::error(e.to_string())),
+        }
+    }
+
+    /// Run the find_duplicates tool.
+    fn find_duplicates(
+        &self,
+        params: FindDuplicatesParams,
+    ) -> Result<CallToolResult, JsonRpcError> {
+        if params.max_distance > 64 {
+            return Ok(CallToolResult::validation_error(McpError::validation(
+                "/max_distance",
+                format!(
+                    "max_distance must be at most 64, got {}",
+                    params.max_distance
+                ),
+            )));
+        }
+
+        let mut hashes = Vec::with_capacity(params.images.len());
+        for NamedImage { id, image } in params.images {
+            let data = match image.read(&self.roots) {
+                Ok(data) => data,
+                Err(e) => return Ok(CallToolResult::error(format!("image {}: {}", id, e))),
+            };
+            match compute_hashes(&data) {
+                Ok(hash) => hashes.push((id, hash)),
+                Err(e) => return Ok(CallToolResult::error(format!("image {}: {}", id, e))),
+            }
+        }
+
+        match media_processor::find_duplicates(&hashes, params.max_distance) {
+            Ok(groups) => CallToolResult::json(&DuplicatesResult { groups }),
+            Err(e) => Ok(CallToolResult::error(e.to_string())),
+        }
+    }
+
+    /// Run the generate_thumbnail tool.
+    fn generate_thumbnail(&self, params: ThumbnailParams) -> Result<CallToolResult, JsonRpcError> {
+        if !(1..=MAX_THUMBNAIL_SIZE).contains(&params.size) {
+            return Ok(CallToolResult::validation_error(McpError::validation(
+                "/size",
+                format!(
+                    "size must be between 1 and {}, got {}",
+                    MAX_THUMBNAIL_SIZE, params.size
+                ),
+            )));
+        }
+        let data = match self.read(&params.image) {
+            Ok(data) => data,
+            Err(error) => return Ok(error),
+        };
+
+        match media_processor::generate_thumbnail(&data, params.size, params.crop_to_square) {
+            Ok(thumbnail) => image_result(
+                thumbnail.data,
+                ImageMetadata {
+                    width: thumbnail.width,
+                    height: thumbnail.height,
+                    size_bytes: thumbnail.size_bytes,
+                    mime_type: "image/jpeg".to_string(),
+                },
+            ),
+            Err(e) => Ok(CallToolResult::error(e.to_string())),
+        }
+    }
+
+    /// Run the resize_image tool.
+    fn resize_image(&self, params: ResizeParams) -> Result<CallToolResult, JsonRpcError> {
+        if !(1..=100).contains(&params.quality) {
+            return Ok(CallToolResult::validation_error(McpError::validation(
+                "/quality",
+                format!("quality must be between 1 and 100, got {}", params.quality),
+            )));
+        }
+        let data = match self.read(&params.image) {
+            Ok(data) => data,
+            Err(error) => return Ok(error),
+        };
+
+        let resized = match media_processor::resize_image_with_format(
+            &data,
+            params.max_dimension,
+            params.quality,
+            params.format,
+        ) {
+            Ok(resized) => resized,
+            Err(e) => return Ok(CallToolResult::error(e.to_string())),
+        };
+        let (width, height) = ImageReader::new(Cursor::new(&resized))
+            .with_guessed_format()
+            .map_err(JsonRpcError::internal_error)?
+            .into_dimensions()
+            .map_err(JsonRpcError::internal_error)?;
+
+        let size_bytes = resized.len();
+        image_result(
+            resized,
+            ImageMetadata {
+                width,
+                height,
+                size_bytes,
+                mime_type: mime_type(params.format).to_string(),
+            },
+        )
+    }
+}
+
+/// Handle a JSON-RPC 2.0 message.
+///
+/// The server has no roots, so images must be passed as base64.
+///
+/// # Arguments
+/// * `message` - The raw JSON-RPC message
+///
+/// # Returns
+/// The JSON-encoded response, or `None` if the message was a notification
+pub fn handle_jsonrpc(message: &str) -> Option<String> {
+    handle_message(&MediaProcessorServer::default(), message)
+}
+
+/// Handle a legacy MCP request and return an MCP response.
+///
+/// The legacy format only carries text, so generate_thumbnail and
+/// resize_image return the image metadata without the image itself.
+///
+/// # Arguments
+/// * `request` - The MCP request to process
+///
+/// # Returns
+/// An McpResponse containing either the result or an error
+pub fn handle(request: McpRequest) -> McpResponse {
+    handle_legacy(&MediaProcessorServer::default(), request, |_, params| {
+        params
+    })
+}
+
+/// JSON Schema for a tool taking an image plus `properties`.
+fn image_schema(mut properties: Value) -> Value {
+    properties["image"] = source::image_source_schema();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": ["image"]
+    })
+}
+
+/// Return an encoded image with its metadata.
+fn image_result(data: Vec<u8>, metadata: ImageMetadata) -> Result<CallToolResult, JsonRpcError> {
+    CallToolResult::image(STANDARD.encode(data), &metadata.mime_type, &metadata)
+}
+
+/// The MIME type of images encoded as `format`.
+fn mime_type(format: OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Jpeg => "image/jpeg",
+        OutputFormat::WebP => "image/webp",
+        OutputFormat::Png => "image/png",
+    }
+}
+
+fn default_max_distance() -> u32 {
+    DEFAULT_MAX_DISTANCE
+}
+
+fn default_thumbnail_size() -> u32 {
+    THUMB_MEDIUM
+}
+
+fn default_quality() -> u8 {
+    DEFAULT_QUALITY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_protocol::Content;
+    use media_processor::{ExifData, HashResult};
+
+    fn fixture(name: &str) -> Value {
+        let path = format!(
+            "{}/../../media-processor/tests/fixtures/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        json!({"data": STANDARD.encode(std::fs::read(path).unwrap())})
+    }
+
+    /// A PNG gradient, which looks nothing like the solid-color fixtures.
+    fn gradient() -> Value {
+        let image =
+            image::RgbImage::from_fn(64, 32, |x, y| image::Rgb([x as u8 * 4, y as u8 * 8, 0]));
+        let mut png = Cursor::new(Vec::new());
+        image.write_to(&mut png, image::ImageFormat::Png).unwrap();
+        json!({"data": STANDARD.encode(png.into_inner())})
+    }
+
+    fn call(method: &str, params: Value) -> McpResponse {
+        handle(McpRequest {
+            method: method.to_string(),
+            params,
+        })
+    }
+
+    fn call_tool(name: &str, arguments: Value) -> CallToolResult {
+        MediaProcessorServer::default()
+            .call_tool(name, arguments)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_extract_exif_reads_camera_and_gps() {
+        let response = call("extract_exif", json!({"image": fixture("with_exif.jpg")}));
+        let exif: ExifData = serde_json::from_str(&response.into_result().unwrap()).unwrap();
+        assert_eq!(exif.camera_make.as_deref(), Some("TestCamera"));
+        assert!((exif.gps_latitude.unwrap() - 40.7128).abs() < 0.001);
+
+        let response = call("extract_exif", json!({"image": fixture("no_exif.jpg")}));
+        assert_eq!(
+            response.into_result().unwrap_err(),
+            McpError::new(ErrorCode::ToolFailed, "No EXIF data found in image")
+        );
+    }
+
+    #[test]
+    fn test_compute_hashes() {
+        let response = call("compute_hashes", json!({"image": fixture("no_exif.jpg")}));
+        let hashes: HashResult = serde_json::from_str(&response.into_result().unwrap()).unwrap();
+        assert_eq!(hashes.sha256.len(), 64);
+        assert_eq!(hashes.perceptual.len(), 16);
+    }
+
+    #[test]
+    fn test_find_duplicates_groups_look_alikes() {
+        let response = call(
+            "find_duplicates",
+            json!({"images": [
+                {"id": "red", "image": fixture("with_exif.jpg")},
+                {"id": "gradient", "image": gradient()},
+                {"id": "red-copy", "image": fixture("with_exif.jpg")},
+                {"id": "blue", "image": fixture("no_exif.jpg")}
+            ]}),
+        );
+        let DuplicatesResult { groups } =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
+        // Solid colors share a perceptual hash, so only the gradient stands out
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].ids, vec!["red", "red-copy", "blue"]);
+        assert!(!groups[0].exact);
+
+        let response = call("find_duplicates", json!({"images": [], "max_distance": 65}));
+        assert_eq!(
+            response.into_result().unwrap_err(),
+            McpError::validation("/max_distance", "max_distance must be at most 64, got 65")
+        );
+    }
+
+    #[test]
+    fn test_generate_thumbnail_returns_image_then_metadata() {
+        let result = call_tool(
+            "generate_thumbnail",
+            json!({"image": gradient(), "size": 16}),
+        );
+        assert!(!result.is_error);
+        let Content::Image { data, mime_type } = &result.content[0] else {
+            panic!("expected an image first, got {:?}", result.content);
+        };
+        assert_eq!(mime_type, "image/jpeg");
+        let thumbnail = image::load_from_memory(&STANDARD.decode(data).unwrap()).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (16, 8));
+
+        let metadata = result.structured_content.unwrap();
+        assert_eq!(metadata["width"], 16);
+        assert_eq!(metadata["height"], 8);
+
+        // The size is checked before the image is read or decoded
+        let response = call(
+            "generate_thumbnail",
+            json!({"image": {"data": "not base64!"}, "size": 4_000_000_000u32}),
+        );
+        assert_eq!(
+            response.into_result().unwrap_err(),
+            McpError::validation("/size", "size must be between 1 and 1024, got 4000000000")
+        );
+    }
+
+    #[test]
+    fn test_resize_image_in_requested_format() {
+        let result = call_tool(
+            "resize_image",
+            json!({"image": gradient(), "max_dimension": 32, "format": "png"}),
+        );
+        assert!(!result.is_error);
+        let metadata: ImageMetadata =
+            serde_json::from_value(result.structured_content.unwrap()).unwrap();
+        assert_eq!((metadata.width, metadata.height), (32, 16));
+        assert_eq!(metadata.mime_type, "image/png");
+
+        // The legacy format gets the metadata alone
+        let response = call(
+            "resize_image",
+            json!({"image": fixture("no_exif.jpg"), "max_dimension": 10}),
+        );
+        let metadata: ImageMetadata =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
+        assert_eq!((metadata.width, metadata.height), (10, 10));
+
+        let response = call(
+            "resize_image",
+            json!({"image": gradient(), "max_dimension": 32, "quality": 0}),
+        );
+        assert_eq!(
+            response.into_result().unwrap_err(),
+            McpError::validation("/quality", "quality must be between 1 and 100, got 0")
+        );
+    }
+
+    #[test]
+    fn test_bad_images_return_tool_errors() {
+        let response = call(
+            "compute_hashes",
+            json!({"image": {"uri": "file:///etc/passwd"}}),
+        );
+        assert_eq!(
+            response.into_result().unwrap_err().message,
+            "Image error: file:///etc/passwd is outside the server's roots"
+        );
+
+        let response = call(
+            "generate_thumbnail",
+            json!({"image": {"data": STANDARD.encode(b"not an image")}}),
+        );
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::ToolFailed);
+        assert!(error.message.starts_with("Failed to decode image"));
+
+        let response = call(
+            "find_duplicates",
+            json!({"images": [
+                {"id": "red", "image": fixture("with_exif.jpg")},
+                {"id": "missing", "image": {"uri": "file:///etc/passwd"}}
+            ]}),
+        );
+        assert_eq!(
+            response.into_result().unwrap_err().message,
+            "image missing: file:///etc/passwd is outside the server's roots"
+        );
+
+        let response = call("compute_hashes", json!({"image": "photo.jpg"}));
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(error.pointer.as_deref(), Some("/image"));
+    }
+
+    #[test]
+    fn test_uris_read_beneath_roots() {
+        let fixtures = PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../media-processor/tests/fixtures"
+        ))
+        .canonicalize()
+        .unwrap();
+        let server = MediaProcessorServer::with_roots(vec![fixtures.clone()]);
+        let uri = format!("file://{}", fixtures.join("gps_only.jpg").display());
+
+        let result = server
+            .call_tool("extract_exif", json!({"image": {"uri": uri}}))
+            .unwrap();
+        assert!(!result.is_error, "{}", result.text_content());
+        assert!(result.structured_content.unwrap()["gps_latitude"].is_number());
+    }
+
+    #[test]
+    fn test_images_beneath_roots_are_resources() {
+        let fixtures = PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../media-processor/tests/fixtures"
+        ))
+        .canonicalize()
+        .unwrap();
+        let server = MediaProcessorServer::with_roots(vec![fixtures.clone()]);
+
+        let resources = server.resources();
+        let resource = resources.iter().find(|r| r.name == "gps_only.jpg").unwrap();
+        assert_eq!(resource.mime_type.as_deref(), Some("image/jpeg"));
+
+        let read = server.read_resource(&resource.uri).unwrap();
+        let blob = read.contents[0].blob.as_deref().unwrap();
+        assert_eq!(
+            STANDARD.decode(blob).unwrap(),
+            std::fs::read(fixtures.join("gps_only.jpg")).unwrap()
+        );
+
+        // A listed URI works as a tool's image
+        let result = server
+            .call_tool("extract_exif", json!({"image": {"uri": resource.uri}}))
+            .unwrap();
+        assert!(!result.is_error, "{}", result.text_content());
+
+        assert!(server.read_resource("file:///etc/passwd.png").is_err());
+        assert!(MediaProcessorServer::default().resources().is_empty());
+    }
+
+    fn jsonrpc(message: Value) -> Value {
+        serde_json::from_str(&handle_jsonrpc(&message.to_string()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_jsonrpc_tools_list_has_schemas() {
+        let response = jsonrpc(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}));
+        let tools = response["result"]["tools"].as_array().unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t["name"].as_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "extract_exif",
+                "compute_hashes",
+                "find_duplicates",
+                "generate_thumbnail",
+                "resize_image"
+            ]
+        );
+        for tool in tools {
+            assert_eq!(tool["inputSchema"]["type"], "object");
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Media Processor MCP server.
+//!
+//! By default reads newline-delimited JSON-RPC messages from stdin and writes
+//! responses to stdout, logging to stderr. Each `--root <dir>` lets tools
+//! read `file://` URIs beneath that directory and lists its images as
+//! resources; without one, images must be passed as base64. An MCP client can launch it with a config entry such as:
+//!
+//! ```json
+//! {
+//!   "mcpServers": {
+//!     "media-processor": {
+//!       "command": "media-processor-mcp",
+//!       "args": ["--root", "/home/me/Pictures"]
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Built with the `http` feature, `media-processor-mcp --http 127.0.0.1:8080`
+//! serves the streamable HTTP transport at `/mcp` instead.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use media_processor_mcp::MediaProcessorServer;
+
+#[cfg(feature = "http")]
+const USAGE: &str = "usage: media-processor-mcp [--root <dir>]... [--http <addr>]";
+#[cfg(not(feature = "http"))]
+const USAGE: &str = "usage: media-processor-mcp [--root <dir>]...";
+
+fn main() -> ExitCode {
+    let mut roots = Vec::new();
+    let mut http = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        match (flag.as_str(), args.next()) {
+            ("--root", Some(dir)) => roots.push(PathBuf::from(dir)),
+            ("--http", Some(addr)) if cfg!(feature = "http") => http = Some(addr),
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    for root in &roots {
+        if !root.is_dir() {
+            eprintln!("media-processor-mcp: {} is not a directory", root.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let server = MediaProcessorServer::with_roots(roots);
+    let served = match http {
+        #[cfg(feature = "http")]
+        Some(addr) => mcp_protocol::serve_http(server, &addr),
+        _ => mcp_protocol::serve_stdio(&server),
+    };
+
+    match served {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("media-processor-mcp: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Where a tool reads its image from.
+//!
+//! Clients either inline the image as base64 or refer to it by a `file://`
+//! URI. URIs are only read from beneath the server's roots, so an agent
+//! cannot use the server to read arbitrary files. The server lists the
+//! images beneath its roots as MCP resources, so the URIs from
+//! `resources/list` can be passed straight to a tool. Other schemes,
+//! including the client's own resource URIs, are refused: the server cannot
+//! fetch a resource back from the client, so such images must be sent as
+//! base64. The WebAssembly component has no roots and only accepts base64.
+
+use std::path::{Path, PathBuf};
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Most images listed as resources, so a large root stays cheap to list.
+pub const MAX_LISTED_IMAGES: usize = 1000;
+
+/// An image passed to a tool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageSource {
+    /// The image bytes, base64-encoded.
+    Data(String),
+    /// A `file://` URI naming an image beneath one of the server's roots.
+    Uri(String),
+}
+
+impl ImageSource {
+    /// Read the image bytes.
+    ///
+    /// # Arguments
+    /// * `roots` - Directories that URIs may point into
+    ///
+    /// # Returns
+    /// The raw image bytes
+    ///
+    /// # Errors
+    /// Returns a message for the model if the data is not valid base64, or
+    /// if the URI is not a `file://` URI naming a readable file beneath one
+    /// of `roots`
+    pub fn read(&self, roots: &[PathBuf]) -> Result<Vec<u8>, String> {
+        match self {
+            ImageSource::Data(data) => STANDARD
+                .decode(data)
+                .map_err(|e| format!("image data is not valid base64: {}", e)),
+            ImageSource::Uri(uri) => read_uri(uri, roots),
+        }
+    }
+}
+
+/// JSON Schema for an image source.
+pub fn image_source_schema() -> Value {
+    json!({
+        "type": "object",
+        "oneOf": [
+            {
+                "properties": {
+                    "data": {"type": "string", "description": "Base64-encoded image bytes"}
+                },
+                "required": ["data"]
+            },
+            {
+                "properties": {
+                    "uri": {
+                        "type": "string",
+                        "description": "file:// URI of an image beneath one of the server's \
+                                        --root directories, as listed by resources/list; \
+                                        no other scheme is read"
+                    }
+                },
+                "required": ["uri"]
+            }
+        ]
+    })
+}
+
+/// Find the images beneath `roots`, in directory order.
+///
+/// Symlinks are skipped, and the search stops after [`MAX_LISTED_IMAGES`].
+///
+/// # Arguments
+/// * `roots` - Directories to search recursively
+///
+/// # Returns
+/// The paths of files with an image extension
+pub fn list_images(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut images = Vec::new();
+    let mut dirs: Vec<PathBuf> = roots.iter().rev().cloned().collect();
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut entries: Vec<_> = entries.filter_map(Result::ok).collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let mut subdirs = Vec::new();
+        for entry in entries {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                subdirs.push(path);
+            } else if file_type.is_file() && image_mime_type(&path).is_some() {
+                images.push(path);
+                if images.len() == MAX_LISTED_IMAGES {
+                    return images;
+                }
+            }
+        }
+        dirs.extend(subdirs.into_iter().rev());
+    }
+    images
+}
+
+/// The `file://` URI of an absolute path, percent-encoding each segment.
+///
+/// # Returns
+/// The URI, or `None` if the path is not valid UTF-8
+pub fn file_uri(path: &Path) -> Option<String> {
+    let path = path.to_str()?;
+    let segments: Vec<_> = path.split('/').map(urlencoding::encode).collect();
+    Some(format!("file://{}", segments.join("/")))
+}
+
+/// The MIME type of an image, judged by its file extension.
+pub fn image_mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "png" => Some("image/png"),
+        "webp" => Some("image/webp"),
+        "gif" => Some("image/gif"),
+        _ => None,
+    }
+}
+
+/// Read a `file://` URI, refusing anything outside `roots`.
+fn read_uri(uri: &str, roots: &[PathBuf]) -> Result<Vec<u8>, String> {
+    let encoded = uri.strip_prefix("file://").ok_or_else(|| {
+        format!(
+            "unsupported URI {}: only file:// URIs can be read; send the image as base64 data",
+            uri
+        )
+    })?;
+    let path = urlencoding::decode(encoded)
+        .map_err(|e| format!("invalid URI {}: {}", uri, e))?
+        .into_owned();
+    if !Path::new(&path).is_absolute() {
+        return Err(format!("invalid URI {}: the path must be absolute", uri));
+    }
+
+    // Resolving symlinks and `..` first keeps a path from escaping its root
+    let path = Path::new(&path)
+        .canonicalize()
+        .map_err(|e| format!("cannot read {}: {}", uri, e))?;
+    let allowed = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| path.starts_with(root));
+    if !allowed {
+        return Err(format!("{} is outside the server's roots", uri));
+    }
+
+    std::fs::read(&path).map_err(|e| format!("cannot read {}: {}", uri, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures() -> PathBuf {
+        PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../media-processor/tests/fixtures"
+        ))
+        .canonicalize()
+        .unwrap()
+    }
+
+    fn uri(path: &Path) -> ImageSource {
+        ImageSource::Uri(format!("file://{}", path.display()))
+    }
+
+    #[test]
+    fn test_reads_base64_and_uris_under_roots() {
+        let path = fixtures().join("no_exif.jpg");
+        let bytes = std::fs::read(&path).unwrap();
+
+        let data = ImageSource::Data(STANDARD.encode(&bytes));
+        assert_eq!(data.read(&[]).unwrap(), bytes);
+        assert_eq!(uri(&path).read(&[fixtures()]).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_lists_images_as_readable_uris() {
+        let images = list_images(&[fixtures()]);
+        assert!(images.contains(&fixtures().join("no_exif.jpg")));
+        assert!(images.iter().all(|path| image_mime_type(path).is_some()));
+
+        let uri = file_uri(&images[0]).unwrap();
+        assert_eq!(
+            ImageSource::Uri(uri).read(&[fixtures()]).unwrap(),
+            std::fs::read(&images[0]).unwrap()
+        );
+
+        assert_eq!(
+            file_uri(Path::new("/photos/a b%.jpg")).unwrap(),
+            "file:///photos/a%20b%25.jpg"
+        );
+    }
+
+    #[test]
+    fn test_refuses_uris_outside_roots() {
+        let roots = [fixtures()];
+
+        let escape = fixtures().join("../../Cargo.toml");
+        let error = uri(&escape).read(&roots).unwrap_err();
+        assert!(
+            error.ends_with("is outside the server's roots"),
+            "{}",
+            error
+        );
+
+        let error = uri(&fixtures().join("no_exif.jpg")).read(&[]).unwrap_err();
+        assert!(
+            error.ends_with("is outside the server's roots"),
+            "{}",
+            error
+        );
+
+        let error = ImageSource::Uri("https://example.com/a.jpg".to_string())
+            .read(&roots)
+            .unwrap_err();
+        assert!(error.starts_with("unsupported URI"), "{}", error);
+
+        let error = ImageSource::Data("not base64!".to_string())
+            .read(&roots)
+            .unwrap_err();
+        assert!(
+            error.starts_with("image data is not valid base64"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Runs the crate as a WebAssembly component in a local wasmtime host.
+//!
+//! The crate is built for `wasm32-unknown-unknown` once per test run,
+//! encoded as a component, and instantiated against the
+//! `media-processor-mcp` world with no imports. The build needs that target
+//! installed, so these tests are ignored by default; CI runs them with
+//! `cargo test -- --ignored`.
+
+use std::sync::LazyLock;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use mcp_test_support::build_component;
+use serde_json::{Value, json};
+use wasmtime::component::{Component, Linker, bindgen};
+use wasmtime::{Engine, Store};
+
+bindgen!({
+    world: "steamboat:media-processor/media-processor-mcp",
+    path: ["../wit/mcp-server.wit", "../wit/media-processor.wit"],
+});
+
+/// The compiled component, shared by every test.
+static COMPONENT: LazyLock<(Engine, Component)> = LazyLock::new(|| {
+    let bytes = build_component(
+        env!("CARGO_MANIFEST_DIR"),
+        env!("CARGO_TARGET_TMPDIR"),
+        "media_processor_mcp",
+    );
+    let engine = Engine::default();
+    let component = Component::new(&engine, bytes).unwrap();
+    (engine, component)
+});
+
+fn instantiate() -> (Store<()>, MediaProcessorMcp) {
+    let (engine, component) = &*COMPONENT;
+    let mut store = Store::new(engine, ());
+    let instance = MediaProcessorMcp::instantiate(&mut store, component, &Linker::new(engine))
+        .expect("the component should need no imports");
+    (store, instance)
+}
+
+/// A fixture image as an inline image source.
+fn fixture(name: &str) -> Value {
+    let path = format!(
+        "{}/../../media-processor/tests/fixtures/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    json!({"data": STANDARD.encode(std::fs::read(path).unwrap())})
+}
+
+#[test]
+#[ignore = "builds the crate for wasm32-unknown-unknown; run with --ignored"]
+fn test_legacy_handle() {
+    let (mut store, instance) = instantiate();
+    let handler = instance.steamboat_mcp_server_handler();
+
+    let request = McpRequest {
+        method: "extract_exif".to_string(),
+        params: json!({"image": fixture("with_exif.jpg")}).to_string(),
+    };
+    let McpResponse::Ok(output) = handler.call_handle(&mut store, &request).unwrap() else {
+        panic!("extract_exif should succeed");
+    };
+    let result: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(result["camera_make"], "TestCamera");
+
+    // A component has no filesystem to read URIs from
+    let request = McpRequest {
+        method: "compute_hashes".to_string(),
+        params: json!({"image": {"uri": "file:///photos/a.jpg"}}).to_string(),
+    };
+    let McpResponse::Err(error) = handler.call_handle(&mut store, &request).unwrap() else {
+        panic!("reading a URI should fail");
+    };
+    assert!(matches!(error.code, ErrorCode::ToolFailed));
+    assert!(error.message.starts_with("Image error"));
+}
+
+#[test]
+#[ignore = "builds the crate for wasm32-unknown-unknown; run with --ignored"]
+fn test_jsonrpc_messages() {
+    let (mut store, instance) = instantiate();
+    let handler = instance.steamboat_mcp_server_handler();
+    let mut call = |message: Value| {
+        handler
+            .call_handle_jsonrpc(&mut store, &message.to_string())
+            .unwrap()
+            .map(|response| serde_json::from_str::<Value>(&response).unwrap())
+    };
+
+    let response = call(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "find_duplicates",
+            "arguments": {"images": [
+                {"id": "a", "image": fixture("with_exif.jpg")},
+                {"id": "b", "image": fixture("with_exif.jpg")}
+            ]}
+        }
+    }))
+    .unwrap();
+    let groups = &response["result"]["structuredContent"]["groups"];
+    assert_eq!(groups[0]["ids"], json!(["a", "b"]));
+    assert_eq!(groups[0]["exact"], true);
+
+    let response = call(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "resize_image",
+            "arguments": {"image": fixture("no_exif.jpg"), "max_dimension": 20, "format": "webp"}
+        }
+    }))
+    .unwrap();
+    assert_eq!(response["result"]["content"][0]["mimeType"], "image/webp");
+    assert_eq!(response["result"]["structuredContent"]["width"], 20);
+}
@@ -0,0 +1,39 @@
+//! Exercises the streamable HTTP transport over a loopback listener.
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use mcp_protocol::HttpTransport;
+use mcp_test_support::{HttpSession, start_http};
+use media_processor_mcp::MediaProcessorServer;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_thumbnail_over_http() {
+    let url = start_http(HttpTransport::new(MediaProcessorServer::default())).await;
+    let (session, _) = HttpSession::initialize(&url, "2025-06-18").await;
+
+    let fixture = std::fs::read(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../media-processor/tests/fixtures/with_exif.jpg"
+    ))
+    .unwrap();
+    let body = session
+        .request(&json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "generate_thumbnail",
+                "arguments": {
+                    "image": {"data": STANDARD.encode(&fixture)},
+                    "size": 40
+                }
+            }
+        }))
+        .await;
+    let result = &body["result"];
+    assert_eq!(result["content"][0]["type"], "image");
+    assert_eq!(result["content"][0]["mimeType"], "image/jpeg");
+    assert_eq!(result["structuredContent"]["width"], 40);
+    assert_eq!(result["structuredContent"]["height"], 40);
+}
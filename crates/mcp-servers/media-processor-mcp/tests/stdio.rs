@@ -0,0 +1,83 @@
+//! Runs the stdio binary the way an MCP client would.
+
+use std::process::{Command, Stdio};
+
+use mcp_test_support::run_stdio;
+use serde_json::json;
+
+const FIXTURES: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../media-processor/tests/fixtures"
+);
+
+#[test]
+fn test_stdio_session_reads_uris_beneath_root() {
+    let uri = |name: &str| format!("file://{}/{}", FIXTURES, name);
+    let run = run_stdio(
+        env!("CARGO_BIN_EXE_media-processor-mcp"),
+        &["--root", FIXTURES],
+        &[
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {"protocolVersion": "2025-06-18", "capabilities": {}}
+            }),
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {
+                    "name": "extract_exif",
+                    "arguments": {"image": {"uri": uri("with_exif.jpg")}}
+                }
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "tools/call",
+                "params": {
+                    "name": "compute_hashes",
+                    "arguments": {"image": {"uri": uri("../../Cargo.toml")}}
+                }
+            }),
+        ],
+    );
+
+    assert_eq!(run.responses.len(), 3);
+    assert_eq!(
+        run.responses[0]["result"]["serverInfo"]["name"],
+        "media-processor-mcp"
+    );
+    assert_eq!(
+        run.responses[1]["result"]["structuredContent"]["camera_model"],
+        "TestModel 2024"
+    );
+    assert_eq!(run.responses[2]["result"]["isError"], true);
+    assert!(
+        run.responses[2]["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .ends_with("is outside the server's roots")
+    );
+    assert!(run.log.contains("serving MCP over stdio"));
+}
+
+#[test]
+fn test_missing_root_is_rejected() {
+    let output = Command::new(env!("CARGO_BIN_EXE_media-processor-mcp"))
+        .args([
+            "--root",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/no-such-dir"),
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("is not a directory")
+    );
+}
@@ -1,4 +1,16 @@
+#[cfg(target_arch = "wasm32")]
+mod component;
+pub mod settle;
+
+use mcp_protocol::{
+    CallToolResult, Implementation, ItemResult, JsonRpcError, McpServer, Tool, handle_legacy,
+    handle_message, parse_arguments, parse_item,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+pub use mcp_protocol::{ErrorCode, McpError, McpRequest, McpResponse};
+pub use settle::{PaymentProfile, PlatformAccount, SettleUpPayment, SettleUpResult, settle_up};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -17,71 +29,221 @@ pub struct PaymentLinkRequest {
     pub memo: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentLinkResult {
     pub platform: Platform,
     pub link: Option<String>,
     pub fallback_text: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct McpRequest {
-    pub method: String,
-    pub params: String,
+/// Parameters of the `generate_payment_links` tool.
+#[derive(Debug, Deserialize)]
+struct BulkPaymentLinkParams {
+    requests: Vec<Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct McpResponse {
-    pub success: bool,
-    pub result: Option<String>,
-    pub error: Option<String>,
+/// Results of the `generate_payment_links` tool, in request order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkPaymentLinkResult {
+    pub results: Vec<ItemResult<PaymentLinkResult>>,
 }
 
-/// Handle an MCP request and return a response
+/// Generate a link for each request, reporting requests that do not parse
+/// or have a non-positive amount as errors in their place.
+pub fn generate_payment_links(requests: Vec<Value>) -> BulkPaymentLinkResult {
+    let results = requests
+        .into_iter()
+        .enumerate()
+        .map(|(i, request)| {
+            let pointer = format!("/requests/{}", i);
+            parse_item(&pointer, request)
+                .and_then(|request| try_generate_link(&request, &pointer))
+                .into()
+        })
+        .collect();
+    BulkPaymentLinkResult { results }
+}
+
+/// The payment link generator as an MCP server.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PaymentLinksServer;
+
+impl McpServer for PaymentLinksServer {
+    fn server_info(&self) -> Implementation {
+        Implementation::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    }
+
+    fn tools(&self) -> Vec<Tool> {
+        vec![
+            Tool::new(
+                "generate_payment_link",
+                "Create a deep link that pre-fills a payment on Venmo, PayPal, or Cash App, \
+                 plus fallback text for Zelle or when links are unavailable.",
+                payment_link_request_schema(),
+            ),
+            Tool::new(
+                "generate_payment_links",
+                "Create payment links for several payments at once. Each request gets its own \
+                 result or error, so one bad request does not fail the rest.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "requests": {"type": "array", "items": payment_link_request_schema()}
+                    },
+                    "required": ["requests"]
+                }),
+            ),
+            Tool::new(
+                "settle_up",
+                "Simplify a group's debts into the fewest payments and give each payment a \
+                 link on a platform the payer and recipient both use, or fallback text if \
+                 they share none.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "debts": debts_schema(),
+                        "profiles": profiles_schema()
+                    },
+                    "required": ["debts", "profiles"]
+                }),
+            ),
+        ]
+    }
+
+    fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, JsonRpcError> {
+        match name {
+            "generate_payment_link" => {
+                let request: PaymentLinkRequest = parse_arguments(arguments)?;
+                match try_generate_link(&request, "") {
+                    Ok(result) => CallToolResult::json(&result),
+                    Err(e) => Ok(CallToolResult::validation_error(e)),
+                }
+            }
+            "generate_payment_links" => {
+                let params: BulkPaymentLinkParams = parse_arguments(arguments)?;
+                CallToolResult::json(&generate_payment_links(params.requests))
+            }
+            "settle_up" => {
+                let params: settle::SettleUpParams = parse_arguments(arguments)?;
+                match settle_up(&params.debts, &params.profiles) {
+                    Ok(result) => CallToolResult::json(&result),
+                    Err(e) => Ok(CallToolResult::validation_error(e)),
+                }
+            }
+            _ => Err(JsonRpcError::unknown_tool(name)),
+        }
+    }
+}
+
+/// Handle a JSON-RPC 2.0 message.
+///
+/// Returns the JSON-encoded response, or `None` if the message was a
+/// notification.
+pub fn handle_jsonrpc(message: &str) -> Option<String> {
+    handle_message(&PaymentLinksServer, message)
+}
+
+/// Handle a legacy MCP request and return a response
 pub fn handle(request: McpRequest) -> McpResponse {
-    match request.method.as_str() {
-        "generate_payment_link" => generate_payment_link(&request.params),
-        _ => McpResponse {
-            success: false,
-            result: None,
-            error: Some(format!("Unknown method: {}", request.method)),
+    handle_legacy(&PaymentLinksServer, request, |_, params| params)
+}
+
+/// JSON Schema for a single payment link request.
+fn payment_link_request_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "platform": platform_schema(),
+            "recipient": {
+                "type": "string",
+                "description": "Recipient's handle, email, or phone number on the platform"
+            },
+            "amount_cents": {"type": "integer", "minimum": 1},
+            "memo": {"type": "string"}
         },
-    }
+        "required": ["platform", "recipient", "amount_cents", "memo"]
+    })
+}
+
+/// JSON Schema for a platform name.
+fn platform_schema() -> Value {
+    json!({"type": "string", "enum": ["venmo", "paypal", "cashapp", "zelle"]})
 }
 
-/// Generate a payment link based on the request parameters
-fn generate_payment_link(params: &str) -> McpResponse {
-    // Parse the request
-    let request: PaymentLinkRequest = match serde_json::from_str(params) {
-        Ok(req) => req,
-        Err(e) => {
-            return McpResponse {
-                success: false,
-                result: None,
-                error: Some(format!("Failed to parse request: {}", e)),
-            };
+/// JSON Schema for a list of debts.
+fn debts_schema() -> Value {
+    json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {
+                "debtor": {"type": "string", "description": "Person who owes money"},
+                "creditor": {"type": "string", "description": "Person who is owed money"},
+                "amount_cents": {"type": "integer", "minimum": 1},
+                "expense_ids": {"type": "array", "items": {"type": "string"}}
+            },
+            "required": ["debtor", "creditor", "amount_cents", "expense_ids"]
         }
-    };
+    })
+}
 
-    // Generate the payment link result
-    let result = generate_link_for_platform(&request);
+/// JSON Schema for a list of payment profiles.
+fn profiles_schema() -> Value {
+    json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {
+                "person": {"type": "string"},
+                "accounts": {
+                    "type": "array",
+                    "description": "The person's accounts, most preferred first",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "platform": platform_schema(),
+                            "handle": {
+                                "type": "string",
+                                "description": "Handle, email, or phone number on the platform"
+                            }
+                        },
+                        "required": ["platform", "handle"]
+                    }
+                }
+            },
+            "required": ["person", "accounts"]
+        }
+    })
+}
 
-    // Serialize the result
-    match serde_json::to_string(&result) {
-        Ok(json) => McpResponse {
-            success: true,
-            result: Some(json),
-            error: None,
-        },
-        Err(e) => McpResponse {
-            success: false,
-            result: None,
-            error: Some(format!("Failed to serialize result: {}", e)),
-        },
+/// Generate a payment link after checking the request's amount.
+///
+/// # Arguments
+/// * `request` - The payment to link
+/// * `pointer` - JSON pointer to the request in the tool's arguments
+///
+/// # Errors
+/// Returns a validation error at the request's `amount_cents` if it is not
+/// positive
+fn try_generate_link(
+    request: &PaymentLinkRequest,
+    pointer: &str,
+) -> Result<PaymentLinkResult, McpError> {
+    if request.amount_cents <= 0 {
+        return Err(McpError::validation(
+            format!("{}/amount_cents", pointer),
+            format!(
+                "amount_cents must be positive, got {}",
+                request.amount_cents
+            ),
+        ));
     }
+    Ok(generate_link_for_platform(request))
 }
 
 /// Generate a payment link for a specific platform
+///
+/// The amount must already be known to be positive.
 fn generate_link_for_platform(request: &PaymentLinkRequest) -> PaymentLinkResult {
     let amount_str = format_amount(request.amount_cents);
 
@@ -138,7 +300,9 @@ fn generate_link_for_platform(request: &PaymentLinkRequest) -> PaymentLinkResult
 
 /// Convert cents to dollars with two decimal places
 fn format_amount(cents: i64) -> String {
-    format!("{:.2}", cents as f64 / 100.0)
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    format!("{}{}.{:02}", sign, cents / 100, cents % 100)
 }
 
 /// Ensure Venmo username has @ prefix
@@ -167,8 +331,8 @@ mod tests {
     fn test_venmo_link_generation() {
         let request = create_request("venmo", "@mike_j", 15000, "Trip expenses");
         let response = handle(request);
-        assert!(response.success);
-        let result: PaymentLinkResult = serde_json::from_str(&response.result.unwrap()).unwrap();
+        let result: PaymentLinkResult =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert_eq!(
             result.link.unwrap(),
             "venmo://paycharge?txn=pay&recipients=@mike_j&amount=150.00&note=Trip%20expenses"
@@ -179,7 +343,8 @@ mod tests {
     fn test_venmo_handles_missing_at_sign() {
         let request = create_request("venmo", "mike_j", 15000, "Trip");
         let response = handle(request);
-        let result: PaymentLinkResult = serde_json::from_str(&response.result.unwrap()).unwrap();
+        let result: PaymentLinkResult =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert!(result.link.unwrap().contains("@mike_j"));
     }
 
@@ -187,7 +352,8 @@ mod tests {
     fn test_venmo_url_encodes_special_characters() {
         let request = create_request("venmo", "@mike_j", 15000, "Trip & dinner");
         let response = handle(request);
-        let result: PaymentLinkResult = serde_json::from_str(&response.result.unwrap()).unwrap();
+        let result: PaymentLinkResult =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert!(result.link.unwrap().contains("Trip%20%26%20dinner"));
     }
 
@@ -195,7 +361,8 @@ mod tests {
     fn test_paypal_link_generation() {
         let request = create_request("paypal", "mike@email.com", 15000, "Trip expenses");
         let response = handle(request);
-        let result: PaymentLinkResult = serde_json::from_str(&response.result.unwrap()).unwrap();
+        let result: PaymentLinkResult =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert_eq!(result.link.unwrap(), "https://paypal.me/mike@email.com/150.00");
     }
 
@@ -203,7 +370,8 @@ mod tests {
     fn test_paypal_memo_in_fallback_not_url() {
         let request = create_request("paypal", "mike@email.com", 15000, "Trip expenses");
         let response = handle(request);
-        let result: PaymentLinkResult = serde_json::from_str(&response.result.unwrap()).unwrap();
+        let result: PaymentLinkResult =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert!(!result.link.unwrap().contains("Trip"));
         assert!(result.fallback_text.contains("Trip expenses"));
     }
@@ -212,7 +380,8 @@ mod tests {
     fn test_cashapp_link_generation() {
         let request = create_request("cashapp", "$TomB", 8550, "Dinner");
         let response = handle(request);
-        let result: PaymentLinkResult = serde_json::from_str(&response.result.unwrap()).unwrap();
+        let result: PaymentLinkResult =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert_eq!(result.link.unwrap(), "https://cash.app/$TomB/85.50");
     }
 
@@ -220,7 +389,8 @@ mod tests {
     fn test_cashapp_adds_dollar_prefix_if_missing() {
         let request = create_request("cashapp", "TomB", 8550, "Dinner");
         let response = handle(request);
-        let result: PaymentLinkResult = serde_json::from_str(&response.result.unwrap()).unwrap();
+        let result: PaymentLinkResult =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert!(result.link.unwrap().contains("$TomB"));
     }
 
@@ -228,7 +398,8 @@ mod tests {
     fn test_zelle_returns_fallback_only() {
         let request = create_request("zelle", "555-123-4567", 6500, "Settlement");
         let response = handle(request);
-        let result: PaymentLinkResult = serde_json::from_str(&response.result.unwrap()).unwrap();
+        let result: PaymentLinkResult =
+            serde_json::from_str(&response.into_result().unwrap()).unwrap();
         assert!(result.link.is_none());
         assert_eq!(result.fallback_text, "Pay 555-123-4567 $65.00 via Zelle");
     }
@@ -237,47 +408,204 @@ mod tests {
     fn test_generate_payment_link_mcp_method() {
         let request = McpRequest {
             method: "generate_payment_link".to_string(),
-            params: r#"{"platform":"venmo","recipient":"@user","amount_cents":5000,"memo":"Test"}"#
-                .to_string(),
+            params: json!({
+                "platform": "venmo",
+                "recipient": "@user",
+                "amount_cents": 5000,
+                "memo": "Test"
+            }),
         };
         let response = handle(request);
-        assert!(response.success);
+        assert!(response.is_ok());
     }
 
     #[test]
     fn test_invalid_platform_returns_error() {
         let request = McpRequest {
             method: "generate_payment_link".to_string(),
-            params: r#"{"platform":"bitcoin","recipient":"addr","amount_cents":5000,"memo":"Test"}"#
-                .to_string(),
+            params: json!({
+                "platform": "bitcoin",
+                "recipient": "addr",
+                "amount_cents": 5000,
+                "memo": "Test"
+            }),
         };
-        let response = handle(request);
-        assert!(!response.success);
-        assert!(response.error.is_some());
+        let error = handle(request).into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(error.pointer.as_deref(), Some("/platform"));
+    }
+
+    #[test]
+    fn test_non_positive_amount_returns_validation_error() {
+        for amount_cents in [0, -500] {
+            let error = handle(create_request("venmo", "@user", amount_cents, "Test"))
+                .into_result()
+                .unwrap_err();
+            assert_eq!(
+                error,
+                McpError::validation(
+                    "/amount_cents",
+                    format!("amount_cents must be positive, got {}", amount_cents)
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_amount_is_exact() {
+        assert_eq!(format_amount(5), "0.05");
+        assert_eq!(format_amount(8550), "85.50");
+        assert_eq!(format_amount(i64::MAX), "92233720368547758.07");
+        assert_eq!(format_amount(-250), "-2.50");
     }
 
     #[test]
     fn test_unknown_method_returns_error() {
         let request = McpRequest {
             method: "unknown".to_string(),
-            params: "{}".to_string(),
+            params: json!({}),
         };
-        let response = handle(request);
-        assert!(!response.success);
-        assert!(response.error.unwrap().contains("Unknown method"));
+        let error = handle(request).into_result().unwrap_err();
+        assert_eq!(error.code, ErrorCode::UnknownMethod);
+        assert_eq!(error.message, "Unknown method: unknown");
     }
 
     // Helper function for creating test requests
     fn create_request(platform: &str, recipient: &str, amount_cents: i64, memo: &str) -> McpRequest {
         McpRequest {
             method: "generate_payment_link".to_string(),
-            params: serde_json::json!({
+            params: json!({
                 "platform": platform,
                 "recipient": recipient,
                 "amount_cents": amount_cents,
                 "memo": memo
-            })
-            .to_string(),
+            }),
         }
     }
+
+    fn jsonrpc(message: Value) -> Value {
+        serde_json::from_str(&handle_jsonrpc(&message.to_string()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_jsonrpc_tools_list() {
+        let response = jsonrpc(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}));
+        let tool = &response["result"]["tools"][0];
+        assert_eq!(tool["name"], "generate_payment_link");
+        assert_eq!(
+            tool["inputSchema"]["properties"]["platform"]["enum"],
+            json!(["venmo", "paypal", "cashapp", "zelle"])
+        );
+    }
+
+    #[test]
+    fn test_jsonrpc_tools_call_returns_link() {
+        let response = jsonrpc(json!({
+            "jsonrpc": "2.0",
+            "id": "link-1",
+            "method": "tools/call",
+            "params": {
+                "name": "generate_payment_link",
+                "arguments": {
+                    "platform": "cashapp",
+                    "recipient": "TomB",
+                    "amount_cents": 8550,
+                    "memo": "Dinner"
+                }
+            }
+        }));
+        assert_eq!(response["id"], "link-1");
+        assert_eq!(
+            response["result"]["structuredContent"]["link"],
+            "https://cash.app/$TomB/85.50"
+        );
+        let text: PaymentLinkResult =
+            serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap())
+                .unwrap();
+        assert_eq!(text.platform, Platform::CashApp);
+    }
+
+    #[test]
+    fn test_jsonrpc_invalid_platform_is_invalid_params() {
+        let response = jsonrpc(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "generate_payment_link",
+                "arguments": {
+                    "platform": "bitcoin",
+                    "recipient": "addr",
+                    "amount_cents": 1,
+                    "memo": ""
+                }
+            }
+        }));
+        assert_eq!(response["error"]["code"], -32602);
+    }
+
+    #[test]
+    fn test_jsonrpc_generate_payment_links_reports_each_request() {
+        let response = jsonrpc(json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "tools/call",
+            "params": {
+                "name": "generate_payment_links",
+                "arguments": {
+                    "requests": [
+                        {"platform": "venmo", "recipient": "amy", "amount_cents": 500, "memo": ""},
+                        {"platform": "bitcoin", "recipient": "x", "amount_cents": 1, "memo": ""},
+                        {"platform": "zelle", "recipient": "cal", "amount_cents": 250, "memo": ""},
+                        {"platform": "paypal", "recipient": "dee", "amount_cents": 0, "memo": ""}
+                    ]
+                }
+            }
+        }));
+        assert_eq!(response["result"]["isError"], false);
+        let results = &response["result"]["structuredContent"]["results"];
+        assert_eq!(results.as_array().unwrap().len(), 4);
+        assert_eq!(results[3]["err"]["code"], "validation");
+        assert_eq!(results[3]["err"]["pointer"], "/requests/3/amount_cents");
+        assert_eq!(results[0]["ok"]["platform"], "venmo");
+        assert_eq!(results[1]["err"]["code"], "invalid_params");
+        assert_eq!(results[1]["err"]["pointer"], "/requests/1/platform");
+        assert_eq!(results[2]["ok"]["link"], Value::Null);
+    }
+
+    #[test]
+    fn test_jsonrpc_settle_up_links_each_payment() {
+        let response = jsonrpc(json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "tools/call",
+            "params": {
+                "name": "settle_up",
+                "arguments": {
+                    "debts": [
+                        {"debtor": "A", "creditor": "B", "amount_cents": 3000, "expense_ids": []},
+                        {"debtor": "B", "creditor": "C", "amount_cents": 3000, "expense_ids": []}
+                    ],
+                    "profiles": [
+                        {"person": "A", "accounts": [{"platform": "cashapp", "handle": "$amy"}]},
+                        {"person": "C", "accounts": [
+                            {"platform": "venmo", "handle": "@cal"},
+                            {"platform": "cashapp", "handle": "cal"}
+                        ]}
+                    ]
+                }
+            }
+        }));
+        let payment = &response["result"]["structuredContent"]["payments"][0];
+        assert_eq!(payment["from"], "A");
+        assert_eq!(payment["to"], "C");
+        assert_eq!(payment["payment_link"]["link"], "https://cash.app/$cal/30.00");
+        assert_eq!(payment["fallback_text"], Value::Null);
+    }
+
+    #[test]
+    fn test_jsonrpc_unknown_method() {
+        let response = jsonrpc(json!({"jsonrpc": "2.0", "id": 3, "method": "roots/list"}));
+        assert_eq!(response["error"]["code"], -32601);
+    }
 }
@@ -0,0 +1,42 @@
+//! Payment Links MCP server.
+//!
+//! By default reads newline-delimited JSON-RPC messages from stdin and writes
+//! responses to stdout, logging to stderr. An MCP client can launch it with a
+//! config entry such as:
+//!
+//! ```json
+//! { "mcpServers": { "payment-links": { "command": "payment-links-mcp" } } }
+//! ```
+//!
+//! Built with the `http` feature, `payment-links-mcp --http 127.0.0.1:8080`
+//! serves the streamable HTTP transport at `/mcp` instead.
+
+use std::process::ExitCode;
+
+use payment_links_mcp::PaymentLinksServer;
+
+#[cfg(feature = "http")]
+const USAGE: &str = "usage: payment-links-mcp [--http <addr>]";
+#[cfg(not(feature = "http"))]
+const USAGE: &str = "usage: payment-links-mcp";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let served = match args.as_slice() {
+        [] => mcp_protocol::serve_stdio(&PaymentLinksServer),
+        #[cfg(feature = "http")]
+        [flag, addr] if flag == "--http" => mcp_protocol::serve_http(PaymentLinksServer, addr),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match served {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("payment-links-mcp: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
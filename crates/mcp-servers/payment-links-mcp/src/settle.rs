@@ -0,0 +1,266 @@
+//! The combined settle-up tool.
+//!
+//! Settling up usually means simplifying a group's debts and then sending
+//! everyone a link for each payment they make. This module does both in one
+//! step: it simplifies the debts with the expense optimizer and pairs each
+//! payment with a link on a platform the payer and recipient share.
+
+use expense_optimizer::{Debt, Payment, try_simplify_debts};
+use mcp_protocol::McpError;
+use serde::{Deserialize, Serialize};
+
+use crate::{PaymentLinkRequest, PaymentLinkResult, Platform, format_amount};
+
+/// A person's account on one payment platform.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlatformAccount {
+    /// The platform.
+    pub platform: Platform,
+    /// The person's handle, email, or phone number on the platform.
+    pub handle: String,
+}
+
+/// The payment platforms one person uses.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PaymentProfile {
+    /// The person.
+    pub person: String,
+    /// The person's accounts, most preferred first.
+    pub accounts: Vec<PlatformAccount>,
+}
+
+/// Parameters for the settle_up tool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SettleUpParams {
+    /// Every debt in the group.
+    pub debts: Vec<Debt>,
+    /// Payment profiles for the people in the group. People without a
+    /// profile get fallback text instead of links.
+    pub profiles: Vec<PaymentProfile>,
+}
+
+/// A simplified payment with the link to make it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettleUpPayment {
+    /// The payment to make.
+    #[serde(flatten)]
+    pub payment: Payment,
+    /// A link on the best platform the payer and recipient share, if any.
+    pub payment_link: Option<PaymentLinkResult>,
+    /// Instructions for paying when the payer and recipient share no
+    /// platform.
+    pub fallback_text: Option<String>,
+}
+
+/// Result of the settle_up tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettleUpResult {
+    /// Number of original debt records.
+    pub original_count: usize,
+    /// The payments that settle the group, each with its link.
+    pub payments: Vec<SettleUpPayment>,
+}
+
+/// Simplify `debts` and attach a payment link to every resulting payment.
+///
+/// # Arguments
+/// * `debts` - Every debt in the group
+/// * `profiles` - Payment profiles for the people in the group
+///
+/// # Returns
+/// The simplified payments, each with a link on the best shared platform
+/// or fallback text if there is none
+///
+/// # Errors
+/// Returns a validation error pointing at the offending debt or profile if
+/// the debts are invalid or a person has more than one profile
+pub fn settle_up(debts: &[Debt], profiles: &[PaymentProfile]) -> Result<SettleUpResult, McpError> {
+    for (index, profile) in profiles.iter().enumerate() {
+        if profiles[..index].iter().any(|p| p.person == profile.person) {
+            return Err(McpError::validation(
+                format!("/profiles/{}/person", index),
+                format!("duplicate payment profile for {}", profile.person),
+            ));
+        }
+    }
+
+    let plan = try_simplify_debts(debts)
+        .map_err(|e| McpError::validation(format!("/debts/{}/{}", e.index(), e.field()), &e))?;
+    let accounts = |person: &str| {
+        profiles
+            .iter()
+            .find(|profile| profile.person == person)
+            .map_or(&[][..], |profile| &profile.accounts[..])
+    };
+
+    let payments = plan
+        .payments
+        .into_iter()
+        .map(
+            |payment| match best_shared_account(accounts(&payment.from), accounts(&payment.to)) {
+                Some(account) => SettleUpPayment {
+                    payment_link: Some(crate::generate_link_for_platform(&PaymentLinkRequest {
+                        platform: account.platform.clone(),
+                        recipient: account.handle.clone(),
+                        amount_cents: payment.amount_cents,
+                        memo: payment.reason.clone(),
+                    })),
+                    fallback_text: None,
+                    payment,
+                },
+                None => SettleUpPayment {
+                    payment_link: None,
+                    fallback_text: Some(format!(
+                        "Pay {} ${}: {} (no shared payment platform)",
+                        payment.to,
+                        format_amount(payment.amount_cents),
+                        payment.reason
+                    )),
+                    payment,
+                },
+            },
+        )
+        .collect();
+
+    Ok(SettleUpResult {
+        original_count: plan.original_count,
+        payments,
+    })
+}
+
+/// Pick the recipient's account to pay on.
+///
+/// Only platforms the payer also uses count. Platforms with deep links beat
+/// Zelle, and otherwise the recipient's preference order decides.
+fn best_shared_account<'a>(
+    payer: &[PlatformAccount],
+    recipient: &'a [PlatformAccount],
+) -> Option<&'a PlatformAccount> {
+    let shared = recipient
+        .iter()
+        .filter(|account| payer.iter().any(|p| p.platform == account.platform));
+    shared
+        .clone()
+        .find(|account| account.platform != Platform::Zelle)
+        .or_else(|| shared.clone().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debt(debtor: &str, creditor: &str, amount_cents: i64) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor: creditor.to_string(),
+            amount_cents,
+            expense_ids: vec![],
+        }
+    }
+
+    fn profile(person: &str, accounts: &[(Platform, &str)]) -> PaymentProfile {
+        PaymentProfile {
+            person: person.to_string(),
+            accounts: accounts
+                .iter()
+                .map(|(platform, handle)| PlatformAccount {
+                    platform: platform.clone(),
+                    handle: handle.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_links_use_best_shared_platform() {
+        let debts = [debt("A", "B", 2000), debt("B", "C", 2000)];
+        let profiles = [
+            profile(
+                "A",
+                &[(Platform::Zelle, "a@example.com"), (Platform::Venmo, "@a")],
+            ),
+            profile(
+                "C",
+                &[(Platform::Zelle, "555-0100"), (Platform::Venmo, "carol")],
+            ),
+        ];
+
+        let result = settle_up(&debts, &profiles).unwrap();
+
+        assert_eq!(result.original_count, 2);
+        assert_eq!(result.payments.len(), 1);
+        let payment = &result.payments[0];
+        assert_eq!(
+            (payment.payment.from.as_str(), payment.payment.to.as_str()),
+            ("A", "C")
+        );
+        // Both use Zelle too, but Venmo has a deep link
+        let link = payment.payment_link.as_ref().unwrap();
+        assert_eq!(link.platform, Platform::Venmo);
+        assert!(
+            link.link
+                .as_ref()
+                .unwrap()
+                .contains("recipients=@carol&amount=20.00")
+        );
+        assert!(payment.fallback_text.is_none());
+    }
+
+    #[test]
+    fn test_zelle_only_and_unshared_platforms() {
+        let debts = [debt("A", "B", 1500), debt("C", "D", 700)];
+        let profiles = [
+            profile("A", &[(Platform::Zelle, "a@example.com")]),
+            profile(
+                "B",
+                &[(Platform::PayPal, "bob"), (Platform::Zelle, "555-0101")],
+            ),
+            profile("C", &[(Platform::CashApp, "$cat")]),
+            profile("D", &[(Platform::Venmo, "@dan")]),
+        ];
+
+        let result = settle_up(&debts, &profiles).unwrap();
+
+        let to_b = result
+            .payments
+            .iter()
+            .find(|p| p.payment.to == "B")
+            .unwrap();
+        let link = to_b.payment_link.as_ref().unwrap();
+        assert_eq!(link.platform, Platform::Zelle);
+        assert_eq!(link.fallback_text, "Pay 555-0101 $15.00 via Zelle");
+
+        let to_d = result
+            .payments
+            .iter()
+            .find(|p| p.payment.to == "D")
+            .unwrap();
+        assert!(to_d.payment_link.is_none());
+        let fallback = to_d.fallback_text.as_deref().unwrap();
+        assert!(fallback.starts_with("Pay D $7.00: "), "{}", fallback);
+        assert!(
+            fallback.ends_with("(no shared payment platform)"),
+            "{}",
+            fallback
+        );
+    }
+
+    #[test]
+    fn test_invalid_input_is_rejected() {
+        let error = settle_up(&[debt("A", "B", -5)], &[]).unwrap_err();
+        assert_eq!(
+            error,
+            McpError::validation(
+                "/debts/0/amount_cents",
+                "debt 0: amount_cents must be positive, got -5"
+            )
+        );
+
+        let profiles = [profile("A", &[]), profile("A", &[])];
+        let error = settle_up(&[], &profiles).unwrap_err();
+        assert_eq!(
+            error,
+            McpError::validation("/profiles/1/person", "duplicate payment profile for A")
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Runs the crate as a WebAssembly component in a local wasmtime host.
+//!
+//! The crate is built for `wasm32-unknown-unknown` once per test run,
+//! encoded as a component, and instantiated against the
+//! `payment-links-mcp` world with no imports. The build needs that target
+//! installed, so these tests are ignored by default; CI runs them with
+//! `cargo test -- --ignored`.
+
+use std::sync::LazyLock;
+
+use mcp_test_support::build_component;
+use serde_json::{Value, json};
+use wasmtime::component::{Component, Linker, bindgen};
+use wasmtime::{Engine, Store};
+
+bindgen!({
+    world: "steamboat:payment-links/payment-links-mcp",
+    path: ["../wit/mcp-server.wit", "../wit/payment-links.wit"],
+});
+
+/// The compiled component, shared by every test.
+static COMPONENT: LazyLock<(Engine, Component)> = LazyLock::new(|| {
+    let bytes = build_component(
+        env!("CARGO_MANIFEST_DIR"),
+        env!("CARGO_TARGET_TMPDIR"),
+        "payment_links_mcp",
+    );
+    let engine = Engine::default();
+    let component = Component::new(&engine, bytes).unwrap();
+    (engine, component)
+});
+
+fn instantiate() -> (Store<()>, PaymentLinksMcp) {
+    let (engine, component) = &*COMPONENT;
+    let mut store = Store::new(engine, ());
+    let instance = PaymentLinksMcp::instantiate(&mut store, component, &Linker::new(engine))
+        .expect("the component should need no imports");
+    (store, instance)
+}
+
+#[test]
+#[ignore = "builds the crate for wasm32-unknown-unknown; run with --ignored"]
+fn test_legacy_handle() {
+    let (mut store, instance) = instantiate();
+    let handler = instance.steamboat_mcp_server_handler();
+
+    let request = McpRequest {
+        method: "generate_payment_link".to_string(),
+        params: json!({
+            "platform": "venmo",
+            "recipient": "@jake",
+            "amount_cents": 4500,
+            "memo": "Dinner"
+        })
+        .to_string(),
+    };
+    let McpResponse::Ok(output) = handler.call_handle(&mut store, &request).unwrap() else {
+        panic!("generate_payment_link should succeed");
+    };
+    let result: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(result["platform"], "venmo");
+    assert!(
+        result["link"]
+            .as_str()
+            .unwrap()
+            .starts_with("venmo://paycharge")
+    );
+
+    let request = McpRequest {
+        method: "generate_payment_link".to_string(),
+        params: json!({"platform": "bitcoin"}).to_string(),
+    };
+    let McpResponse::Err(error) = handler.call_handle(&mut store, &request).unwrap() else {
+        panic!("an unknown platform should be rejected");
+    };
+    assert!(matches!(error.code, ErrorCode::InvalidParams));
+    assert_eq!(error.pointer.as_deref(), Some("/platform"));
+}
+
+#[test]
+#[ignore = "builds the crate for wasm32-unknown-unknown; run with --ignored"]
+fn test_jsonrpc_messages() {
+    let (mut store, instance) = instantiate();
+    let handler = instance.steamboat_mcp_server_handler();
+    let mut call = |message: Value| {
+        handler
+            .call_handle_jsonrpc(&mut store, &message.to_string())
+            .unwrap()
+            .map(|response| serde_json::from_str::<Value>(&response).unwrap())
+    };
+
+    let response = call(json!({"jsonrpc": "2.0", "id": "a", "method": "tools/list"})).unwrap();
+    assert_eq!(response["id"], "a");
+    assert_eq!(
+        response["result"]["tools"][0]["name"],
+        "generate_payment_link"
+    );
+
+    let response = call(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "generate_payment_link",
+            "arguments": {
+                "platform": "zelle",
+                "recipient": "555-123-4567",
+                "amount_cents": 6500,
+                "memo": "Settlement"
+            }
+        }
+    }))
+    .unwrap();
+    assert_eq!(
+        response["result"]["structuredContent"]["fallback_text"],
+        "Pay 555-123-4567 $65.00 via Zelle"
+    );
+
+    let response = call(json!({"jsonrpc": "2.0", "id": 3, "method": "roots/list"})).unwrap();
+    assert_eq!(response["error"]["code"], -32601);
+}
@@ -0,0 +1,34 @@
+//! Exercises the streamable HTTP transport over a loopback listener.
+
+use mcp_protocol::HttpTransport;
+use mcp_test_support::{HttpSession, start_http};
+use payment_links_mcp::PaymentLinksServer;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_generate_link_over_http() {
+    let url = start_http(HttpTransport::new(PaymentLinksServer)).await;
+    let (session, body) = HttpSession::initialize(&url, "2025-03-26").await;
+    assert_eq!(body["result"]["protocolVersion"], "2025-03-26");
+
+    let body = session
+        .request(&json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "generate_payment_link",
+                "arguments": {
+                    "platform": "zelle",
+                    "recipient": "555-123-4567",
+                    "amount_cents": 6500,
+                    "memo": "Settlement"
+                }
+            }
+        }))
+        .await;
+    assert_eq!(
+        body["result"]["structuredContent"]["fallback_text"],
+        "Pay 555-123-4567 $65.00 via Zelle"
+    );
+}
@@ -0,0 +1,39 @@
+//! Runs the stdio binary the way an MCP client would.
+
+use mcp_test_support::run_stdio;
+use serde_json::json;
+
+#[test]
+fn test_stdio_session() {
+    let run = run_stdio(
+        env!("CARGO_BIN_EXE_payment-links-mcp"),
+        &[],
+        &[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {
+                    "name": "generate_payment_link",
+                    "arguments": {
+                        "platform": "paypal",
+                        "recipient": "mike",
+                        "amount_cents": 1250,
+                        "memo": "Lunch"
+                    }
+                }
+            }),
+        ],
+    );
+
+    assert_eq!(run.responses.len(), 2);
+    assert_eq!(
+        run.responses[0]["result"]["tools"][0]["name"],
+        "generate_payment_link"
+    );
+    assert_eq!(
+        run.responses[1]["result"]["structuredContent"]["link"],
+        "https://paypal.me/mike/12.50"
+    );
+}
@@ -164,22 +164,22 @@ pub fn extract_exif(image_data: &[u8]) -> Result<ExifData, ExifError> {
     data.gps_longitude = lon;
 
     // Extract orientation
-    if let Some(field) = exif.get_field(Tag::Orientation, In::PRIMARY) {
-        if let Some(val) = field.value.get_uint(0) {
-            data.orientation = Some(val);
-        }
+    if let Some(field) = exif.get_field(Tag::Orientation, In::PRIMARY)
+        && let Some(val) = field.value.get_uint(0)
+    {
+        data.orientation = Some(val);
     }
 
     // Extract dimensions (PixelXDimension, PixelYDimension from EXIF)
-    if let Some(field) = exif.get_field(Tag::PixelXDimension, In::PRIMARY) {
-        if let Some(val) = field.value.get_uint(0) {
-            data.width = Some(val);
-        }
+    if let Some(field) = exif.get_field(Tag::PixelXDimension, In::PRIMARY)
+        && let Some(val) = field.value.get_uint(0)
+    {
+        data.width = Some(val);
     }
-    if let Some(field) = exif.get_field(Tag::PixelYDimension, In::PRIMARY) {
-        if let Some(val) = field.value.get_uint(0) {
-            data.height = Some(val);
-        }
+    if let Some(field) = exif.get_field(Tag::PixelYDimension, In::PRIMARY)
+        && let Some(val) = field.value.get_uint(0)
+    {
+        data.height = Some(val);
     }
 
     Ok(data)
@@ -203,22 +203,22 @@ fn extract_gps_component(exif: &exif::Exif, coord_tag: Tag, ref_tag: Tag) -> Opt
     let ref_field = exif.get_field(ref_tag, In::PRIMARY)?;
 
     // GPS coordinates are stored as three Rational values: degrees, minutes, seconds
-    if let Value::Rational(ref rationals) = coord_field.value {
-        if rationals.len() >= 3 {
-            let degrees = rationals[0].to_f64();
-            let minutes = rationals[1].to_f64();
-            let seconds = rationals[2].to_f64();
-
-            // Get direction character (N/S/E/W) from the reference field
-            let direction = ref_field
-                .display_value()
-                .to_string()
-                .chars()
-                .next()
-                .unwrap_or('N');
-
-            return Some(dms_to_decimal(degrees, minutes, seconds, direction));
-        }
+    if let Value::Rational(ref rationals) = coord_field.value
+        && rationals.len() >= 3
+    {
+        let degrees = rationals[0].to_f64();
+        let minutes = rationals[1].to_f64();
+        let seconds = rationals[2].to_f64();
+
+        // Get direction character (N/S/E/W) from the reference field
+        let direction = ref_field
+            .display_value()
+            .to_string()
+            .chars()
+            .next()
+            .unwrap_or('N');
+
+        return Some(dms_to_decimal(degrees, minutes, seconds, direction));
     }
 
     None
@@ -198,6 +198,113 @@ pub fn hamming_distance(hash1: &str, hash2: &str) -> Result<u32, HashError> {
     Ok(xor.count_ones())
 }
 
+/// A set of images that are copies or near-copies of each other.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    /// IDs of the images in the group, in input order.
+    pub ids: Vec<String>,
+    /// Whether every image in the group has the same SHA-256 hash.
+    pub exact: bool,
+    /// Largest perceptual hash distance between any two images in the group.
+    pub max_distance: u32,
+}
+
+/// Groups images that are duplicates of each other.
+///
+/// Two images match if their SHA-256 hashes are equal or their perceptual
+/// hashes are within `max_distance` bits. Matches are transitive, so a group
+/// can contain two images further apart than `max_distance` if a third image
+/// is close to both; `max_distance` on the group reports the widest pair.
+///
+/// # Arguments
+///
+/// * `images` - Image IDs with their hashes, as from [`compute_hashes`].
+/// * `max_distance` - Largest Hamming distance counted as a match (0-64).
+///
+/// # Returns
+///
+/// Groups of two or more images, ordered by their first image, or an error
+/// if a perceptual hash is invalid. Images without a match are left out.
+///
+/// # Examples
+///
+/// ```
+/// use media_processor::hash::{find_duplicates, HashResult};
+///
+/// let hashes = |perceptual: &str| HashResult {
+///     sha256: perceptual.repeat(4),
+///     perceptual: perceptual.to_string(),
+/// };
+/// let images = vec![
+///     ("a".to_string(), hashes("ff00ff00ff00ff00")),
+///     ("b".to_string(), hashes("0f0f0f0f0f0f0f0f")),
+///     ("c".to_string(), hashes("ff00ff00ff00ff01")),
+/// ];
+///
+/// let groups = find_duplicates(&images, 4).unwrap();
+/// assert_eq!(groups.len(), 1);
+/// assert_eq!(groups[0].ids, vec!["a", "c"]);
+/// ```
+pub fn find_duplicates(
+    images: &[(String, HashResult)],
+    max_distance: u32,
+) -> Result<Vec<DuplicateGroup>, HashError> {
+    let n = images.len();
+    let mut distances = vec![0; n * n];
+    // Union-find over image indices
+    let mut parent: Vec<usize> = (0..n).collect();
+
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let (a, b) = (&images[i].1, &images[j].1);
+            let distance = hamming_distance(&a.perceptual, &b.perceptual)?;
+            distances[i * n + j] = distance;
+            distances[j * n + i] = distance;
+
+            if a.sha256 == b.sha256 || distance <= max_distance {
+                let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                parent[ri.max(rj)] = ri.min(rj);
+            }
+        }
+    }
+
+    // Every root is its group's smallest index, so groups come out in order
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for i in 0..n {
+        let r = root(&mut parent, i);
+        members[r].push(i);
+    }
+
+    Ok(members
+        .into_iter()
+        .filter(|group| group.len() > 1)
+        .map(|group| {
+            let first = &images[group[0]].1.sha256;
+            let exact = group.iter().all(|&i| &images[i].1.sha256 == first);
+            let max_distance = group
+                .iter()
+                .flat_map(|&i| group.iter().map(move |&j| (i, j)))
+                .map(|(i, j)| distances[i * n + j])
+                .max()
+                .unwrap_or(0);
+
+            DuplicateGroup {
+                ids: group.iter().map(|&i| images[i].0.clone()).collect(),
+                exact,
+                max_distance,
+            }
+        })
+        .collect())
+}
+
 // Helper module for hex encoding (inline to avoid external dependency)
 mod hex {
     const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
@@ -353,4 +460,68 @@ mod tests {
         let result = compute_hashes(invalid_data);
         assert!(result.is_err(), "Should fail for invalid image data");
     }
+
+    fn hashed(id: &str, sha256: &str, perceptual: &str) -> (String, HashResult) {
+        (
+            id.to_string(),
+            HashResult {
+                sha256: sha256.to_string(),
+                perceptual: perceptual.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_find_duplicates_exact_and_near() {
+        let images = vec![
+            hashed("a", "aa", "ff00000000000000"),
+            hashed("b", "bb", "00000000000000ff"),
+            hashed("c", "aa", "ff00000000000000"),
+            hashed("d", "dd", "0000000000000fff"),
+            hashed("e", "ee", "f0f0f0f0f0f0f0f0"),
+        ];
+
+        let groups = find_duplicates(&images, 4).unwrap();
+
+        assert_eq!(
+            groups,
+            vec![
+                DuplicateGroup {
+                    ids: vec!["a".to_string(), "c".to_string()],
+                    exact: true,
+                    max_distance: 0,
+                },
+                DuplicateGroup {
+                    ids: vec!["b".to_string(), "d".to_string()],
+                    exact: false,
+                    max_distance: 4,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_duplicates_is_transitive() {
+        // a-b and b-c are within 2 bits, but a-c are 4 apart
+        let images = vec![
+            hashed("a", "1", "0000000000000000"),
+            hashed("b", "2", "0000000000000003"),
+            hashed("c", "3", "000000000000000f"),
+        ];
+
+        let groups = find_duplicates(&images, 2).unwrap();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].ids.len(), 3);
+        assert_eq!(groups[0].max_distance, 4);
+    }
+
+    #[test]
+    fn test_find_duplicates_rejects_invalid_hash() {
+        let images = vec![
+            hashed("a", "1", "0000000000000000"),
+            hashed("b", "2", "xyz"),
+        ];
+        assert!(find_duplicates(&images, 2).is_err());
+    }
 }
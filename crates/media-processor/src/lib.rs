@@ -8,6 +8,9 @@
 //! - **Thumbnail generation**: Generate thumbnails at standard sizes
 //! - **Content hashing**: SHA-256 and perceptual hashing for deduplication
 
+// The unit tests spell out their range checks and keep a placeholder assertion
+#![cfg_attr(test, allow(clippy::manual_range_contains, clippy::assertions_on_constants))]
+
 pub mod exif;
 pub mod hash;
 pub mod resize;
@@ -31,7 +34,10 @@ pub use thumbnail::{
 };
 
 // Re-export main types and functions from hash module
-pub use hash::{compute_hashes, hamming_distance, perceptual_hash, sha256_hash, HashError, HashResult};
+pub use hash::{
+    compute_hashes, find_duplicates, hamming_distance, perceptual_hash, sha256_hash, DuplicateGroup,
+    HashError, HashResult,
+};
 
 // Re-export WASM init function when wasm feature is enabled
 #[cfg(feature = "wasm")]
@@ -2,8 +2,8 @@
 //!
 //! Tests the end-to-end flow: expenses -> splits -> balances -> debts -> simplification
 
-use expense_optimizer::{Debt, SimplificationResult, simplify_debts};
-use finance_core::{BalanceSummary, Cents, Expense, calculate_all_balances_impl, split_expense_impl};
+use expense_optimizer::{SimplificationResult, calculate_net_balances, settle_expenses};
+use finance_core::{Cents, Expense, Settlement, calculate_all_balances_impl, split_expense_impl};
 use std::time::Instant;
 
 // ============================================================================
@@ -29,64 +29,6 @@ fn make_expense(
     }
 }
 
-/// Convert balances to debts for simplification.
-///
-/// For each person with a negative balance (debtor), we create debts to people
-/// with positive balances (creditors). This uses a greedy approach to create
-/// pairwise debts that sum to the correct amounts.
-fn balances_to_debts(balances: &[BalanceSummary]) -> Vec<Debt> {
-    let mut debts = Vec::new();
-
-    // Separate creditors (positive balance) and debtors (negative balance)
-    let mut creditors: Vec<(&str, Cents)> = balances
-        .iter()
-        .filter(|b| b.net_balance_cents > 0)
-        .map(|b| (b.attendee_id.as_str(), b.net_balance_cents))
-        .collect();
-
-    let mut debtors: Vec<(&str, Cents)> = balances
-        .iter()
-        .filter(|b| b.net_balance_cents < 0)
-        .map(|b| (b.attendee_id.as_str(), -b.net_balance_cents)) // Store as positive
-        .collect();
-
-    // Sort for deterministic matching
-    creditors.sort_by(|a, b| b.1.cmp(&a.1));
-    debtors.sort_by(|a, b| b.1.cmp(&a.1));
-
-    // Create pairwise debts
-    let mut creditor_idx = 0;
-    let mut debtor_idx = 0;
-
-    while creditor_idx < creditors.len() && debtor_idx < debtors.len() {
-        let (creditor_name, ref mut creditor_amount) = creditors[creditor_idx];
-        let (debtor_name, ref mut debtor_amount) = debtors[debtor_idx];
-
-        let transfer_amount = (*creditor_amount).min(*debtor_amount);
-
-        if transfer_amount > 0 {
-            debts.push(Debt {
-                debtor: debtor_name.to_string(),
-                creditor: creditor_name.to_string(),
-                amount_cents: transfer_amount,
-                expense_ids: vec![], // Not tracking specific expenses in this conversion
-            });
-
-            *creditor_amount -= transfer_amount;
-            *debtor_amount -= transfer_amount;
-        }
-
-        if *creditor_amount == 0 {
-            creditor_idx += 1;
-        }
-        if *debtor_amount == 0 {
-            debtor_idx += 1;
-        }
-    }
-
-    debts
-}
-
 /// Verify that all payments in a simplification result are valid
 fn verify_payments(result: &SimplificationResult) -> bool {
     result.payments.iter().all(|p| {
@@ -136,11 +78,8 @@ fn test_end_to_end_flow() {
     let balances = calculate_all_balances_impl(&expenses);
     assert_eq!(balances.len(), 3, "Should have 3 participants");
 
-    // Step 3: Convert balances to debts
-    let debts = balances_to_debts(&balances);
-
-    // Step 4: Simplify debts
-    let result = simplify_debts(&debts);
+    // Step 3: Turn each share into a debt and simplify them
+    let result = settle_expenses(&expenses, &[]).result;
 
     // Verify optimization occurred
     assert!(
@@ -371,11 +310,8 @@ fn test_realistic_trip_scenario() {
     let balance_sum: Cents = balances.iter().map(|b| b.net_balance_cents).sum();
     assert_eq!(balance_sum, 0, "Balances must sum to zero");
 
-    // Convert to debts
-    let debts = balances_to_debts(&balances);
-
-    // Simplify debts
-    let result = simplify_debts(&debts);
+    // Convert to debts and simplify them
+    let result = settle_expenses(&expenses, &[]).result;
 
     // Assert: payments <= 14 (at most n-1 payments for n people)
     assert!(
@@ -407,7 +343,10 @@ fn test_realistic_trip_scenario() {
     println!("  Participants: {}", attendees.len());
     println!("  Original debt records: {}", result.original_count);
     println!("  Optimized payments: {}", result.optimized_count);
-    println!("  Savings: {:.1}%", result.savings_percent);
+    println!(
+        "  Savings: {} transactions ({} bps)",
+        result.metrics.transactions_saved, result.metrics.savings_bps
+    );
     println!("  Execution time: {:?}", duration);
 }
 
@@ -490,8 +429,7 @@ fn test_debt_simplification_amount_preservation() {
     ];
 
     let balances = calculate_all_balances_impl(&expenses);
-    let debts = balances_to_debts(&balances);
-    let result = simplify_debts(&debts);
+    let result = settle_expenses(&expenses, &[]).result;
 
     // Calculate total positive balance (what creditors are owed)
     let total_owed_to_creditors: Cents = balances
@@ -538,8 +476,7 @@ fn test_many_small_transactions() {
     let sum: Cents = balances.iter().map(|b| b.net_balance_cents).sum();
     assert_eq!(sum, 0, "Balance conservation must hold with many transactions");
 
-    let debts = balances_to_debts(&balances);
-    let result = simplify_debts(&debts);
+    let result = settle_expenses(&expenses, &[]).result;
 
     // With 10 people, we should have at most 9 payments
     assert!(
@@ -547,3 +484,63 @@ fn test_many_small_transactions() {
         "Should have at most 9 payments for 10 people"
     );
 }
+
+/// Test that the expense pipeline agrees with finance-core balances
+#[test]
+fn test_settle_expenses_matches_finance_core_balances() {
+    let expenses = vec![
+        make_expense(
+            "exp1",
+            "alice",
+            15000, // $150.00
+            vec!["alice", "bob", "charlie", "diana"],
+            "food",
+            "Fancy dinner",
+        ),
+        make_expense(
+            "exp2",
+            "bob",
+            8000, // $80.00
+            vec!["bob", "charlie"],
+            "entertainment",
+            "Movie tickets",
+        ),
+        make_expense(
+            "exp3",
+            "charlie",
+            12345, // $123.45
+            vec!["alice", "bob", "charlie", "diana"],
+            "activities",
+            "Group activity",
+        ),
+    ];
+
+    let settlement = settle_expenses(&expenses, &[]);
+    let balances = calculate_all_balances_impl(&expenses);
+
+    // The pairwise debts carry exactly the finance-core net balances
+    let debt_balances = calculate_net_balances(&settlement.debts);
+    for balance in &balances {
+        let from_debts = debt_balances.get(&balance.attendee_id).copied().unwrap_or(0);
+        assert_eq!(
+            from_debts, balance.net_balance_cents,
+            "Net balance mismatch for {}",
+            balance.attendee_id
+        );
+    }
+
+    // Every debt names the expenses behind it
+    assert!(settlement.debts.iter().all(|d| !d.expense_ids.is_empty()));
+    assert!(verify_payments(&settlement.result), "All payments should be valid");
+
+    // A recorded settlement is deducted before simplifying
+    let paid = Settlement {
+        from_id: "diana".to_string(),
+        to_id: "alice".to_string(),
+        amount_cents: 1000,
+    };
+    let after_payment = settle_expenses(&expenses, &[paid]);
+    let total_before: Cents = settlement.result.payments.iter().map(|p| p.amount_cents).sum();
+    let total_after: Cents = after_payment.result.payments.iter().map(|p| p.amount_cents).sum();
+    assert_eq!(total_after, total_before - 1000);
+}
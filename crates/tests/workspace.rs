@@ -0,0 +1,79 @@
+//! Consistency checks between the root and MCP server workspaces
+//!
+//! `mcp-servers` is its own workspace so the servers build on their own, but
+//! its members also belong to the root workspace, which then resolves their
+//! `workspace = true` keys from its own tables. Both manifests must agree on
+//! every key the servers' workspace declares.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The `crates` directory holding the root workspace manifest
+fn crates_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .canonicalize()
+        .expect("crates directory should exist")
+}
+
+/// Read the `name = value` lines of one table in a workspace manifest
+///
+/// Path dependencies are resolved against the manifest's directory, so the
+/// same crate compares equal from either workspace.
+fn manifest_table(dir: &Path, table: &str) -> BTreeMap<String, String> {
+    let manifest = std::fs::read_to_string(dir.join("Cargo.toml"))
+        .expect("workspace manifest should be readable");
+    let header = format!("[{}]", table);
+
+    let mut entries = BTreeMap::new();
+    let mut in_table = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_table = line == header;
+            continue;
+        }
+        if !in_table || line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .expect("table lines should be `name = value`");
+        entries.insert(key.trim().to_string(), resolve_path(dir, value.trim()));
+    }
+    entries
+}
+
+/// Replace a relative `path = "..."` in a dependency with its absolute form
+fn resolve_path(dir: &Path, value: &str) -> String {
+    let Some((before, rest)) = value.split_once("path = \"") else {
+        return value.to_string();
+    };
+    let (path, after) = rest.split_once('"').expect("path should be quoted");
+    let resolved = dir
+        .join(path)
+        .canonicalize()
+        .expect("path dependency should exist");
+    format!("{}path = \"{}\"{}", before, resolved.display(), after)
+}
+
+#[test]
+fn test_mcp_servers_workspace_agrees_with_root() {
+    let root = crates_dir();
+    let servers = root.join("mcp-servers");
+
+    for table in ["workspace.package", "workspace.dependencies"] {
+        let root_entries = manifest_table(&root, table);
+        let server_entries = manifest_table(&servers, table);
+        assert!(!server_entries.is_empty(), "[{}] should not be empty", table);
+
+        for (key, value) in &server_entries {
+            assert_eq!(
+                root_entries.get(key),
+                Some(value),
+                "[{}] {} differs between Cargo.toml and mcp-servers/Cargo.toml",
+                table,
+                key
+            );
+        }
+    }
+}